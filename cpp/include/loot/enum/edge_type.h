@@ -38,10 +38,14 @@ enum struct EdgeType : unsigned int {
   masterFlag,
   master,
   masterlistRequirement,
+  layerRequirement,
   userRequirement,
   masterlistLoadAfter,
+  layerLoadAfter,
   userLoadAfter,
+  pin,
   masterlistGroup,
+  layerGroup,
   userGroup,
   recordOverlap,
   assetOverlap,
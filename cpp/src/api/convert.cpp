@@ -13,14 +13,22 @@ std::optional<loot::EdgeType> convert(loot::rust::EdgeType edgeType) {
       return loot::EdgeType::master;
     case loot::rust::EdgeType::MasterlistRequirement:
       return loot::EdgeType::masterlistRequirement;
+    case loot::rust::EdgeType::LayerRequirement:
+      return loot::EdgeType::layerRequirement;
     case loot::rust::EdgeType::UserRequirement:
       return loot::EdgeType::userRequirement;
     case loot::rust::EdgeType::MasterlistLoadAfter:
       return loot::EdgeType::masterlistLoadAfter;
+    case loot::rust::EdgeType::LayerLoadAfter:
+      return loot::EdgeType::layerLoadAfter;
     case loot::rust::EdgeType::UserLoadAfter:
       return loot::EdgeType::userLoadAfter;
+    case loot::rust::EdgeType::Pin:
+      return loot::EdgeType::pin;
     case loot::rust::EdgeType::MasterlistGroup:
       return loot::EdgeType::masterlistGroup;
+    case loot::rust::EdgeType::LayerGroup:
+      return loot::EdgeType::layerGroup;
     case loot::rust::EdgeType::UserGroup:
       return loot::EdgeType::userGroup;
     case loot::rust::EdgeType::RecordOverlap:
@@ -36,14 +36,22 @@ std::string describeEdgeType(EdgeType edgeType) {
       return "Master";
     case EdgeType::masterlistRequirement:
       return "Masterlist Requirement";
+    case EdgeType::layerRequirement:
+      return "Layer Requirement";
     case EdgeType::userRequirement:
       return "User Requirement";
     case EdgeType::masterlistLoadAfter:
       return "Masterlist Load After";
+    case EdgeType::layerLoadAfter:
+      return "Layer Load After";
     case EdgeType::userLoadAfter:
       return "User Load After";
+    case EdgeType::pin:
+      return "Pin";
     case EdgeType::masterlistGroup:
       return "Masterlist Group";
+    case EdgeType::layerGroup:
+      return "Layer Group";
     case EdgeType::userGroup:
       return "User Group";
     case EdgeType::recordOverlap:
@@ -31,6 +31,21 @@ bool startsWith(std::string_view str, std::string_view prefix) {
   return str.substr(0, prefix.size()) == prefix;
 }
 
+bool endsWith(std::string_view str, std::string_view suffix) {
+  if (str.size() < suffix.size()) {
+    return false;
+  }
+
+  return str.substr(str.size() - suffix.size()) == suffix;
+}
+
+// Layer edge types are displayed as "Layer <name> <type>".
+bool isLayerEdgeType(std::string_view edgeTypeDisplay,
+                     std::string_view typeSuffix) {
+  return startsWith(edgeTypeDisplay, "Layer ") &&
+         endsWith(edgeTypeDisplay, typeSuffix);
+}
+
 std::string replace(std::string_view str,
                     std::string_view from,
                     std::string_view to) {
@@ -60,14 +75,22 @@ EdgeType toEdgeType(std::string_view edgeTypeDisplay) {
     return EdgeType::master;
   } else if (edgeTypeDisplay == "Masterlist Requirement") {
     return EdgeType::masterlistRequirement;
+  } else if (isLayerEdgeType(edgeTypeDisplay, " Requirement")) {
+    return EdgeType::layerRequirement;
   } else if (edgeTypeDisplay == "User Requirement") {
     return EdgeType::userRequirement;
   } else if (edgeTypeDisplay == "Masterlist Load After") {
     return EdgeType::masterlistLoadAfter;
+  } else if (isLayerEdgeType(edgeTypeDisplay, " Load After")) {
+    return EdgeType::layerLoadAfter;
   } else if (edgeTypeDisplay == "User Load After") {
     return EdgeType::userLoadAfter;
+  } else if (edgeTypeDisplay == "Pin") {
+    return EdgeType::pin;
   } else if (edgeTypeDisplay == "Masterlist Group") {
     return EdgeType::masterlistGroup;
+  } else if (isLayerEdgeType(edgeTypeDisplay, " Group")) {
+    return EdgeType::layerGroup;
   } else if (edgeTypeDisplay == "User Group") {
     return EdgeType::userGroup;
   } else if (edgeTypeDisplay == "Record Overlap") {
@@ -99,8 +122,10 @@ std::vector<Vertex> parseCyclicError(std::string_view what) {
       const auto secondSepPos = suffix.find("--", sepPos + 2);
       const auto escapedEdgeName =
           suffix.substr(sepPos + 2, secondSepPos - (sepPos + 2));
+      const auto edgeName =
+          replace(replace(escapedEdgeName, "\\-", "-"), "\\\\", "\\");
 
-      vertices.push_back(Vertex(name, toEdgeType(escapedEdgeName)));
+      vertices.push_back(Vertex(name, toEdgeType(edgeName)));
 
       pos = secondSepPos + 2;
     } else {
@@ -265,10 +265,14 @@ impl TryFrom<libloot::EdgeType> for EdgeType {
             libloot::EdgeType::MasterFlag => Ok(EdgeType::MasterFlag),
             libloot::EdgeType::Master => Ok(EdgeType::Master),
             libloot::EdgeType::MasterlistRequirement => Ok(EdgeType::MasterlistRequirement),
+            libloot::EdgeType::LayerRequirement(_) => Ok(EdgeType::LayerRequirement),
             libloot::EdgeType::UserRequirement => Ok(EdgeType::UserRequirement),
             libloot::EdgeType::MasterlistLoadAfter => Ok(EdgeType::MasterlistLoadAfter),
+            libloot::EdgeType::LayerLoadAfter(_) => Ok(EdgeType::LayerLoadAfter),
             libloot::EdgeType::UserLoadAfter => Ok(EdgeType::UserLoadAfter),
+            libloot::EdgeType::Pin => Ok(EdgeType::Pin),
             libloot::EdgeType::MasterlistGroup => Ok(EdgeType::MasterlistGroup),
+            libloot::EdgeType::LayerGroup(_) => Ok(EdgeType::LayerGroup),
             libloot::EdgeType::UserGroup => Ok(EdgeType::UserGroup),
             libloot::EdgeType::RecordOverlap => Ok(EdgeType::RecordOverlap),
             libloot::EdgeType::AssetOverlap => Ok(EdgeType::AssetOverlap),
@@ -291,6 +295,7 @@ impl TryFrom<EdgeType> for libloot::EdgeType {
             EdgeType::UserRequirement => Ok(libloot::EdgeType::UserRequirement),
             EdgeType::MasterlistLoadAfter => Ok(libloot::EdgeType::MasterlistLoadAfter),
             EdgeType::UserLoadAfter => Ok(libloot::EdgeType::UserLoadAfter),
+            EdgeType::Pin => Ok(libloot::EdgeType::Pin),
             EdgeType::MasterlistGroup => Ok(libloot::EdgeType::MasterlistGroup),
             EdgeType::UserGroup => Ok(libloot::EdgeType::UserGroup),
             EdgeType::RecordOverlap => Ok(libloot::EdgeType::RecordOverlap),
@@ -31,9 +31,9 @@ impl std::fmt::Display for VerboseError {
             Self::CyclicInteractionError(cycle) => {
                 write!(f, "CyclicInteractionError: ")?;
                 for vertex in cycle {
-                    let name = vertex.name().replace('\\', "\\\\").replace('-', "\\-");
+                    let name = escape_cycle_text(vertex.name());
                     match vertex.out_edge_type() {
-                        Some(e) => write!(f, "{name}--{e}--")?,
+                        Some(e) => write!(f, "{name}--{}--", escape_cycle_text(&e.to_string()))?,
                         None => write!(f, "{name}")?,
                     }
                 }
@@ -58,6 +58,13 @@ impl std::fmt::Display for VerboseError {
     }
 }
 
+/// Escape text so that it can be written between the "--" separators of a
+/// cycle. Edge types need escaping too, as layer edge types include the
+/// layer's name.
+fn escape_cycle_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('-', "\\-")
+}
+
 variant_box_from_error!(UnsupportedEnumValueError, VerboseError::Other);
 variant_box_from_error!(NotValidUtf8, VerboseError::Other);
 variant_box_from_error!(DatabaseLockPoisonError, VerboseError::Other);
@@ -95,7 +102,11 @@ impl From<SortPluginsError> for VerboseError {
             SortPluginsError::PluginDataError(e) => e.into(),
             SortPluginsError::DatabaseLockPoisoned
             | SortPluginsError::PluginNotLoaded(_)
+            | SortPluginsError::CyclesFound(_)
             | SortPluginsError::CycleFoundInvolving(_)
+            | SortPluginsError::NonUniqueOrder(_, _)
+            | SortPluginsError::InvalidPin(_)
+            | SortPluginsError::Cancelled
             | SortPluginsError::PathfindingError(_)
             | _ => Self::Other(Box::new(value)),
         }
@@ -244,10 +244,14 @@ mod ffi {
         MasterFlag,
         Master,
         MasterlistRequirement,
+        LayerRequirement,
         UserRequirement,
         MasterlistLoadAfter,
+        LayerLoadAfter,
         UserLoadAfter,
+        Pin,
         MasterlistGroup,
+        LayerGroup,
         UserGroup,
         RecordOverlap,
         AssetOverlap,
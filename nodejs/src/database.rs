@@ -241,14 +241,14 @@ pub struct Vertex(libloot::Vertex);
 #[napi]
 impl Vertex {
     #[napi(constructor)]
-    pub fn new(name: String, out_edge_type: Option<EdgeType>) -> Self {
+    pub fn new(name: String, out_edge_type: Option<EdgeType>) -> Result<Self, VerboseError> {
         let mut vertex = libloot::Vertex::new(name);
 
         if let Some(out_edge_type) = out_edge_type {
-            vertex = vertex.with_out_edge_type(out_edge_type.into());
+            vertex = vertex.with_out_edge_type(out_edge_type.try_into()?);
         }
 
-        Self(vertex)
+        Ok(Self(vertex))
     }
 
     #[napi(getter)]
@@ -284,10 +284,14 @@ pub enum EdgeType {
     MasterFlag,
     Master,
     MasterlistRequirement,
+    LayerRequirement,
     UserRequirement,
     MasterlistLoadAfter,
+    LayerLoadAfter,
     UserLoadAfter,
+    Pin,
     MasterlistGroup,
+    LayerGroup,
     UserGroup,
     RecordOverlap,
     AssetOverlap,
@@ -304,10 +308,14 @@ impl TryFrom<libloot::EdgeType> for EdgeType {
             libloot::EdgeType::MasterFlag => Ok(EdgeType::MasterFlag),
             libloot::EdgeType::Master => Ok(EdgeType::Master),
             libloot::EdgeType::MasterlistRequirement => Ok(EdgeType::MasterlistRequirement),
+            libloot::EdgeType::LayerRequirement(_) => Ok(EdgeType::LayerRequirement),
             libloot::EdgeType::UserRequirement => Ok(EdgeType::UserRequirement),
             libloot::EdgeType::MasterlistLoadAfter => Ok(EdgeType::MasterlistLoadAfter),
+            libloot::EdgeType::LayerLoadAfter(_) => Ok(EdgeType::LayerLoadAfter),
             libloot::EdgeType::UserLoadAfter => Ok(EdgeType::UserLoadAfter),
+            libloot::EdgeType::Pin => Ok(EdgeType::Pin),
             libloot::EdgeType::MasterlistGroup => Ok(EdgeType::MasterlistGroup),
+            libloot::EdgeType::LayerGroup(_) => Ok(EdgeType::LayerGroup),
             libloot::EdgeType::UserGroup => Ok(EdgeType::UserGroup),
             libloot::EdgeType::RecordOverlap => Ok(EdgeType::RecordOverlap),
             libloot::EdgeType::AssetOverlap => Ok(EdgeType::AssetOverlap),
@@ -318,22 +326,29 @@ impl TryFrom<libloot::EdgeType> for EdgeType {
     }
 }
 
-impl From<EdgeType> for libloot::EdgeType {
-    fn from(value: EdgeType) -> Self {
+impl TryFrom<EdgeType> for libloot::EdgeType {
+    type Error = UnsupportedEnumValueError;
+
+    fn try_from(value: EdgeType) -> Result<Self, Self::Error> {
         match value {
-            EdgeType::Hardcoded => libloot::EdgeType::Hardcoded,
-            EdgeType::MasterFlag => libloot::EdgeType::MasterFlag,
-            EdgeType::Master => libloot::EdgeType::Master,
-            EdgeType::MasterlistRequirement => libloot::EdgeType::MasterlistRequirement,
-            EdgeType::UserRequirement => libloot::EdgeType::UserRequirement,
-            EdgeType::MasterlistLoadAfter => libloot::EdgeType::MasterlistLoadAfter,
-            EdgeType::UserLoadAfter => libloot::EdgeType::UserLoadAfter,
-            EdgeType::MasterlistGroup => libloot::EdgeType::MasterlistGroup,
-            EdgeType::UserGroup => libloot::EdgeType::UserGroup,
-            EdgeType::RecordOverlap => libloot::EdgeType::RecordOverlap,
-            EdgeType::AssetOverlap => libloot::EdgeType::AssetOverlap,
-            EdgeType::TieBreak => libloot::EdgeType::TieBreak,
-            EdgeType::BlueprintMaster => libloot::EdgeType::BlueprintMaster,
+            EdgeType::Hardcoded => Ok(libloot::EdgeType::Hardcoded),
+            EdgeType::MasterFlag => Ok(libloot::EdgeType::MasterFlag),
+            EdgeType::Master => Ok(libloot::EdgeType::Master),
+            EdgeType::MasterlistRequirement => Ok(libloot::EdgeType::MasterlistRequirement),
+            EdgeType::UserRequirement => Ok(libloot::EdgeType::UserRequirement),
+            EdgeType::MasterlistLoadAfter => Ok(libloot::EdgeType::MasterlistLoadAfter),
+            EdgeType::UserLoadAfter => Ok(libloot::EdgeType::UserLoadAfter),
+            EdgeType::Pin => Ok(libloot::EdgeType::Pin),
+            EdgeType::MasterlistGroup => Ok(libloot::EdgeType::MasterlistGroup),
+            EdgeType::UserGroup => Ok(libloot::EdgeType::UserGroup),
+            EdgeType::RecordOverlap => Ok(libloot::EdgeType::RecordOverlap),
+            EdgeType::AssetOverlap => Ok(libloot::EdgeType::AssetOverlap),
+            EdgeType::TieBreak => Ok(libloot::EdgeType::TieBreak),
+            EdgeType::BlueprintMaster => Ok(libloot::EdgeType::BlueprintMaster),
+            // The name of the layer is unknown.
+            EdgeType::LayerRequirement | EdgeType::LayerLoadAfter | EdgeType::LayerGroup => {
+                Err(UnsupportedEnumValueError)
+            }
         }
     }
 }
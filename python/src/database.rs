@@ -280,10 +280,14 @@ pub enum EdgeType {
     MasterFlag,
     Master,
     MasterlistRequirement,
+    LayerRequirement,
     UserRequirement,
     MasterlistLoadAfter,
+    LayerLoadAfter,
     UserLoadAfter,
+    Pin,
     MasterlistGroup,
+    LayerGroup,
     UserGroup,
     RecordOverlap,
     AssetOverlap,
@@ -300,10 +304,14 @@ impl TryFrom<libloot::EdgeType> for EdgeType {
             libloot::EdgeType::MasterFlag => Ok(EdgeType::MasterFlag),
             libloot::EdgeType::Master => Ok(EdgeType::Master),
             libloot::EdgeType::MasterlistRequirement => Ok(EdgeType::MasterlistRequirement),
+            libloot::EdgeType::LayerRequirement(_) => Ok(EdgeType::LayerRequirement),
             libloot::EdgeType::UserRequirement => Ok(EdgeType::UserRequirement),
             libloot::EdgeType::MasterlistLoadAfter => Ok(EdgeType::MasterlistLoadAfter),
+            libloot::EdgeType::LayerLoadAfter(_) => Ok(EdgeType::LayerLoadAfter),
             libloot::EdgeType::UserLoadAfter => Ok(EdgeType::UserLoadAfter),
+            libloot::EdgeType::Pin => Ok(EdgeType::Pin),
             libloot::EdgeType::MasterlistGroup => Ok(EdgeType::MasterlistGroup),
+            libloot::EdgeType::LayerGroup(_) => Ok(EdgeType::LayerGroup),
             libloot::EdgeType::UserGroup => Ok(EdgeType::UserGroup),
             libloot::EdgeType::RecordOverlap => Ok(EdgeType::RecordOverlap),
             libloot::EdgeType::AssetOverlap => Ok(EdgeType::AssetOverlap),
@@ -326,12 +334,17 @@ impl TryFrom<EdgeType> for libloot::EdgeType {
             EdgeType::UserRequirement => Ok(libloot::EdgeType::UserRequirement),
             EdgeType::MasterlistLoadAfter => Ok(libloot::EdgeType::MasterlistLoadAfter),
             EdgeType::UserLoadAfter => Ok(libloot::EdgeType::UserLoadAfter),
+            EdgeType::Pin => Ok(libloot::EdgeType::Pin),
             EdgeType::MasterlistGroup => Ok(libloot::EdgeType::MasterlistGroup),
             EdgeType::UserGroup => Ok(libloot::EdgeType::UserGroup),
             EdgeType::RecordOverlap => Ok(libloot::EdgeType::RecordOverlap),
             EdgeType::AssetOverlap => Ok(libloot::EdgeType::AssetOverlap),
             EdgeType::TieBreak => Ok(libloot::EdgeType::TieBreak),
             EdgeType::BlueprintMaster => Ok(libloot::EdgeType::BlueprintMaster),
+            // The name of the layer is unknown.
+            EdgeType::LayerRequirement | EdgeType::LayerLoadAfter | EdgeType::LayerGroup => {
+                Err(UnsupportedEnumValueError)
+            }
         }
     }
 }
@@ -342,10 +355,14 @@ fn repr_edge_type(value: libloot::EdgeType) -> &'static str {
         libloot::EdgeType::MasterFlag => "EdgeType.MasterFlag",
         libloot::EdgeType::Master => "EdgeType.Master",
         libloot::EdgeType::MasterlistRequirement => "EdgeType.MasterlistRequirement",
+        libloot::EdgeType::LayerRequirement(_) => "EdgeType.LayerRequirement",
         libloot::EdgeType::UserRequirement => "EdgeType.UserRequirement",
         libloot::EdgeType::MasterlistLoadAfter => "EdgeType.MasterlistLoadAfter",
+        libloot::EdgeType::LayerLoadAfter(_) => "EdgeType.LayerLoadAfter",
         libloot::EdgeType::UserLoadAfter => "EdgeType.UserLoadAfter",
+        libloot::EdgeType::Pin => "EdgeType.Pin",
         libloot::EdgeType::MasterlistGroup => "EdgeType.MasterlistGroup",
+        libloot::EdgeType::LayerGroup(_) => "EdgeType.LayerGroup",
         libloot::EdgeType::UserGroup => "EdgeType.UserGroup",
         libloot::EdgeType::RecordOverlap => "EdgeType.RecordOverlap",
         libloot::EdgeType::AssetOverlap => "EdgeType.AssetOverlap",
@@ -56,7 +56,11 @@ impl From<SortPluginsError> for VerboseError {
             SortPluginsError::PluginDataError(e) => e.into(),
             SortPluginsError::DatabaseLockPoisoned
             | SortPluginsError::PluginNotLoaded(_)
+            | SortPluginsError::CyclesFound(_)
             | SortPluginsError::CycleFoundInvolving(_)
+            | SortPluginsError::NonUniqueOrder(_, _)
+            | SortPluginsError::InvalidPin(_)
+            | SortPluginsError::Cancelled
             | SortPluginsError::PathfindingError(_)
             | _ => Self::Other(Box::new(value)),
         }
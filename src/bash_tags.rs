@@ -0,0 +1,308 @@
+use crate::metadata::{PluginMetadata, Tag, TagSuggestion, metadata_document::plugins_to_yaml};
+
+/// A source of Bash Tag suggestions for a plugin. Sources are listed in order
+/// of increasing precedence.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum BashTagSource {
+    /// The `{{BASH:...}}` block in the plugin's description.
+    Description,
+    Masterlist,
+    /// An additional metadata layer, identified by its name.
+    Layer(String),
+    Userlist,
+}
+
+impl std::fmt::Display for BashTagSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BashTagSource::Description => write!(f, "description"),
+            BashTagSource::Masterlist => write!(f, "masterlist"),
+            BashTagSource::Layer(name) => write!(f, "metadata layer \"{name}\""),
+            BashTagSource::Userlist => write!(f, "userlist"),
+        }
+    }
+}
+
+/// A Bash Tag that one or more sources suggest adding to or removing from a
+/// plugin, and which of those suggestions take effect.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ResolvedBashTag {
+    name: String,
+    added_by: Vec<BashTagSource>,
+    removed_by: Option<BashTagSource>,
+}
+
+impl ResolvedBashTag {
+    /// Get the tag's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check if the tag applies to the plugin, i.e. it has not been removed
+    /// by a source with a higher precedence than the last source that added
+    /// it.
+    pub fn is_effective(&self) -> bool {
+        self.removed_by.is_none()
+    }
+
+    /// Get the sources that suggest adding the tag, in order of precedence.
+    pub fn added_by(&self) -> &[BashTagSource] {
+        &self.added_by
+    }
+
+    /// Get the source that removed the tag, if the tag is not effective.
+    pub fn removed_by(&self) -> Option<&BashTagSource> {
+        self.removed_by.as_ref()
+    }
+}
+
+/// The resolved Bash Tags of a loaded plugin.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PluginBashTags {
+    plugin_name: String,
+    tags: Vec<ResolvedBashTag>,
+}
+
+impl PluginBashTags {
+    /// Get the name of the plugin that the tags are for.
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin_name
+    }
+
+    /// Get all the tags suggested for the plugin, including those that have
+    /// been removed, in the order that they were first suggested.
+    pub fn tags(&self) -> &[ResolvedBashTag] {
+        &self.tags
+    }
+
+    /// Get the names of the tags that apply to the plugin.
+    pub fn effective_tags(&self) -> impl Iterator<Item = &str> {
+        self.tags
+            .iter()
+            .filter(|t| t.is_effective())
+            .map(ResolvedBashTag::name)
+    }
+}
+
+/// The resolved Bash Tags of all loaded plugins that have any Bash Tag
+/// suggestions, as created by
+/// [Game::bash_tag_report][crate::Game::bash_tag_report]. Plugins are listed
+/// in load order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BashTagReport {
+    plugins: Vec<PluginBashTags>,
+}
+
+impl BashTagReport {
+    pub(crate) fn new(plugins: Vec<PluginBashTags>) -> Self {
+        Self { plugins }
+    }
+
+    /// Get the resolved tags of each plugin in the report.
+    pub fn plugins(&self) -> &[PluginBashTags] {
+        &self.plugins
+    }
+
+    /// Write the report as a Wrye Bash tag list, which uses the same format
+    /// as a LOOT masterlist that only contains plugins' Bash Tag suggestions.
+    ///
+    /// Effective tags are written as additions and tags that have been
+    /// removed are written as removals, so that Wrye Bash also removes them
+    /// if they are listed in a plugin's description.
+    pub fn to_wrye_bash_taglist(&self) -> String {
+        let plugins: Vec<_> = self
+            .plugins
+            .iter()
+            .filter_map(|p| {
+                let mut metadata = PluginMetadata::new(&p.plugin_name).ok()?;
+                metadata.set_tags(
+                    p.tags
+                        .iter()
+                        .map(|t| {
+                            let suggestion = if t.is_effective() {
+                                TagSuggestion::Addition
+                            } else {
+                                TagSuggestion::Removal
+                            };
+                            Tag::new(t.name.clone(), suggestion)
+                        })
+                        .collect(),
+                );
+                Some(metadata)
+            })
+            .collect();
+
+        plugins_to_yaml(&plugins)
+    }
+}
+
+/// Resolve a plugin's Bash Tags from the tags in its description and the tag
+/// suggestions from each metadata source, which must be given in order of
+/// increasing precedence and have had their conditions evaluated. Within a
+/// source, later suggestions for a tag override earlier ones.
+pub(crate) fn resolve_bash_tags<'a>(
+    plugin_name: &str,
+    description_tags: &[String],
+    sources: impl IntoIterator<Item = (BashTagSource, &'a [Tag])>,
+) -> PluginBashTags {
+    let mut tags: Vec<ResolvedBashTag> = Vec::new();
+
+    let description_suggestions = description_tags
+        .iter()
+        .map(|t| (BashTagSource::Description, t.as_str(), true));
+    let metadata_suggestions = sources.into_iter().flat_map(|(source, tags)| {
+        tags.iter()
+            .map(move |t| (source.clone(), t.name(), t.is_addition()))
+    });
+
+    for (source, name, is_addition) in description_suggestions.chain(metadata_suggestions) {
+        if !tags.iter().any(|t| t.name == name) {
+            tags.push(ResolvedBashTag {
+                name: name.to_owned(),
+                added_by: Vec::new(),
+                removed_by: None,
+            });
+        }
+
+        let Some(tag) = tags.iter_mut().find(|t| t.name == name) else {
+            continue;
+        };
+
+        if is_addition {
+            if !tag.added_by.contains(&source) {
+                tag.added_by.push(source);
+            }
+            tag.removed_by = None;
+        } else {
+            tag.removed_by = Some(source);
+        }
+    }
+
+    PluginBashTags {
+        plugin_name: plugin_name.to_owned(),
+        tags,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addition(name: &str) -> Tag {
+        Tag::new(name.into(), TagSuggestion::Addition)
+    }
+
+    fn removal(name: &str) -> Tag {
+        Tag::new(name.into(), TagSuggestion::Removal)
+    }
+
+    mod resolve_bash_tags {
+        use super::*;
+
+        #[test]
+        fn should_include_description_tags() {
+            let tags = resolve_bash_tags("Blank.esp", &["Delev".into(), "Relev".into()], []);
+
+            assert_eq!(
+                vec!["Delev", "Relev"],
+                tags.effective_tags().collect::<Vec<_>>()
+            );
+            assert_eq!(&[BashTagSource::Description], tags.tags()[0].added_by());
+        }
+
+        #[test]
+        fn should_let_higher_precedence_sources_override_lower_precedence_sources() {
+            let masterlist = [removal("Delev"), addition("Relev"), addition("Names")];
+            let layer = [addition("Delev")];
+            let userlist = [removal("Relev")];
+
+            let tags = resolve_bash_tags(
+                "Blank.esp",
+                &["Delev".into()],
+                [
+                    (BashTagSource::Masterlist, masterlist.as_slice()),
+                    (BashTagSource::Layer("layer".into()), layer.as_slice()),
+                    (BashTagSource::Userlist, userlist.as_slice()),
+                ],
+            );
+
+            assert_eq!(
+                vec!["Delev", "Names"],
+                tags.effective_tags().collect::<Vec<_>>()
+            );
+
+            let delev = &tags.tags()[0];
+            assert_eq!(
+                &[
+                    BashTagSource::Description,
+                    BashTagSource::Layer("layer".into())
+                ],
+                delev.added_by()
+            );
+            assert!(delev.removed_by().is_none());
+
+            let relev = &tags.tags()[1];
+            assert!(!relev.is_effective());
+            assert_eq!(&[BashTagSource::Masterlist], relev.added_by());
+            assert_eq!(Some(&BashTagSource::Userlist), relev.removed_by());
+        }
+
+        #[test]
+        fn should_record_removals_of_tags_that_were_never_added() {
+            let masterlist = [removal("Relev")];
+
+            let tags = resolve_bash_tags(
+                "Blank.esp",
+                &[],
+                [(BashTagSource::Masterlist, masterlist.as_slice())],
+            );
+
+            assert!(tags.tags()[0].added_by().is_empty());
+            assert_eq!(
+                Some(&BashTagSource::Masterlist),
+                tags.tags()[0].removed_by()
+            );
+        }
+    }
+
+    mod bash_tag_report {
+        use super::*;
+
+        #[test]
+        fn to_wrye_bash_taglist_should_write_effective_tags_as_additions_and_others_as_removals() {
+            let masterlist = [removal("Delev"), addition("Relev")];
+            let report = BashTagReport::new(vec![
+                resolve_bash_tags(
+                    "Blank.esp",
+                    &["Delev".into()],
+                    [(BashTagSource::Masterlist, masterlist.as_slice())],
+                ),
+                resolve_bash_tags("Blank.esm", &["Names".into()], []),
+            ]);
+
+            let mut document = crate::metadata::metadata_document::MetadataDocument::default();
+            document
+                .load_from_str(&report.to_wrye_bash_taglist())
+                .unwrap();
+
+            let esp = document.find_plugin("Blank.esp").unwrap().unwrap();
+            assert_eq!(&[removal("Delev"), addition("Relev")], esp.tags());
+
+            let esm = document.find_plugin("Blank.esm").unwrap().unwrap();
+            assert_eq!(&[addition("Names")], esm.tags());
+        }
+
+        #[test]
+        fn to_wrye_bash_taglist_should_list_plugins_in_report_order() {
+            let report = BashTagReport::new(vec![
+                resolve_bash_tags("B.esp", &["Delev".into()], []),
+                resolve_bash_tags("A.esp", &["Delev".into()], []),
+            ]);
+
+            let taglist = report.to_wrye_bash_taglist();
+
+            assert!(taglist.find("B.esp").unwrap() < taglist.find("A.esp").unwrap());
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use crate::metadata::{PluginCleaningData, PluginMetadata, select_message_content};
+
+/// Whether cleaning data says that a plugin is dirty or clean.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CleaningStatus {
+    /// The plugin contains records that should be cleaned.
+    Dirty,
+    /// The plugin has been verified as not needing to be cleaned.
+    Clean,
+}
+
+impl std::fmt::Display for CleaningStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CleaningStatus::Dirty => write!(f, "dirty"),
+            CleaningStatus::Clean => write!(f, "clean"),
+        }
+    }
+}
+
+/// A plugin's cleaning data that matches the plugin's current CRC.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CleaningReportEntry {
+    plugin_name: String,
+    status: CleaningStatus,
+    data: PluginCleaningData,
+    detail: Option<String>,
+}
+
+impl CleaningReportEntry {
+    /// Get the name of the plugin that the entry is for.
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin_name
+    }
+
+    /// Get whether the plugin is dirty or clean.
+    pub fn status(&self) -> CleaningStatus {
+        self.status
+    }
+
+    /// Get the cleaning data, which gives the plugin's CRC, the number of
+    /// records that need cleaning and the cleaning utility that found them.
+    pub fn cleaning_data(&self) -> &PluginCleaningData {
+        &self.data
+    }
+
+    /// Get the cleaning data's detail text in the language that the report
+    /// was created for, if the cleaning data has any detail.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+}
+
+/// Lists which loaded plugins are dirty and which have been verified as clean,
+/// as created by [Game::cleaning_report][crate::Game::cleaning_report].
+///
+/// Entries are listed in load order, with the dirty entries for a plugin
+/// listed before its clean entries. Plugins that have no cleaning data that
+/// matches their current CRC are not listed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct CleaningReport {
+    entries: Vec<CleaningReportEntry>,
+}
+
+impl CleaningReport {
+    pub(crate) fn new(entries: Vec<CleaningReportEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Get all the report's entries.
+    pub fn entries(&self) -> &[CleaningReportEntry] {
+        &self.entries
+    }
+
+    /// Get the entries for dirty plugins.
+    pub fn dirty_entries(&self) -> impl Iterator<Item = &CleaningReportEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == CleaningStatus::Dirty)
+    }
+
+    /// Get the entries for plugins that have been verified as clean.
+    pub fn clean_entries(&self) -> impl Iterator<Item = &CleaningReportEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == CleaningStatus::Clean)
+    }
+
+    /// Write the report as comma-separated values, with a header row followed
+    /// by one row per entry. CRCs are written as 8-digit hexadecimal numbers.
+    pub fn to_csv(&self) -> String {
+        use std::fmt::Write;
+
+        let mut csv = String::from(
+            "plugin,status,crc,itm_count,deleted_reference_count,deleted_navmesh_count,cleaning_utility,detail\n",
+        );
+
+        for entry in &self.entries {
+            let _e = writeln!(
+                csv,
+                "{},{},{:08X},{},{},{},{},{}",
+                csv_field(&entry.plugin_name),
+                entry.status,
+                entry.data.crc(),
+                entry.data.itm_count(),
+                entry.data.deleted_reference_count(),
+                entry.data.deleted_navmesh_count(),
+                csv_field(entry.data.cleaning_utility()),
+                csv_field(entry.detail().unwrap_or_default()),
+            );
+        }
+
+        csv
+    }
+
+    /// Write the report as a JSON array of objects, one per entry, with the
+    /// same fields as [CleaningReport::to_csv] writes.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "plugin": e.plugin_name,
+                    "status": e.status.to_string(),
+                    "crc": format!("{:08X}", e.data.crc()),
+                    "itm_count": e.data.itm_count(),
+                    "deleted_reference_count": e.data.deleted_reference_count(),
+                    "deleted_navmesh_count": e.data.deleted_navmesh_count(),
+                    "cleaning_utility": e.data.cleaning_utility(),
+                    "detail": e.detail,
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&entries)
+    }
+}
+
+/// Quote a CSV field if it contains a character that would otherwise be
+/// treated as part of the CSV syntax.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Get a plugin's report entries from its evaluated metadata, in which only
+/// cleaning data that matches the plugin's CRC remains.
+pub(crate) fn cleaning_report_entries(
+    plugin_name: &str,
+    metadata: &PluginMetadata,
+    language: &str,
+) -> Vec<CleaningReportEntry> {
+    let dirty = metadata
+        .dirty_info()
+        .iter()
+        .map(|d| (CleaningStatus::Dirty, d));
+    let clean = metadata
+        .clean_info()
+        .iter()
+        .map(|d| (CleaningStatus::Clean, d));
+
+    dirty
+        .chain(clean)
+        .map(|(status, data)| CleaningReportEntry {
+            plugin_name: plugin_name.to_owned(),
+            status,
+            data: data.clone(),
+            detail: select_message_content(data.detail(), language).map(|c| c.text().to_owned()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::MessageContent;
+
+    fn report() -> CleaningReport {
+        let mut metadata = PluginMetadata::new("Blank.esp").unwrap();
+        metadata.set_dirty_info(vec![
+            PluginCleaningData::new(0xDEAD_BEEF, "xEdit, v4".into())
+                .with_itm_count(2)
+                .with_deleted_reference_count(3)
+                .with_deleted_navmesh_count(1)
+                .with_detail(vec![
+                    MessageContent::new("Clean \"manually\"".into()),
+                    MessageContent::new("Manuell".into()).with_language("de".into()),
+                ])
+                .unwrap(),
+        ]);
+        metadata.set_clean_info(vec![PluginCleaningData::new(0x0000_00FF, "BOSS".into())]);
+
+        CleaningReport::new(cleaning_report_entries("Blank.esp", &metadata, "de"))
+    }
+
+    mod cleaning_report_entries {
+        use super::*;
+
+        #[test]
+        fn should_list_dirty_entries_before_clean_entries() {
+            let report = report();
+
+            let statuses: Vec<_> = report
+                .entries()
+                .iter()
+                .map(CleaningReportEntry::status)
+                .collect();
+
+            assert_eq!(vec![CleaningStatus::Dirty, CleaningStatus::Clean], statuses);
+            assert_eq!(1, report.dirty_entries().count());
+            assert_eq!(1, report.clean_entries().count());
+        }
+
+        #[test]
+        fn should_select_detail_in_the_given_language() {
+            let report = report();
+
+            assert_eq!(Some("Manuell"), report.entries()[0].detail());
+            assert_eq!(None, report.entries()[1].detail());
+        }
+
+        #[test]
+        fn should_be_empty_if_there_is_no_cleaning_data() {
+            let metadata = PluginMetadata::new("Blank.esp").unwrap();
+
+            assert!(cleaning_report_entries("Blank.esp", &metadata, "en").is_empty());
+        }
+    }
+
+    mod cleaning_report {
+        use super::*;
+
+        #[test]
+        fn to_csv_should_write_a_header_and_a_row_per_entry_quoting_fields_as_needed() {
+            let mut metadata = PluginMetadata::new("Blank.esp").unwrap();
+            metadata.set_dirty_info(vec![
+                PluginCleaningData::new(0xDEAD_BEEF, "xEdit, v4".into())
+                    .with_itm_count(2)
+                    .with_detail(vec![MessageContent::new("Clean \"manually\"".into())])
+                    .unwrap(),
+            ]);
+            metadata.set_clean_info(vec![PluginCleaningData::new(0x0000_00FF, "BOSS".into())]);
+            let report = CleaningReport::new(cleaning_report_entries("Blank.esp", &metadata, "en"));
+
+            assert_eq!(
+                "plugin,status,crc,itm_count,deleted_reference_count,deleted_navmesh_count,cleaning_utility,detail\n\
+                Blank.esp,dirty,DEADBEEF,2,0,0,\"xEdit, v4\",\"Clean \"\"manually\"\"\"\n\
+                Blank.esp,clean,000000FF,0,0,0,BOSS,\n",
+                report.to_csv()
+            );
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn to_json_should_write_an_object_per_entry() {
+            let json: serde_json::Value =
+                serde_json::from_str(&report().to_json().unwrap()).unwrap();
+
+            assert_eq!(
+                serde_json::json!([
+                    {
+                        "plugin": "Blank.esp",
+                        "status": "dirty",
+                        "crc": "DEADBEEF",
+                        "itm_count": 2_u32,
+                        "deleted_reference_count": 3_u32,
+                        "deleted_navmesh_count": 1_u32,
+                        "cleaning_utility": "xEdit, v4",
+                        "detail": "Manuell",
+                    },
+                    {
+                        "plugin": "Blank.esp",
+                        "status": "clean",
+                        "crc": "000000FF",
+                        "itm_count": 0_u32,
+                        "deleted_reference_count": 0_u32,
+                        "deleted_navmesh_count": 0_u32,
+                        "cleaning_utility": "BOSS",
+                        "detail": null,
+                    }
+                ]),
+                json
+            );
+        }
+    }
+}
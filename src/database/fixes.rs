@@ -0,0 +1,1025 @@
+use std::sync::Arc;
+
+use crate::{
+    EdgeType, Vertex,
+    metadata::{File, Filename, Group, PluginMetadata, metadata_document::MetadataDocument},
+    sorting::groups::{build_layered_groups_graph, find_path},
+};
+
+use super::MetadataLayer;
+
+/// Represents a change to metadata that would remove an edge that is part of
+/// a cycle.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum MetadataChange {
+    /// The new user metadata for a plugin, which can be applied using
+    /// [Database::set_plugin_user_metadata][crate::Database::set_plugin_user_metadata].
+    UserPluginMetadata(PluginMetadata),
+    /// The new set of user groups, which can be applied using
+    /// [Database::set_user_groups][crate::Database::set_user_groups].
+    UserGroups(Vec<Group>),
+    /// The new masterlist metadata for a plugin. This can't be applied using
+    /// the userlist and needs to be made to the masterlist instead.
+    MasterlistPluginMetadata(PluginMetadata),
+    /// The new masterlist definition of a group. This can't be applied using
+    /// the userlist and needs to be made to the masterlist instead.
+    MasterlistGroup(Group),
+    /// The name of an additional metadata layer and the new metadata for a
+    /// plugin in that layer. This can't be applied using the userlist and
+    /// needs to be made to the layer instead.
+    LayerPluginMetadata(String, PluginMetadata),
+    /// The name of an additional metadata layer and the new definition of a
+    /// group in that layer. This can't be applied using the userlist and needs
+    /// to be made to the layer instead.
+    LayerGroup(String, Group),
+}
+
+/// Represents a suggested fix for one or more cycles, which involves removing
+/// an edge by changing the metadata that caused it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CycleFix {
+    from: String,
+    to: String,
+    edge_type: EdgeType,
+    change: MetadataChange,
+}
+
+impl CycleFix {
+    /// Get the name of the plugin or group that the removed edge goes from.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Get the name of the plugin or group that the removed edge goes to.
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// Get the type of the removed edge.
+    pub fn edge_type(&self) -> EdgeType {
+        self.edge_type.clone()
+    }
+
+    /// Get the metadata change that would remove the edge.
+    pub fn change(&self) -> &MetadataChange {
+        &self.change
+    }
+}
+
+pub(crate) fn suggest_cycle_fixes(
+    masterlist: &MetadataDocument,
+    layers: &[MetadataLayer],
+    userlist: &MetadataDocument,
+    cycles: &[Vec<Vertex>],
+) -> Vec<CycleFix> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    for (cycle_index, cycle) in cycles.iter().enumerate() {
+        for edge in cycle_edges(cycle) {
+            if let Some(candidate) = candidates.iter_mut().find(|c| c.edge == edge) {
+                candidate.cycles.push(cycle_index);
+            } else if let Some(rank) = removal_rank(&edge.2) {
+                candidates.push(Candidate {
+                    edge,
+                    rank,
+                    cycles: vec![cycle_index],
+                });
+            }
+        }
+    }
+
+    // Only consider edges that the current metadata allows to be removed.
+    candidates.retain(|c| {
+        !FixState::new(masterlist, layers, userlist)
+            .remove_edge(&c.edge)
+            .is_empty()
+    });
+
+    // Order candidates by preference so that the first cover found of a given
+    // size is the one that uses the edges that are easiest to remove.
+    candidates.sort_by_key(|c| c.rank);
+
+    let mut uncovered: Vec<usize> = candidates
+        .iter()
+        .flat_map(|c| c.cycles.iter().copied())
+        .collect();
+    uncovered.sort_unstable();
+    uncovered.dedup();
+
+    let mut cover = Vec::new();
+    for size in 1..=candidates.len() {
+        if find_cover(&candidates, &uncovered, size, 0, &mut cover) {
+            break;
+        }
+    }
+
+    let mut state = FixState::new(masterlist, layers, userlist);
+    let mut fixes = Vec::new();
+
+    for candidate in cover.into_iter().filter_map(|i| candidates.get(i)) {
+        let (from, to, edge_type) = &candidate.edge;
+        fixes.extend(
+            state
+                .remove_edge(&candidate.edge)
+                .into_iter()
+                .map(|change| CycleFix {
+                    from: from.clone(),
+                    to: to.clone(),
+                    edge_type: edge_type.clone(),
+                    change,
+                }),
+        );
+    }
+
+    fixes
+}
+
+/// Search for a set of at most `size` candidates, taken from those at or after
+/// `offset`, that between them are part of all the `uncovered` cycles. The
+/// indices of the chosen candidates are appended to `cover`, and combinations
+/// are tried in lexicographic order.
+fn find_cover(
+    candidates: &[Candidate],
+    uncovered: &[usize],
+    size: usize,
+    offset: usize,
+    cover: &mut Vec<usize>,
+) -> bool {
+    if uncovered.is_empty() {
+        return true;
+    }
+    if size == 0 {
+        return false;
+    }
+
+    for (index, candidate) in candidates.iter().enumerate().skip(offset) {
+        let remaining: Vec<usize> = uncovered
+            .iter()
+            .copied()
+            .filter(|i| !candidate.cycles.contains(i))
+            .collect();
+
+        // A smallest cover never includes an edge that doesn't fix another
+        // cycle.
+        if remaining.len() == uncovered.len() {
+            continue;
+        }
+
+        cover.push(index);
+        if find_cover(candidates, &remaining, size - 1, index + 1, cover) {
+            return true;
+        }
+        cover.pop();
+    }
+
+    false
+}
+
+type Edge = (String, String, EdgeType);
+
+#[derive(Debug)]
+struct Candidate {
+    edge: Edge,
+    rank: u8,
+    cycles: Vec<usize>,
+}
+
+fn cycle_edges(cycle: &[Vertex]) -> impl Iterator<Item = Edge> {
+    let next_vertices = cycle.iter().skip(1).chain(cycle.first());
+
+    cycle
+        .iter()
+        .zip(next_vertices)
+        .filter_map(|(vertex, next_vertex)| {
+            vertex.out_edge_type().map(|edge_type| {
+                (
+                    vertex.name().to_owned(),
+                    next_vertex.name().to_owned(),
+                    edge_type,
+                )
+            })
+        })
+}
+
+/// Lower ranks are preferred for removal, and edges that can't be removed
+/// have no rank.
+fn removal_rank(edge_type: &EdgeType) -> Option<u8> {
+    match edge_type {
+        EdgeType::UserLoadAfter => Some(0),
+        EdgeType::UserGroup => Some(1),
+        EdgeType::UserRequirement => Some(2),
+        EdgeType::LayerLoadAfter(_) => Some(3),
+        EdgeType::LayerGroup(_) => Some(4),
+        EdgeType::LayerRequirement(_) => Some(5),
+        EdgeType::MasterlistLoadAfter => Some(6),
+        EdgeType::MasterlistRequirement => Some(7),
+        _ => None,
+    }
+}
+
+/// Holds metadata as it is changed by fixes, so that later fixes build on
+/// earlier ones.
+#[derive(Debug)]
+struct FixState<'a> {
+    masterlist: DocumentState<'a>,
+    layers: Vec<(Arc<str>, DocumentState<'a>)>,
+    userlist: DocumentState<'a>,
+}
+
+impl<'a> FixState<'a> {
+    fn new(
+        masterlist: &'a MetadataDocument,
+        layers: &'a [MetadataLayer],
+        userlist: &'a MetadataDocument,
+    ) -> Self {
+        Self {
+            masterlist: DocumentState::new(masterlist),
+            layers: layers
+                .iter()
+                .map(|l| (Arc::clone(&l.name), DocumentState::new(&l.document)))
+                .collect(),
+            userlist: DocumentState::new(userlist),
+        }
+    }
+
+    /// Get the changes that would remove the given edge, which must all be
+    /// applied to remove it. If the edge can't be removed, no changes are
+    /// returned.
+    fn remove_edge(&mut self, edge: &Edge) -> Vec<MetadataChange> {
+        let (from, to, edge_type) = edge;
+
+        match edge_type {
+            EdgeType::UserLoadAfter => {
+                let changed = self.userlist.remove_load_after_file(to, from);
+                if !changed.is_empty() {
+                    changed
+                        .into_iter()
+                        .map(MetadataChange::UserPluginMetadata)
+                        .collect()
+                } else if self.userlist.remove_after_group(from, to) {
+                    vec![MetadataChange::UserGroups(self.userlist.groups.clone())]
+                } else {
+                    Vec::new()
+                }
+            }
+            EdgeType::UserRequirement => self
+                .userlist
+                .remove_requirement(to, from)
+                .into_iter()
+                .map(MetadataChange::UserPluginMetadata)
+                .collect(),
+            EdgeType::UserGroup => self.remove_group_edge(from, to, |state, plugin| {
+                state
+                    .userlist
+                    .unset_group(plugin)
+                    .into_iter()
+                    .map(MetadataChange::UserPluginMetadata)
+                    .collect()
+            }),
+            EdgeType::MasterlistLoadAfter => {
+                let changed = self.masterlist.remove_load_after_file(to, from);
+                if !changed.is_empty() {
+                    changed
+                        .into_iter()
+                        .map(MetadataChange::MasterlistPluginMetadata)
+                        .collect()
+                } else if self.masterlist.remove_after_group(from, to) {
+                    self.masterlist
+                        .group(to)
+                        .map(MetadataChange::MasterlistGroup)
+                        .into_iter()
+                        .collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            EdgeType::MasterlistRequirement => self
+                .masterlist
+                .remove_requirement(to, from)
+                .into_iter()
+                .map(MetadataChange::MasterlistPluginMetadata)
+                .collect(),
+            EdgeType::LayerLoadAfter(name) => {
+                let Some(layer) = self.layer_mut(name) else {
+                    return Vec::new();
+                };
+
+                let changed = layer.remove_load_after_file(to, from);
+                if !changed.is_empty() {
+                    to_layer_changes(name, changed)
+                } else if layer.remove_after_group(from, to) {
+                    layer
+                        .group(to)
+                        .map(|g| MetadataChange::LayerGroup(name.to_string(), g))
+                        .into_iter()
+                        .collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            EdgeType::LayerRequirement(name) => {
+                let Some(layer) = self.layer_mut(name) else {
+                    return Vec::new();
+                };
+
+                to_layer_changes(name, layer.remove_requirement(to, from))
+            }
+            EdgeType::LayerGroup(name) => self.remove_group_edge(from, to, |state, plugin| {
+                state
+                    .layer_mut(name)
+                    .map(|layer| to_layer_changes(name, layer.unset_group(plugin)))
+                    .unwrap_or_default()
+            }),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remove a group edge by unsetting the group of the plugin that it goes
+    /// to, or failing that the plugin that it goes from. Unsetting a group
+    /// only removes the edge if the plugins' groups are no longer connected
+    /// afterwards, e.g. the default group may still load after the other
+    /// plugin's group, so a change is only kept if it removes the edge.
+    fn remove_group_edge(
+        &mut self,
+        from: &str,
+        to: &str,
+        unset_group: impl Fn(&mut Self, &str) -> Vec<MetadataChange>,
+    ) -> Vec<MetadataChange> {
+        for plugin in [to, from] {
+            let snapshot = self.snapshot();
+            let changes = unset_group(self, plugin);
+            if changes.is_empty() {
+                continue;
+            }
+
+            if self.has_group_path(from, to) {
+                self.restore(snapshot);
+            } else {
+                return changes;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Check if the first plugin's group would load before the second
+    /// plugin's group with the current metadata. If the groups graph can't be
+    /// built, the groups are assumed to still be connected.
+    fn has_group_path(&self, from_plugin: &str, to_plugin: &str) -> bool {
+        let layer_groups: Vec<_> = self
+            .layers
+            .iter()
+            .map(|(name, layer)| (Arc::clone(name), layer.groups.as_slice()))
+            .collect();
+
+        let Ok(graph) = build_layered_groups_graph(
+            &self.masterlist.groups,
+            &layer_groups,
+            &self.userlist.groups,
+        ) else {
+            return true;
+        };
+
+        find_path(&graph, &self.group(from_plugin), &self.group(to_plugin))
+            .map_or(true, |path| path.len() > 1)
+    }
+
+    /// Get the plugin's group, taking it from the highest-precedence document
+    /// that sets it.
+    fn group(&self, plugin_name: &str) -> String {
+        std::iter::once(&self.userlist)
+            .chain(self.layers.iter().rev().map(|(_, layer)| layer))
+            .chain(std::iter::once(&self.masterlist))
+            .find_map(|document| document.plugin_group(plugin_name))
+            .unwrap_or_else(|| Group::DEFAULT_NAME.to_owned())
+    }
+
+    fn snapshot(&self) -> Vec<Vec<PluginMetadata>> {
+        std::iter::once(&self.masterlist)
+            .chain(self.layers.iter().map(|(_, layer)| layer))
+            .chain(std::iter::once(&self.userlist))
+            .map(|document| document.changed_plugins.clone())
+            .collect()
+    }
+
+    fn restore(&mut self, snapshot: Vec<Vec<PluginMetadata>>) {
+        let documents = std::iter::once(&mut self.masterlist)
+            .chain(self.layers.iter_mut().map(|(_, layer)| layer))
+            .chain(std::iter::once(&mut self.userlist));
+
+        for (document, changed_plugins) in documents.zip(snapshot) {
+            document.changed_plugins = changed_plugins;
+        }
+    }
+
+    fn layer_mut(&mut self, name: &str) -> Option<&mut DocumentState<'a>> {
+        self.layers
+            .iter_mut()
+            .find(|(n, _)| n.as_ref() == name)
+            .map(|(_, layer)| layer)
+    }
+}
+
+fn to_layer_changes(layer_name: &str, changed: Vec<PluginMetadata>) -> Vec<MetadataChange> {
+    changed
+        .into_iter()
+        .map(|m| MetadataChange::LayerPluginMetadata(layer_name.to_owned(), m))
+        .collect()
+}
+
+/// Holds one metadata document's plugin entries and groups as they are
+/// changed by fixes.
+#[derive(Debug)]
+struct DocumentState<'a> {
+    document: &'a MetadataDocument,
+    changed_plugins: Vec<PluginMetadata>,
+    groups: Vec<Group>,
+}
+
+impl<'a> DocumentState<'a> {
+    fn new(document: &'a MetadataDocument) -> Self {
+        Self {
+            document,
+            changed_plugins: Vec::new(),
+            groups: document.groups().to_vec(),
+        }
+    }
+
+    /// Get the document's entries that apply to the given plugin, i.e. its
+    /// exact entry and any regex entries that match it, with earlier changes
+    /// applied. Each entry keeps its own name, so a changed regex entry
+    /// replaces the original.
+    fn plugin_entries(&self, plugin_name: &str) -> Vec<PluginMetadata> {
+        self.document
+            .plugin_entries(plugin_name)
+            .map(|e| {
+                find_plugin(&self.changed_plugins, e.name())
+                    .unwrap_or(e)
+                    .clone()
+            })
+            .collect()
+    }
+
+    fn remove_load_after_file(
+        &mut self,
+        plugin_name: &str,
+        file_name: &str,
+    ) -> Vec<PluginMetadata> {
+        self.change_entries(plugin_name, |m| {
+            without_file(m, file_name, PluginMetadata::load_after_files, |m, f| {
+                m.set_load_after_files(f);
+            })
+        })
+    }
+
+    fn remove_requirement(&mut self, plugin_name: &str, file_name: &str) -> Vec<PluginMetadata> {
+        self.change_entries(plugin_name, |m| {
+            without_file(m, file_name, PluginMetadata::requirements, |m, f| {
+                m.set_requirements(f);
+            })
+        })
+    }
+
+    fn plugin_group(&self, plugin_name: &str) -> Option<String> {
+        self.plugin_entries(plugin_name)
+            .into_iter()
+            .find_map(|e| e.group().map(str::to_owned))
+    }
+
+    fn unset_group(&mut self, plugin_name: &str) -> Vec<PluginMetadata> {
+        self.change_entries(plugin_name, |mut m| {
+            m.group().is_some().then(|| {
+                m.unset_group();
+                m
+            })
+        })
+    }
+
+    /// Apply the given change to each of the plugin's entries, returning the
+    /// entries that were changed.
+    fn change_entries(
+        &mut self,
+        plugin_name: &str,
+        change: impl Fn(PluginMetadata) -> Option<PluginMetadata>,
+    ) -> Vec<PluginMetadata> {
+        let changed: Vec<_> = self
+            .plugin_entries(plugin_name)
+            .into_iter()
+            .filter_map(change)
+            .collect();
+
+        for metadata in &changed {
+            replace_plugin(&mut self.changed_plugins, metadata.clone());
+        }
+
+        changed
+    }
+
+    fn remove_after_group(&mut self, after_group: &str, group_name: &str) -> bool {
+        remove_after_group(&mut self.groups, after_group, group_name)
+    }
+
+    fn group(&self, name: &str) -> Option<Group> {
+        self.groups.iter().find(|g| g.name() == name).cloned()
+    }
+}
+
+fn find_plugin<'a>(plugins: &'a [PluginMetadata], name: &str) -> Option<&'a PluginMetadata> {
+    plugins.iter().find(|p| unicase::eq(p.name(), name))
+}
+
+fn replace_plugin(plugins: &mut Vec<PluginMetadata>, metadata: PluginMetadata) {
+    if let Some(existing) = plugins
+        .iter_mut()
+        .find(|p| unicase::eq(p.name(), metadata.name()))
+    {
+        *existing = metadata;
+    } else {
+        plugins.push(metadata);
+    }
+}
+
+/// Returns the given metadata with all files with the given name removed from
+/// the given list, or None if the list doesn't contain any such files.
+fn without_file(
+    mut metadata: PluginMetadata,
+    file_name: &str,
+    getter: impl Fn(&PluginMetadata) -> &[File],
+    setter: impl Fn(&mut PluginMetadata, Vec<File>),
+) -> Option<PluginMetadata> {
+    let file_name = Filename::new(file_name.to_owned());
+    let files = getter(&metadata);
+
+    if !files.iter().any(|f| *f.name() == file_name) {
+        return None;
+    }
+
+    let files = files
+        .iter()
+        .filter(|f| *f.name() != file_name)
+        .cloned()
+        .collect();
+    setter(&mut metadata, files);
+
+    Some(metadata)
+}
+
+/// Removes the given after group from the given group, returning true if it
+/// was present.
+fn remove_after_group(groups: &mut [Group], after_group: &str, group_name: &str) -> bool {
+    let Some(group) = groups.iter_mut().find(|g| g.name() == group_name) else {
+        return false;
+    };
+
+    if !group.after_groups().iter().any(|g| g == after_group) {
+        return false;
+    }
+
+    let after_groups = group
+        .after_groups()
+        .iter()
+        .filter(|g| *g != after_group)
+        .cloned()
+        .collect();
+
+    *group = group.clone().with_after_groups(after_groups);
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Database, GameType};
+
+    struct Fixture {
+        _temp_dir: tempfile::TempDir,
+        database: Database,
+    }
+
+    impl Fixture {
+        fn new(masterlist: &str) -> Self {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let masterlist_path = temp_dir.path().join("masterlist.yaml");
+            std::fs::write(&masterlist_path, masterlist).unwrap();
+
+            let mut database = Database::new(loot_condition_interpreter::State::new(
+                GameType::Oblivion.into(),
+                temp_dir.path().into(),
+            ));
+            database.load_masterlist(&masterlist_path).unwrap();
+
+            Self {
+                _temp_dir: temp_dir,
+                database,
+            }
+        }
+    }
+
+    fn plugin_with_load_after(name: &str, load_after: &[&str]) -> PluginMetadata {
+        let mut metadata = PluginMetadata::new(name).unwrap();
+        metadata.set_load_after_files(load_after.iter().map(|f| File::new((*f).into())).collect());
+        metadata
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_prefer_removing_user_load_after_metadata() {
+        let mut fixture = Fixture::new("plugins: [{name: B.esp, after: [A.esp]}]");
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["B.esp"]));
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::MasterlistLoadAfter),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!("B.esp", fixes[0].from());
+        assert_eq!("A.esp", fixes[0].to());
+        assert_eq!(EdgeType::UserLoadAfter, fixes[0].edge_type());
+        assert_eq!(
+            &MetadataChange::UserPluginMetadata(plugin_with_load_after("A.esp", &[])),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_suggest_masterlist_changes_if_there_is_no_user_metadata_edge() {
+        let fixture = Fixture::new("plugins: [{name: B.esp, after: [A.esp, C.esp]}]");
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::MasterlistLoadAfter),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(EdgeType::MasterlistLoadAfter, fixes[0].edge_type());
+        assert_eq!(
+            &MetadataChange::MasterlistPluginMetadata(plugin_with_load_after("B.esp", &["C.esp"])),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_not_suggest_a_fix_if_no_edges_can_be_removed() {
+        let fixture = Fixture::new("plugins: []");
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::Master),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Hardcoded),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert!(fixes.is_empty());
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_remove_an_edge_shared_by_multiple_cycles_once() {
+        let mut fixture = Fixture::new("plugins: []");
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("B.esp", &["A.esp"]));
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["B.esp", "C.esp"]));
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("C.esp", &["B.esp"]));
+
+        let cycles = vec![
+            vec![
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+                Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+            ],
+            vec![
+                Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+                Vertex::new("C.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+            ],
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&cycles);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!("A.esp", fixes[0].from());
+        assert_eq!("B.esp", fixes[0].to());
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_remove_the_fewest_edges_needed_to_fix_all_cycles() {
+        let mut fixture = Fixture::new("plugins: []");
+        for (plugin, requirement) in [("B.esp", "A.esp"), ("D.esp", "C.esp")] {
+            let mut metadata = PluginMetadata::new(plugin).unwrap();
+            metadata.set_requirements(vec![File::new(requirement.into())]);
+            fixture.database.set_plugin_user_metadata(metadata);
+        }
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("F.esp", &["E.esp"]));
+
+        // Removing the E.esp -> F.esp edge first would fix two cycles, but
+        // would leave two more that need an edge each to be removed.
+        let cycles = vec![
+            vec![
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserRequirement),
+                Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+            ],
+            vec![
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserRequirement),
+                Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+                Vertex::new("E.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+                Vertex::new("F.esp".into()).with_out_edge_type(EdgeType::Master),
+            ],
+            vec![
+                Vertex::new("C.esp".into()).with_out_edge_type(EdgeType::UserRequirement),
+                Vertex::new("D.esp".into()).with_out_edge_type(EdgeType::Master),
+                Vertex::new("E.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+                Vertex::new("F.esp".into()).with_out_edge_type(EdgeType::Master),
+            ],
+            vec![
+                Vertex::new("C.esp".into()).with_out_edge_type(EdgeType::UserRequirement),
+                Vertex::new("D.esp".into()).with_out_edge_type(EdgeType::Master),
+            ],
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&cycles);
+
+        let edges: Vec<_> = fixes.iter().map(|f| (f.from(), f.to())).collect();
+        assert_eq!(vec![("A.esp", "B.esp"), ("C.esp", "D.esp")], edges);
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_include_earlier_changes_to_the_same_plugin() {
+        let mut fixture = Fixture::new("plugins: []");
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["B.esp", "C.esp"]));
+
+        let cycles = vec![
+            vec![
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::Master),
+                Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+            ],
+            vec![
+                Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::Master),
+                Vertex::new("C.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+            ],
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&cycles);
+
+        assert_eq!(2, fixes.len());
+        assert_eq!(
+            &MetadataChange::UserPluginMetadata(plugin_with_load_after("A.esp", &["C.esp"])),
+            fixes[0].change()
+        );
+        assert_eq!(
+            &MetadataChange::UserPluginMetadata(plugin_with_load_after("A.esp", &[])),
+            fixes[1].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_change_the_regex_entry_that_causes_an_edge() {
+        let mut fixture = Fixture::new("plugins: []");
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["C.esp"]));
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after(r"A\.esp", &["B.esp"]));
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::Master),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            &MetadataChange::UserPluginMetadata(plugin_with_load_after(r"A\.esp", &[])),
+            fixes[0].change()
+        );
+
+        let MetadataChange::UserPluginMetadata(metadata) = fixes[0].change().clone() else {
+            panic!("expected a user plugin metadata change");
+        };
+        fixture.database.set_plugin_user_metadata(metadata);
+
+        let metadata = fixture
+            .database
+            .plugin_user_metadata("A.esp", false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(&[File::new("C.esp".into())], metadata.load_after_files());
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_change_every_entry_that_causes_an_edge() {
+        let mut fixture = Fixture::new("plugins: []");
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["B.esp"]));
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after(r"A\.esp", &["B.esp"]));
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::Master),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        let changes: Vec<_> = fixes.iter().map(CycleFix::change).collect();
+        assert_eq!(
+            vec![
+                &MetadataChange::UserPluginMetadata(plugin_with_load_after("A.esp", &[])),
+                &MetadataChange::UserPluginMetadata(plugin_with_load_after(r"A\.esp", &[])),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_remove_user_group_load_after_metadata() {
+        let mut fixture =
+            Fixture::new("groups: [{name: default}, {name: A}, {name: B, after: [A]}]");
+        fixture.database.set_user_groups(vec![
+            Group::new("A".into()).with_after_groups(vec!["B".into()]),
+        ]);
+
+        let cycle = vec![
+            Vertex::new("A".into()).with_out_edge_type(EdgeType::MasterlistLoadAfter),
+            Vertex::new("B".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            &MetadataChange::UserGroups(vec![Group::default(), Group::new("A".into()),]),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_prefer_removing_layer_metadata_over_masterlist_metadata() {
+        let mut fixture = Fixture::new("plugins: [{name: B.esp, after: [A.esp]}]");
+        fixture
+            .database
+            .load_metadata_layer_from_str("local", "plugins: []")
+            .unwrap();
+        fixture
+            .database
+            .load_metadata_layer_from_str("modpack", "plugins: [{name: A.esp, after: [B.esp]}]")
+            .unwrap();
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::MasterlistLoadAfter),
+            Vertex::new("B.esp".into())
+                .with_out_edge_type(EdgeType::LayerLoadAfter("modpack".into())),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            EdgeType::LayerLoadAfter("modpack".into()),
+            fixes[0].edge_type()
+        );
+        assert_eq!(
+            &MetadataChange::LayerPluginMetadata(
+                "modpack".into(),
+                plugin_with_load_after("A.esp", &[])
+            ),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_prefer_removing_user_metadata_over_layer_metadata() {
+        let mut fixture = Fixture::new("plugins: []");
+        fixture
+            .database
+            .load_metadata_layer_from_str("modpack", "plugins: [{name: B.esp, req: [A.esp]}]")
+            .unwrap();
+        fixture
+            .database
+            .set_plugin_user_metadata(plugin_with_load_after("A.esp", &["B.esp"]));
+
+        let cycle = vec![
+            Vertex::new("A.esp".into())
+                .with_out_edge_type(EdgeType::LayerRequirement("modpack".into())),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(EdgeType::UserLoadAfter, fixes[0].edge_type());
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_change_the_layer_that_sets_a_group() {
+        let mut fixture = Fixture::new("groups: [{name: default}, {name: A}]");
+        fixture
+            .database
+            .load_metadata_layer_from_str("modpack", "plugins: [{name: B.esp, group: A}]")
+            .unwrap();
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::LayerGroup("modpack".into())),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            &MetadataChange::LayerPluginMetadata(
+                "modpack".into(),
+                PluginMetadata::new("B.esp").unwrap()
+            ),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_unset_the_earlier_group_if_the_default_group_loads_after_it() {
+        let mut fixture =
+            Fixture::new("groups: [{name: A}, {name: default, after: [A]}, {name: B, after: [A]}]");
+        let mut metadata = PluginMetadata::new("A.esp").unwrap();
+        metadata.set_group("A".into());
+        fixture.database.set_plugin_user_metadata(metadata);
+        let mut metadata = PluginMetadata::new("B.esp").unwrap();
+        metadata.set_group("B".into());
+        fixture.database.set_plugin_user_metadata(metadata);
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserGroup),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            &MetadataChange::UserPluginMetadata(PluginMetadata::new("A.esp").unwrap()),
+            fixes[0].change()
+        );
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_not_unset_a_group_if_the_default_group_would_keep_the_edge() {
+        let mut fixture = Fixture::new(
+            "groups: [{name: A}, {name: default, after: [A]}, {name: B, after: [default]}]",
+        );
+        let mut metadata = PluginMetadata::new("A.esp").unwrap();
+        metadata.set_group("A".into());
+        fixture.database.set_plugin_user_metadata(metadata);
+        let mut metadata = PluginMetadata::new("B.esp").unwrap();
+        metadata.set_group("B".into());
+        fixture.database.set_plugin_user_metadata(metadata);
+
+        let cycle = vec![
+            Vertex::new("A.esp".into()).with_out_edge_type(EdgeType::UserGroup),
+            Vertex::new("B.esp".into()).with_out_edge_type(EdgeType::Master),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert!(fixes.is_empty());
+    }
+
+    #[test]
+    fn suggest_cycle_fixes_should_remove_layer_group_load_after_metadata() {
+        let mut fixture = Fixture::new("groups: [{name: default}, {name: A}, {name: B}]");
+        fixture
+            .database
+            .load_metadata_layer_from_str("modpack", "groups: [{name: B, after: [A]}]")
+            .unwrap();
+
+        let cycle = vec![
+            Vertex::new("A".into()).with_out_edge_type(EdgeType::LayerLoadAfter("modpack".into())),
+            Vertex::new("B".into()).with_out_edge_type(EdgeType::UserLoadAfter),
+        ];
+
+        let fixes = fixture.database.suggest_cycle_fixes(&[cycle]);
+
+        assert_eq!(1, fixes.len());
+        assert_eq!(
+            &MetadataChange::LayerGroup("modpack".into(), Group::new("B".into())),
+            fixes[0].change()
+        );
+    }
+}
@@ -1,24 +1,28 @@
 mod conditions;
 mod error;
+mod fixes;
+mod overlay;
 
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, io::Read, path::Path, sync::Arc};
 
 use conditions::{evaluate_all_conditions, evaluate_condition, filter_map_on_condition};
 
 use crate::{
     logging,
     metadata::{
-        Group, Message, PluginMetadata,
+        Group, Message, MetadataSource, PluginMetadata,
         error::{LoadMetadataError, WriteMetadataError, WriteMetadataErrorReason},
         metadata_document::MetadataDocument,
     },
     sorting::{
-        error::GroupsPathError,
-        groups::{build_groups_graph, find_path},
+        error::{BuildGroupsGraphError, GroupsPathError},
+        groups::{GroupsGraph, build_layered_groups_graph, find_path},
         vertex::Vertex,
     },
 };
 pub use error::{ConditionEvaluationError, MetadataRetrievalError};
+pub use fixes::{CycleFix, MetadataChange};
+pub use overlay::MetadataOverlay;
 
 /// Control behaviour when writing to files.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -30,19 +34,33 @@ pub enum WriteMode {
 }
 
 /// The interface through which metadata can be accessed.
+///
+/// Metadata is held in an ordered stack of layers: the masterlist at the
+/// bottom, then any additional named metadata layers in the order that they
+/// were first loaded, then the userlist at the top. Where layers provide
+/// metadata for the same plugin or group, the metadata is merged, with
+/// higher layers taking precedence.
 #[derive(Debug)]
 pub struct Database {
     masterlist: MetadataDocument,
+    layers: Vec<MetadataLayer>,
     userlist: MetadataDocument,
     condition_evaluator_state: loot_condition_interpreter::State,
 }
 
+#[derive(Debug)]
+struct MetadataLayer {
+    name: Arc<str>,
+    document: MetadataDocument,
+}
+
 impl Database {
     #[must_use]
     pub(crate) fn new(condition_evaluator_state: loot_condition_interpreter::State) -> Self {
         Self {
-            masterlist: MetadataDocument::default(),
-            userlist: MetadataDocument::default(),
+            masterlist: MetadataDocument::new(MetadataSource::Masterlist),
+            layers: Vec::new(),
+            userlist: MetadataDocument::new(MetadataSource::Userlist),
             condition_evaluator_state,
         }
     }
@@ -80,6 +98,120 @@ impl Database {
             .load_with_prelude(masterlist_path, prelude_path)
     }
 
+    /// Loads the masterlist from the given string.
+    ///
+    /// Replaces any existing data that was previously loaded from a masterlist.
+    pub fn load_masterlist_from_str(&mut self, masterlist: &str) -> Result<(), LoadMetadataError> {
+        self.masterlist.load_from_str(masterlist)
+    }
+
+    /// Loads the masterlist from the given reader.
+    ///
+    /// Replaces any existing data that was previously loaded from a masterlist.
+    pub fn load_masterlist_from_reader<R: Read>(
+        &mut self,
+        masterlist: R,
+    ) -> Result<(), LoadMetadataError> {
+        self.masterlist.load_from_reader(masterlist)
+    }
+
+    /// Loads the masterlist from the given string, using the given prelude
+    /// string.
+    ///
+    /// Replaces any existing data that was previously loaded from a masterlist.
+    pub fn load_masterlist_with_prelude_from_str(
+        &mut self,
+        masterlist: &str,
+        prelude: &str,
+    ) -> Result<(), LoadMetadataError> {
+        self.masterlist
+            .load_with_prelude_from_str(masterlist, prelude)
+    }
+
+    /// Loads the masterlist from the given reader, using the prelude read from
+    /// the given reader.
+    ///
+    /// Replaces any existing data that was previously loaded from a masterlist.
+    pub fn load_masterlist_with_prelude_from_reader<M: Read, P: Read>(
+        &mut self,
+        masterlist: M,
+        prelude: P,
+    ) -> Result<(), LoadMetadataError> {
+        self.masterlist
+            .load_with_prelude_from_reader(masterlist, prelude)
+    }
+
+    /// Loads an additional metadata layer with the given name from the given
+    /// path.
+    ///
+    /// If a layer with the given name has already been loaded, its data is
+    /// replaced and it keeps its position in the stack of layers, otherwise
+    /// the new layer is added above all other additional layers, but below
+    /// the userlist.
+    pub fn load_metadata_layer(
+        &mut self,
+        name: &str,
+        path: &Path,
+    ) -> Result<(), LoadMetadataError> {
+        self.load_layer(name, |document| document.load(path))
+    }
+
+    /// Loads an additional metadata layer with the given name from the given
+    /// string.
+    ///
+    /// If a layer with the given name has already been loaded, its data is
+    /// replaced and it keeps its position in the stack of layers, otherwise
+    /// the new layer is added above all other additional layers, but below
+    /// the userlist.
+    pub fn load_metadata_layer_from_str(
+        &mut self,
+        name: &str,
+        metadata: &str,
+    ) -> Result<(), LoadMetadataError> {
+        self.load_layer(name, |document| document.load_from_str(metadata))
+    }
+
+    fn load_layer(
+        &mut self,
+        name: &str,
+        load: impl FnOnce(&mut MetadataDocument) -> Result<(), LoadMetadataError>,
+    ) -> Result<(), LoadMetadataError> {
+        if let Some(layer) = self.layers.iter_mut().find(|l| &*l.name == name) {
+            return load(&mut layer.document);
+        }
+
+        let mut document = MetadataDocument::new(MetadataSource::Layer);
+        load(&mut document)?;
+
+        self.layers.push(MetadataLayer {
+            name: name.into(),
+            document,
+        });
+
+        Ok(())
+    }
+
+    /// Gets the names of the loaded additional metadata layers, from lowest to
+    /// highest precedence.
+    pub fn metadata_layers(&self) -> Vec<&str> {
+        self.layers.iter().map(|l| l.name.as_ref()).collect()
+    }
+
+    /// Get the names of the loaded additional metadata layers, from lowest to
+    /// highest precedence, in the form that [EdgeType][crate::EdgeType]
+    /// values use to identify them.
+    pub(crate) fn metadata_layer_names(&self) -> Vec<Arc<str>> {
+        self.layers.iter().map(|l| Arc::clone(&l.name)).collect()
+    }
+
+    /// Discards the additional metadata layer with the given name, if one is
+    /// loaded.
+    ///
+    /// Any layers above the discarded layer move down one position.
+    pub fn discard_metadata_layer(&mut self, name: &str) {
+        self.layers.retain(|l| &*l.name != name);
+    }
+
     /// Loads the userlist from the given path.
     ///
     /// Replaces any existing data that was previously loaded from a userlist.
@@ -87,8 +219,47 @@ impl Database {
         self.userlist.load(path)
     }
 
+    /// Loads the userlist from the given string.
+    ///
+    /// Replaces any existing data that was previously loaded from a userlist.
+    pub fn load_userlist_from_str(&mut self, userlist: &str) -> Result<(), LoadMetadataError> {
+        self.userlist.load_from_str(userlist)
+    }
+
+    /// Loads the userlist from the given reader.
+    ///
+    /// Replaces any existing data that was previously loaded from a userlist.
+    pub fn load_userlist_from_reader<R: Read>(
+        &mut self,
+        userlist: R,
+    ) -> Result<(), LoadMetadataError> {
+        self.userlist.load_from_reader(userlist)
+    }
+
+    /// Loads the userlist from the given JSON string. The JSON must have the
+    /// structure described in the [metadata module's][crate::metadata#json]
+    /// documentation.
+    ///
+    /// Replaces any existing data that was previously loaded from a userlist.
+    #[cfg(feature = "json")]
+    pub fn load_userlist_from_json(&mut self, userlist: &str) -> Result<(), LoadMetadataError> {
+        self.userlist.load_from_json_str(userlist)
+    }
+
+    /// Serialises all loaded user-added metadata as JSON, with the structure
+    /// described in the [metadata module's][crate::metadata#json]
+    /// documentation.
+    #[cfg(feature = "json")]
+    pub fn user_metadata_to_json(&self) -> Result<String, serde_json::Error> {
+        self.userlist.to_json()
+    }
+
     /// Writes a metadata file containing all loaded user-added metadata.
     ///
+    /// If a userlist was loaded, only the plugin and group entries that have
+    /// changed since it was loaded are rewritten, so the userlist's comments,
+    /// anchors and formatting are otherwise preserved.
+    ///
     /// If `output_path` already exists, it will be written if `overwrite` is
     /// `true`, otherwise no data will be written.
     pub fn write_user_metadata(
@@ -144,6 +315,9 @@ impl Database {
     /// Bash Tag suggestions can include Bash Tags not in this list.
     pub fn known_bash_tags(&self) -> Vec<String> {
         let mut tags = self.masterlist.bash_tags().to_vec();
+        for layer in &self.layers {
+            tags.extend_from_slice(layer.document.bash_tags());
+        }
         tags.extend_from_slice(self.userlist.bash_tags());
 
         tags
@@ -167,6 +341,7 @@ impl Database {
             .masterlist
             .messages()
             .iter()
+            .chain(self.layers.iter().flat_map(|l| l.document.messages()))
             .chain(self.userlist.messages());
 
         if evaluate_conditions {
@@ -186,13 +361,19 @@ impl Database {
     ///
     /// If `include_user_metadata` is `true`, any group metadata present in the
     /// userlist is included in the returned metadata, otherwise the metadata
-    /// returned only includes metadata from the masterlist.
+    /// returned only includes metadata from the masterlist and any additional
+    /// metadata layers.
     pub fn groups(&self, include_user_metadata: bool) -> Vec<Group> {
+        let mut groups = self.masterlist.groups().to_vec();
+        for layer in &self.layers {
+            groups = merge_groups(&groups, layer.document.groups());
+        }
+
         if include_user_metadata {
-            merge_groups(self.masterlist.groups(), self.userlist.groups())
-        } else {
-            self.masterlist.groups().to_vec()
+            groups = merge_groups(&groups, self.userlist.groups());
         }
+
+        groups
     }
 
     /// Gets the groups that are defined or extended in the loaded userlist.
@@ -220,18 +401,67 @@ impl Database {
         from_group_name: &str,
         to_group_name: &str,
     ) -> Result<Vec<Vertex>, GroupsPathError> {
-        let graph = build_groups_graph(self.masterlist.groups(), self.userlist.groups())?;
+        let graph = self.groups_graph(self.userlist.groups())?;
 
         let path = find_path(&graph, from_group_name, to_group_name)?;
 
         Ok(path)
     }
 
+    /// Build the groups graph from all loaded group metadata, using the given
+    /// groups in place of the userlist's groups.
+    pub(crate) fn groups_graph(
+        &self,
+        userlist_groups: &[Group],
+    ) -> Result<GroupsGraph, BuildGroupsGraphError> {
+        let layer_groups: Vec<_> = self
+            .layers
+            .iter()
+            .map(|l| (Arc::clone(&l.name), l.document.groups()))
+            .collect();
+
+        build_layered_groups_graph(self.masterlist.groups(), &layer_groups, userlist_groups)
+    }
+
+    /// Suggest metadata changes that would remove the given cycles.
+    ///
+    /// Each cycle is given as a path in the same form as
+    /// [SortPluginsError::CycleFound][crate::error::SortPluginsError::CycleFound],
+    /// and may be a cycle of plugins or of groups.
+    ///
+    /// Fixes remove the fewest edges needed to break all the cycles that can
+    /// be fixed. If there is more than one way to do that, edges are preferred
+    /// in the following order: user "load after" metadata, then user group
+    /// metadata, then user requirements, then the same for additional metadata
+    /// layers, then masterlist "load after" metadata, then masterlist
+    /// requirements. Finding the fewest edges takes time that grows
+    /// exponentially with the number of edges, so this can be slow if given
+    /// many cycles.
+    /// Other edges (e.g. those due to a plugin's masters) can't be removed by
+    /// changing metadata. If a cycle contains no edges that can be removed, no
+    /// fix is suggested for it. A group edge between two plugins is removed by
+    /// unsetting one of their groups, but only if that leaves no path between
+    /// their groups, as the default group may still load after the other
+    /// plugin's group.
+    ///
+    /// An edge is removed by changing each metadata entry that causes it. If
+    /// more than one entry causes an edge (e.g. a plugin's own entry and a
+    /// regex entry that matches it), a fix is suggested for each entry, and
+    /// all of them must be applied to remove the edge.
+    ///
+    /// Each suggested change includes the changes of any earlier fixes that
+    /// affect the same metadata entry or groups, so the changes can be applied
+    /// in the order that they are returned.
+    pub fn suggest_cycle_fixes(&self, cycles: &[Vec<Vertex>]) -> Vec<CycleFix> {
+        fixes::suggest_cycle_fixes(&self.masterlist, &self.layers, &self.userlist, cycles)
+    }
+
     /// Get all of a plugin's loaded metadata.
     ///
     /// If `include_user_metadata` is `true`, any user metadata the plugin has
     /// is included in the returned metadata, otherwise the metadata returned
-    /// only includes metadata from the masterlist.
+    /// only includes metadata from the masterlist and any additional metadata
+    /// layers.
     ///
     /// If `evaluateConditions` is `true`, any metadata conditions are evaluated
     /// before the metadata otherwise unevaluated metadata is returned.
@@ -243,14 +473,17 @@ impl Database {
         include_user_metadata: bool,
         evaluate_conditions: bool,
     ) -> Result<Option<PluginMetadata>, MetadataRetrievalError> {
-        let mut metadata = self.masterlist.find_plugin(plugin_name)?;
+        let documents = std::iter::once(&self.masterlist)
+            .chain(self.layers.iter().map(|l| &l.document))
+            .chain(include_user_metadata.then_some(&self.userlist));
 
-        if include_user_metadata {
-            if let Some(mut user_metadata) = self.userlist.find_plugin(plugin_name)? {
+        let mut metadata: Option<PluginMetadata> = None;
+        for document in documents {
+            if let Some(mut higher_metadata) = document.find_plugin(plugin_name)? {
                 if let Some(metadata) = metadata {
-                    user_metadata.merge_metadata(&metadata);
+                    higher_metadata.merge_metadata(&metadata);
                 }
-                metadata = Some(user_metadata);
+                metadata = Some(higher_metadata);
             }
         }
 
@@ -287,6 +520,79 @@ impl Database {
         Ok(metadata)
     }
 
+    /// Get a plugin's metadata loaded from the additional metadata layer with
+    /// the given name. If no layer with that name is loaded, `None` is
+    /// returned.
+    ///
+    /// If `evaluateConditions` is `true`, any metadata conditions are evaluated
+    /// before the metadata otherwise unevaluated metadata is returned.
+    /// Evaluating plugin metadata conditions does **not** clear the condition
+    /// cache.
+    pub fn plugin_layer_metadata(
+        &self,
+        layer_name: &str,
+        plugin_name: &str,
+        evaluate_conditions: bool,
+    ) -> Result<Option<PluginMetadata>, MetadataRetrievalError> {
+        let Some(layer) = self.layers.iter().find(|l| &*l.name == layer_name) else {
+            return Ok(None);
+        };
+
+        if evaluate_conditions {
+            self.plugin_metadata_from(&layer.document, plugin_name)
+        } else {
+            layer.document.find_plugin(plugin_name).map_err(Into::into)
+        }
+    }
+
+    /// Get a plugin's metadata loaded from the masterlist, with its conditions
+    /// evaluated.
+    pub(crate) fn masterlist_plugin_metadata(
+        &self,
+        plugin_name: &str,
+    ) -> Result<Option<PluginMetadata>, MetadataRetrievalError> {
+        self.plugin_metadata_from(&self.masterlist, plugin_name)
+    }
+
+    /// Get a plugin's metadata from each additional metadata layer that has
+    /// metadata for it, paired with the layer's name, with its conditions
+    /// evaluated. The metadata is given in increasing order of precedence.
+    pub(crate) fn plugin_layers_metadata(
+        &self,
+        plugin_name: &str,
+    ) -> Result<Vec<(Arc<str>, PluginMetadata)>, MetadataRetrievalError> {
+        let mut metadata = Vec::new();
+        for layer in &self.layers {
+            if let Some(m) = self.plugin_metadata_from(&layer.document, plugin_name)? {
+                metadata.push((Arc::clone(&layer.name), m));
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Get a copy of the loaded userlist with the given overlay's changes
+    /// applied.
+    pub(crate) fn overlaid_userlist(&self, overlay: &MetadataOverlay) -> MetadataDocument {
+        let mut userlist = self.userlist.clone();
+        overlay.apply_to(&mut userlist);
+        userlist
+    }
+
+    /// Get a plugin's metadata from the given metadata document, with its
+    /// conditions evaluated.
+    pub(crate) fn plugin_metadata_from(
+        &self,
+        document: &MetadataDocument,
+        plugin_name: &str,
+    ) -> Result<Option<PluginMetadata>, MetadataRetrievalError> {
+        match document.find_plugin(plugin_name)? {
+            Some(metadata) => evaluate_all_conditions(metadata, &self.condition_evaluator_state)
+                .map_err(Into::into),
+            None => Ok(None),
+        }
+    }
+
     /// Sets a plugin's user metadata, replacing any loaded user metadata for
     /// that plugin.
     pub fn set_plugin_user_metadata(&mut self, plugin_metadata: PluginMetadata) {
@@ -1069,6 +1375,137 @@ plugins:
         }
     }
 
+    mod metadata_layers {
+        use super::*;
+
+        fn layer_yaml(load_after: &str) -> String {
+            format!("plugins:\n  - name: {BLANK_ESM}\n    after: ['{load_after}']\n")
+        }
+
+        #[test]
+        fn load_metadata_layer_should_keep_the_position_of_a_reloaded_layer() {
+            let fixture = Fixture::new(GameType::Oblivion);
+            let mut database = fixture.database();
+
+            database
+                .load_metadata_layer_from_str("modpack", &layer_yaml("A.esp"))
+                .unwrap();
+            database
+                .load_metadata_layer("local", &fixture.metadata_path)
+                .unwrap();
+            database
+                .load_metadata_layer_from_str("modpack", &layer_yaml("B.esp"))
+                .unwrap();
+
+            assert_eq!(vec!["modpack", "local"], database.metadata_layers());
+            assert_eq!(
+                &[File::new("B.esp".into())],
+                database
+                    .plugin_layer_metadata("modpack", BLANK_ESM, false)
+                    .unwrap()
+                    .unwrap()
+                    .load_after_files()
+            );
+        }
+
+        #[test]
+        fn plugin_metadata_should_merge_layers_with_higher_layers_preferred() {
+            let fixture = Fixture::new(GameType::Oblivion);
+            let mut database = fixture.database();
+
+            database.load_masterlist(&fixture.metadata_path).unwrap();
+            database
+                .load_metadata_layer_from_str("modpack", &layer_yaml("A.esp"))
+                .unwrap();
+
+            let mut plugin = PluginMetadata::new(BLANK_ESM).unwrap();
+            plugin.set_load_after_files(vec![File::new(BLANK_DIFFERENT_ESM.into())]);
+            database.set_plugin_user_metadata(plugin);
+
+            assert_eq!(
+                &[
+                    File::new(BLANK_DIFFERENT_ESM.into()),
+                    File::new("A.esp".into()),
+                    File::new("Oblivion.esm".into())
+                ],
+                database
+                    .plugin_metadata(BLANK_ESM, true, false)
+                    .unwrap()
+                    .unwrap()
+                    .load_after_files()
+            );
+            assert_eq!(
+                &[File::new("A.esp".into()), File::new("Oblivion.esm".into())],
+                database
+                    .plugin_metadata(BLANK_ESM, false, false)
+                    .unwrap()
+                    .unwrap()
+                    .load_after_files()
+            );
+        }
+
+        #[test]
+        fn discard_metadata_layer_should_not_change_how_higher_layers_are_identified() {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let mut database = Database::new(loot_condition_interpreter::State::new(
+                GameType::Oblivion.into(),
+                tmp_dir.path().into(),
+            ));
+
+            database
+                .load_metadata_layer_from_str("modpack", &layer_yaml("A.esp"))
+                .unwrap();
+            database
+                .load_metadata_layer_from_str("local", &layer_yaml("B.esp"))
+                .unwrap();
+
+            let names: Vec<_> = database
+                .plugin_layers_metadata(BLANK_ESM)
+                .unwrap()
+                .into_iter()
+                .map(|(n, _)| n)
+                .collect();
+            assert_eq!(vec![Arc::from("modpack"), Arc::from("local")], names);
+
+            database.discard_metadata_layer("modpack");
+
+            let layers = database.plugin_layers_metadata(BLANK_ESM).unwrap();
+            assert_eq!(vec!["local"], database.metadata_layers());
+            assert_eq!(1, layers.len());
+            assert_eq!("local", &*layers[0].0);
+            assert_eq!(&[File::new("B.esp".into())], layers[0].1.load_after_files());
+        }
+
+        #[test]
+        fn discard_metadata_layer_should_remove_only_that_layers_metadata() {
+            let fixture = Fixture::new(GameType::Oblivion);
+            let mut database = fixture.database();
+
+            database.load_masterlist(&fixture.metadata_path).unwrap();
+            database
+                .load_metadata_layer_from_str("modpack", &layer_yaml("A.esp"))
+                .unwrap();
+
+            database.discard_metadata_layer("modpack");
+
+            assert!(database.metadata_layers().is_empty());
+            assert!(
+                database
+                    .plugin_layer_metadata("modpack", BLANK_ESM, false)
+                    .unwrap()
+                    .is_none()
+            );
+            assert_eq!(
+                &[File::new("Oblivion.esm".into())],
+                database
+                    .plugin_metadata(BLANK_ESM, false, false)
+                    .unwrap()
+                    .unwrap()
+                    .load_after_files()
+            );
+        }
+    }
+
     mod plugin_user_metadata {
         use super::*;
 
@@ -0,0 +1,123 @@
+use crate::metadata::{Group, PluginMetadata, metadata_document::MetadataDocument};
+
+/// A set of changes to user metadata that can be applied temporarily, e.g. to
+/// preview the effect that a change would have on sorting before it is made.
+///
+/// The changes are applied in the same way as the equivalent [Database]
+/// functions would apply them: discarded plugin metadata is discarded first,
+/// then plugin metadata is set, replacing any existing user metadata for the
+/// same plugins, then the user groups are replaced if they have been set.
+///
+/// [Database]: crate::Database
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct MetadataOverlay {
+    discarded_plugins: Vec<String>,
+    plugins: Vec<PluginMetadata>,
+    groups: Option<Vec<Group>>,
+}
+
+impl MetadataOverlay {
+    /// Create an overlay that makes no changes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set user metadata for a plugin, as
+    /// [Database::set_plugin_user_metadata][crate::Database::set_plugin_user_metadata]
+    /// would.
+    #[must_use]
+    pub fn with_plugin_user_metadata(mut self, plugin_metadata: PluginMetadata) -> Self {
+        self.plugins.push(plugin_metadata);
+        self
+    }
+
+    /// Discard the user metadata for the plugin with the given filename, as
+    /// [Database::discard_plugin_user_metadata][crate::Database::discard_plugin_user_metadata]
+    /// would.
+    #[must_use]
+    pub fn with_discarded_plugin_user_metadata(mut self, plugin: String) -> Self {
+        self.discarded_plugins.push(plugin);
+        self
+    }
+
+    /// Replace the user group definitions, as
+    /// [Database::set_user_groups][crate::Database::set_user_groups] would.
+    #[must_use]
+    pub fn with_user_groups(mut self, groups: Vec<Group>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// Get the plugin user metadata that the overlay sets.
+    pub fn plugin_user_metadata(&self) -> &[PluginMetadata] {
+        &self.plugins
+    }
+
+    /// Get the filenames of the plugins that the overlay discards user
+    /// metadata for.
+    pub fn discarded_plugin_user_metadata(&self) -> &[String] {
+        &self.discarded_plugins
+    }
+
+    /// Get the user group definitions that the overlay sets, if any.
+    pub fn user_groups(&self) -> Option<&[Group]> {
+        self.groups.as_deref()
+    }
+
+    pub(crate) fn apply_to(&self, userlist: &mut MetadataDocument) {
+        for plugin in &self.discarded_plugins {
+            userlist.remove_plugin_metadata(plugin);
+        }
+
+        for plugin in &self.plugins {
+            userlist.set_plugin_metadata(plugin.clone());
+        }
+
+        if let Some(groups) = &self.groups {
+            userlist.set_groups(groups.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_to_should_replace_plugin_metadata_and_groups() {
+        let mut userlist = MetadataDocument::default();
+        let mut metadata = PluginMetadata::new("A.esp").unwrap();
+        metadata.set_group("old".into());
+        userlist.set_plugin_metadata(metadata);
+
+        let mut metadata = PluginMetadata::new("A.esp").unwrap();
+        metadata.set_group("new".into());
+        let overlay = MetadataOverlay::new()
+            .with_plugin_user_metadata(metadata)
+            .with_user_groups(vec![Group::new("new".into())]);
+
+        overlay.apply_to(&mut userlist);
+
+        let plugin = userlist.find_plugin("A.esp").unwrap().unwrap();
+        assert_eq!(Some("new"), plugin.group());
+        assert_eq!(
+            &[Group::default(), Group::new("new".into())],
+            userlist.groups()
+        );
+    }
+
+    #[test]
+    fn apply_to_should_discard_plugin_metadata_before_setting_it() {
+        let mut userlist = MetadataDocument::default();
+        let mut metadata = PluginMetadata::new("A.esp").unwrap();
+        metadata.set_group("old".into());
+        userlist.set_plugin_metadata(metadata);
+
+        let overlay = MetadataOverlay::new().with_discarded_plugin_user_metadata("A.esp".into());
+
+        overlay.apply_to(&mut userlist);
+
+        assert!(userlist.find_plugin("A.esp").unwrap().is_none());
+    }
+}
@@ -0,0 +1,401 @@
+use std::ops::RangeInclusive;
+
+use crate::{plugin::Plugin, plugin::error::PluginDataError};
+
+/// Whether a plugin could be given a flag that reduces the range of FormIDs
+/// that its new records can use.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum FlagEligibility {
+    /// The game does not support plugins with the flag.
+    Unsupported,
+    /// All the plugin's new records have FormIDs in the range that the flag
+    /// allows.
+    Eligible,
+    /// The given raw FormIDs of new records are outside the range that the
+    /// flag allows.
+    FormIdsOutOfRange(Vec<u32>),
+}
+
+impl FlagEligibility {
+    /// Check if the plugin could be given the flag.
+    pub fn is_eligible(&self) -> bool {
+        *self == FlagEligibility::Eligible
+    }
+}
+
+/// How a loaded plugin's records affect whether it could be a light or medium
+/// plugin, as found by
+/// [Game::plugin_eligibility][crate::Game::plugin_eligibility].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PluginEligibility {
+    plugin_name: String,
+    is_light_plugin: bool,
+    is_medium_plugin: bool,
+    new_record_count: usize,
+    override_record_counts: Vec<(String, usize)>,
+    light: FlagEligibility,
+    medium: FlagEligibility,
+}
+
+impl PluginEligibility {
+    /// Get the name of the plugin.
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin_name
+    }
+
+    /// Check if the plugin is already a light plugin.
+    pub fn is_light_plugin(&self) -> bool {
+        self.is_light_plugin
+    }
+
+    /// Check if the plugin is already a medium plugin.
+    pub fn is_medium_plugin(&self) -> bool {
+        self.is_medium_plugin
+    }
+
+    /// Get the number of records that the plugin adds, i.e. that don't
+    /// override records from its masters.
+    pub fn new_record_count(&self) -> usize {
+        self.new_record_count
+    }
+
+    /// Get the plugin's masters paired with the number of their records that
+    /// the plugin overrides, in the order that the masters are listed in the
+    /// plugin's header.
+    pub fn override_record_counts(&self) -> &[(String, usize)] {
+        &self.override_record_counts
+    }
+
+    /// Get whether the plugin could be a light plugin.
+    pub fn light(&self) -> &FlagEligibility {
+        &self.light
+    }
+
+    /// Get whether the plugin could be a medium plugin.
+    pub fn medium(&self) -> &FlagEligibility {
+        &self.medium
+    }
+}
+
+/// The light and medium plugin eligibility of all loaded plugins, as created
+/// by [Game::eligibility_report][crate::Game::eligibility_report]. Plugins are
+/// listed in load order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct EligibilityReport {
+    plugins: Vec<PluginEligibility>,
+}
+
+impl EligibilityReport {
+    pub(crate) fn new(plugins: Vec<PluginEligibility>) -> Self {
+        Self { plugins }
+    }
+
+    /// Get the eligibility of each plugin in the report.
+    pub fn plugins(&self) -> &[PluginEligibility] {
+        &self.plugins
+    }
+
+    /// Get the names of plugins that are not light plugins but could be.
+    pub fn light_plugin_candidates(&self) -> impl Iterator<Item = &str> {
+        self.plugins
+            .iter()
+            .filter(|p| !p.is_light_plugin && p.light.is_eligible())
+            .map(PluginEligibility::plugin_name)
+    }
+
+    /// Get the names of plugins that are neither light nor medium plugins but
+    /// could be medium plugins.
+    pub fn medium_plugin_candidates(&self) -> impl Iterator<Item = &str> {
+        self.plugins
+            .iter()
+            .filter(|p| !p.is_light_plugin && !p.is_medium_plugin && p.medium.is_eligible())
+            .map(PluginEligibility::plugin_name)
+    }
+}
+
+/// The number of records that a plugin can contain, which decides how much of
+/// a FormID is its object index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum PluginScale {
+    Full,
+    Medium,
+    Small,
+}
+
+impl PluginScale {
+    pub(crate) fn of(plugin: &Plugin) -> Self {
+        if plugin.is_light_plugin() {
+            PluginScale::Small
+        } else if plugin.is_medium_plugin() {
+            PluginScale::Medium
+        } else {
+            PluginScale::Full
+        }
+    }
+
+    fn object_index_mask(self) -> u32 {
+        match self {
+            PluginScale::Full => 0x00FF_FFFF,
+            PluginScale::Medium => 0x0000_FFFF,
+            PluginScale::Small => 0x0000_0FFF,
+        }
+    }
+}
+
+/// A source of records that a FormID could belong to, identified by the bits
+/// of the FormID that aren't its object index.
+struct FormIdSource {
+    mod_index: u32,
+    object_index_mask: u32,
+}
+
+impl FormIdSource {
+    fn contains(&self, form_id: u32) -> bool {
+        form_id & !self.object_index_mask == self.mod_index
+    }
+}
+
+/// Get the eligibility of a loaded plugin, using `master_scale` to get the
+/// scales of its masters, which are needed to tell which of a Starfield
+/// plugin's records are new.
+pub(crate) fn plugin_eligibility(
+    plugin: &Plugin,
+    master_scale: impl Fn(&str) -> Option<PluginScale>,
+) -> Result<PluginEligibility, PluginDataError> {
+    let game_id = esplugin::GameId::from(plugin.game_type());
+    let masters = plugin.masters()?;
+
+    let master_scales = if game_id == esplugin::GameId::Starfield {
+        masters
+            .iter()
+            .map(|m| {
+                master_scale(m)
+                    .ok_or_else(|| esplugin::Error::PluginMetadataNotFound(m.clone()).into())
+            })
+            .collect::<Result<Vec<_>, PluginDataError>>()?
+    } else {
+        vec![PluginScale::Full; masters.len()]
+    };
+
+    let parent_scale = if game_id == esplugin::GameId::Starfield {
+        PluginScale::of(plugin)
+    } else {
+        PluginScale::Full
+    };
+
+    let records = classify_form_ids(plugin.form_ids()?, &master_scales, parent_scale);
+
+    let light = flag_eligibility(
+        light_object_index_range(game_id, plugin.header_version()),
+        &records.new_records,
+    );
+    let medium = flag_eligibility(medium_object_index_range(game_id), &records.new_records);
+
+    Ok(PluginEligibility {
+        plugin_name: plugin.name().to_owned(),
+        is_light_plugin: plugin.is_light_plugin(),
+        is_medium_plugin: plugin.is_medium_plugin(),
+        new_record_count: records.new_records.len(),
+        override_record_counts: masters.into_iter().zip(records.override_counts).collect(),
+        light,
+        medium,
+    })
+}
+
+struct ClassifiedFormIds {
+    /// Pairs of raw FormIDs and object indexes.
+    new_records: Vec<(u32, u32)>,
+    /// The number of records overridden from each master, in master order.
+    override_counts: Vec<usize>,
+}
+
+/// Sort a plugin's raw FormIDs into new records and overrides of its masters'
+/// records, in the same way as esplugin.
+fn classify_form_ids(
+    form_ids: &[u32],
+    master_scales: &[PluginScale],
+    parent_scale: PluginScale,
+) -> ClassifiedFormIds {
+    let mut full_mod_index = 0;
+    let mut medium_mod_index = 0xFD00_0000;
+    let mut small_mod_index = 0xFE00_0000;
+
+    let sources: Vec<_> = master_scales
+        .iter()
+        .map(|scale| {
+            let mod_index = match scale {
+                PluginScale::Full => &mut full_mod_index,
+                PluginScale::Medium => &mut medium_mod_index,
+                PluginScale::Small => &mut small_mod_index,
+            };
+            let source = FormIdSource {
+                mod_index: *mod_index,
+                object_index_mask: scale.object_index_mask(),
+            };
+            *mod_index = mod_index.saturating_add(scale.object_index_mask() + 1);
+            source
+        })
+        .collect();
+
+    let mut new_records = Vec::new();
+    let mut override_counts = vec![0; sources.len()];
+    for form_id in form_ids {
+        let master_index = sources.iter().position(|s| s.contains(*form_id));
+        match master_index.and_then(|i| override_counts.get_mut(i)) {
+            Some(count) => *count += 1,
+            None => new_records.push((*form_id, form_id & parent_scale.object_index_mask())),
+        }
+    }
+
+    ClassifiedFormIds {
+        new_records,
+        override_counts,
+    }
+}
+
+fn flag_eligibility(
+    valid_range: Option<RangeInclusive<u32>>,
+    new_records: &[(u32, u32)],
+) -> FlagEligibility {
+    let Some(valid_range) = valid_range else {
+        return FlagEligibility::Unsupported;
+    };
+
+    let out_of_range: Vec<_> = new_records
+        .iter()
+        .filter(|(_, object_index)| !valid_range.contains(object_index))
+        .map(|(form_id, _)| *form_id)
+        .collect();
+
+    if out_of_range.is_empty() {
+        FlagEligibility::Eligible
+    } else {
+        FlagEligibility::FormIdsOutOfRange(out_of_range)
+    }
+}
+
+/// Get the range of object indexes that a light plugin's new records can use,
+/// which is the same as esplugin uses.
+fn light_object_index_range(
+    game_id: esplugin::GameId,
+    header_version: Option<f32>,
+) -> Option<RangeInclusive<u32>> {
+    match game_id {
+        esplugin::GameId::SkyrimSE => match header_version {
+            Some(v) if v < 1.71 => Some(0x800..=0xFFF),
+            Some(_) => Some(0..=0xFFF),
+            None => Some(0..=0),
+        },
+        esplugin::GameId::Fallout4 => match header_version {
+            Some(v) if v < 1.0 => Some(0x800..=0xFFF),
+            Some(_) => Some(0x001..=0xFFF),
+            None => Some(0..=0),
+        },
+        esplugin::GameId::Starfield => Some(0..=0xFFF),
+        _ => None,
+    }
+}
+
+fn medium_object_index_range(game_id: esplugin::GameId) -> Option<RangeInclusive<u32>> {
+    match game_id {
+        esplugin::GameId::Starfield => Some(0..=0xFFFF),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod classify_form_ids {
+        use super::*;
+
+        #[test]
+        fn should_count_form_ids_with_a_master_mod_index_as_overrides() {
+            let form_ids = [0x0000_0001, 0x0000_0002, 0x0100_0003, 0x0200_0004];
+            let masters = [PluginScale::Full, PluginScale::Full];
+
+            let records = classify_form_ids(&form_ids, &masters, PluginScale::Full);
+
+            assert_eq!(vec![2, 1], records.override_counts);
+            assert_eq!(vec![(0x0200_0004, 0x4)], records.new_records);
+        }
+
+        #[test]
+        fn should_use_master_scales_to_find_overrides() {
+            let form_ids = [0x0000_0001, 0xFD00_0002, 0xFE00_1003, 0xFE00_2004];
+            let masters = [PluginScale::Full, PluginScale::Small, PluginScale::Medium];
+
+            let records = classify_form_ids(&form_ids, &masters, PluginScale::Full);
+
+            assert_eq!(vec![1, 0, 1], records.override_counts);
+            assert_eq!(
+                vec![(0xFE00_1003, 0x1003), (0xFE00_2004, 0x2004)],
+                records.new_records
+            );
+        }
+
+        #[test]
+        fn should_mask_new_records_object_indexes_using_the_parent_scale() {
+            let records = classify_form_ids(&[0x0100_1801], &[], PluginScale::Small);
+
+            assert_eq!(vec![(0x0100_1801, 0x801)], records.new_records);
+        }
+    }
+
+    mod flag_eligibility {
+        use super::*;
+
+        #[test]
+        fn should_be_unsupported_if_there_is_no_valid_range() {
+            assert_eq!(
+                FlagEligibility::Unsupported,
+                flag_eligibility(None, &[(0x0100_0001, 0x1)])
+            );
+        }
+
+        #[test]
+        fn should_list_form_ids_with_object_indexes_outside_the_valid_range() {
+            let new_records = [
+                (0x0100_0800, 0x800),
+                (0x0100_1000, 0x1000),
+                (0x0100_0001, 0x1),
+            ];
+
+            let eligibility = flag_eligibility(Some(0x800..=0xFFF), &new_records);
+
+            assert_eq!(
+                FlagEligibility::FormIdsOutOfRange(vec![0x0100_1000, 0x0100_0001]),
+                eligibility
+            );
+            assert!(!eligibility.is_eligible());
+        }
+
+        #[test]
+        fn should_be_eligible_if_all_object_indexes_are_in_the_valid_range() {
+            let eligibility = flag_eligibility(Some(0..=0xFFF), &[(0x0100_0800, 0x800)]);
+
+            assert!(eligibility.is_eligible());
+        }
+    }
+
+    #[test]
+    fn light_object_index_range_should_depend_on_the_game_and_header_version() {
+        use esplugin::GameId;
+
+        assert_eq!(
+            Some(0x800..=0xFFF),
+            light_object_index_range(GameId::SkyrimSE, Some(1.7))
+        );
+        assert_eq!(
+            Some(0..=0xFFF),
+            light_object_index_range(GameId::SkyrimSE, Some(1.71))
+        );
+        assert_eq!(
+            Some(0x001..=0xFFF),
+            light_object_index_range(GameId::Fallout4, Some(1.0))
+        );
+        assert_eq!(None, light_object_index_range(GameId::Skyrim, Some(1.7)));
+    }
+}
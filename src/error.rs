@@ -4,7 +4,7 @@ use std::path::PathBuf;
 pub use crate::database::{ConditionEvaluationError, MetadataRetrievalError};
 pub use crate::plugin::error::PluginDataError;
 use crate::plugin::error::PluginValidationError;
-pub use crate::sorting::error::GroupsPathError;
+pub use crate::sorting::error::{CyclicComponent, GroupsPathError, PinError};
 
 use crate::sorting::error::{
     BuildGroupsGraphError, PluginGraphValidationError, SortingError, display_cycle,
@@ -200,7 +200,11 @@ pub enum SortPluginsError {
     MetadataRetrievalError(MetadataRetrievalError),
     UndefinedGroup(String),
     CycleFound(Vec<Vertex>),
+    CyclesFound(Vec<CyclicComponent>),
     CycleFoundInvolving(String),
+    NonUniqueOrder(String, String),
+    InvalidPin(PinError),
+    Cancelled,
     PluginDataError(PluginDataError),
     PathfindingError(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
@@ -212,7 +216,21 @@ impl std::fmt::Display for SortPluginsError {
             Self::PluginNotLoaded(n) => write!(f, "the plugin \"{n}\" has not been loaded"),
             Self::UndefinedGroup(g) => write!(f, "the group \"{g}\" does not exist"),
             Self::CycleFound(c) => write!(f, "found a cycle: {}", display_cycle(c)),
+            Self::CyclesFound(c) => write!(
+                f,
+                "found cycles: {}",
+                c.iter()
+                    .map(|c| display_cycle(c.cycle()))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
             Self::CycleFoundInvolving(n) => write!(f, "found a cycle involving \"{n}\""),
+            Self::NonUniqueOrder(a, b) => write!(
+                f,
+                "the sorted load order is not unique: no edge exists between \"{a}\" and \"{b}\""
+            ),
+            Self::InvalidPin(_) => write!(f, "a pin could not be applied"),
+            Self::Cancelled => write!(f, "sorting was cancelled"),
             Self::PluginDataError(_) => write!(f, "failed to read loaded plugin data"),
             Self::MetadataRetrievalError(_) => write!(f, "failed to retrieve plugin metadata"),
             Self::PathfindingError(_) => write!(f, "failed to find a path in the plugins graph"),
@@ -224,6 +242,7 @@ impl std::error::Error for SortPluginsError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::MetadataRetrievalError(e) => Some(e),
+            Self::InvalidPin(e) => Some(e),
             Self::PluginDataError(e) => Some(e),
             Self::PathfindingError(e) => Some(e.as_ref()),
             _ => None,
@@ -246,7 +265,11 @@ impl From<SortingError> for SortPluginsError {
             },
             SortingError::UndefinedGroup(g) => Self::UndefinedGroup(g.into_group_name()),
             SortingError::CycleFound(c) => Self::CycleFound(c.into_cycle()),
+            SortingError::CyclesFound(c) => Self::CyclesFound(c),
             SortingError::CycleInvolving(n) => Self::CycleFoundInvolving(n),
+            SortingError::NonUniqueOrder(a, b) => Self::NonUniqueOrder(a, b),
+            SortingError::InvalidPin(e) => Self::InvalidPin(e),
+            SortingError::Cancelled => Self::Cancelled,
             SortingError::PluginDataError(e) => Self::PluginDataError(e),
             SortingError::PathfindingError(e) => Self::PathfindingError(Box::new(e)),
         }
@@ -281,3 +304,110 @@ impl From<ConditionEvaluationError> for SortPluginsError {
         ))
     }
 }
+
+/// Represents an error that occurred while creating a report about the loaded
+/// plugins.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PluginReportError {
+    DatabaseLockPoisoned,
+    PluginNotLoaded(String),
+    MetadataRetrievalError(MetadataRetrievalError),
+    PluginDataError(PluginDataError),
+}
+
+impl std::fmt::Display for PluginReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DatabaseLockPoisoned => DatabaseLockPoisonError.fmt(f),
+            Self::PluginNotLoaded(n) => write!(f, "the plugin \"{n}\" has not been loaded"),
+            Self::MetadataRetrievalError(_) => write!(f, "failed to retrieve plugin metadata"),
+            Self::PluginDataError(_) => write!(f, "failed to read loaded plugin data"),
+        }
+    }
+}
+
+impl std::error::Error for PluginReportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::DatabaseLockPoisoned | Self::PluginNotLoaded(_) => None,
+            Self::MetadataRetrievalError(e) => Some(e),
+            Self::PluginDataError(e) => Some(e),
+        }
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for PluginReportError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        PluginReportError::DatabaseLockPoisoned
+    }
+}
+
+impl From<MetadataRetrievalError> for PluginReportError {
+    fn from(value: MetadataRetrievalError) -> Self {
+        PluginReportError::MetadataRetrievalError(value)
+    }
+}
+
+impl From<PluginDataError> for PluginReportError {
+    fn from(value: PluginDataError) -> Self {
+        PluginReportError::PluginDataError(value)
+    }
+}
+
+/// Represents an error that occurred while checking a plugin for problems.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CheckPluginError {
+    DatabaseLockPoisoned,
+    PluginNotLoaded(String),
+    MetadataRetrievalError(MetadataRetrievalError),
+    PluginDataError(PluginDataError),
+}
+
+impl std::fmt::Display for CheckPluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DatabaseLockPoisoned => DatabaseLockPoisonError.fmt(f),
+            Self::PluginNotLoaded(n) => write!(f, "the plugin \"{n}\" has not been loaded"),
+            Self::MetadataRetrievalError(_) => write!(f, "failed to retrieve plugin metadata"),
+            Self::PluginDataError(_) => write!(f, "failed to read loaded plugin data"),
+        }
+    }
+}
+
+impl std::error::Error for CheckPluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MetadataRetrievalError(e) => Some(e),
+            Self::PluginDataError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for CheckPluginError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        CheckPluginError::DatabaseLockPoisoned
+    }
+}
+
+impl From<MetadataRetrievalError> for CheckPluginError {
+    fn from(value: MetadataRetrievalError) -> Self {
+        CheckPluginError::MetadataRetrievalError(value)
+    }
+}
+
+impl From<ConditionEvaluationError> for CheckPluginError {
+    fn from(value: ConditionEvaluationError) -> Self {
+        CheckPluginError::MetadataRetrievalError(MetadataRetrievalError::ConditionEvaluationError(
+            value,
+        ))
+    }
+}
+
+impl From<PluginDataError> for CheckPluginError {
+    fn from(value: PluginDataError) -> Self {
+        CheckPluginError::PluginDataError(value)
+    }
+}
@@ -10,25 +10,36 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
     LogLevel,
-    database::Database,
+    bash_tags::{BashTagReport, BashTagSource, resolve_bash_tags},
+    cleaning::{CleaningReport, cleaning_report_entries},
+    database::{Database, MetadataOverlay},
+    eligibility::{EligibilityReport, PluginEligibility, PluginScale, plugin_eligibility},
     error::{
-        DatabaseLockPoisonError, GameHandleCreationError, LoadOrderError, LoadOrderStateError,
-        LoadPluginsError, SortPluginsError,
+        CheckPluginError, DatabaseLockPoisonError, GameHandleCreationError, LoadOrderError,
+        LoadOrderStateError, LoadPluginsError, PluginReportError, SortPluginsError,
     },
     escape_ascii,
+    health::{InstallState, PluginProblem, check_plugin},
     logging::{self, format_details, is_log_enabled},
     metadata::{
         Filename,
+        metadata_document::MetadataDocument,
         plugin_metadata::{GHOST_FILE_EXTENSION, iends_with_ascii},
     },
     plugin::{
         LoadScope, Plugin,
         error::{InvalidFilenameReason, PluginValidationError},
-        plugins_metadata, validate_plugin_path_and_header,
+        has_ascii_extension, has_plugin_file_extension, plugins_metadata,
+        validate_plugin_path_and_header,
     },
+    slots::{LoadOrderSlots, load_order_slots},
     sorting::{
-        groups::build_groups_graph,
-        plugins::{PluginSortingData, sort_plugins},
+        export::SortingGraph,
+        groups::GroupsGraph,
+        options::SortOptions,
+        plugins::{PluginSortingData, build_plugins_graph, sort_plugins},
+        report::SortReport,
+        session::{OverlapCache, SortingSession},
     },
 };
 
@@ -448,23 +459,85 @@ impl Game {
     /// their current load order. All given plugins must have been already been
     /// loaded using [Game::load_plugins] or [Game::load_plugin_headers].
     pub fn sort_plugins(&self, plugin_names: &[&str]) -> Result<Vec<String>, SortPluginsError> {
-        let plugins = plugin_names
-            .iter()
-            .map(|n| {
-                self.cache
-                    .plugin(n)
-                    .ok_or_else(|| SortPluginsError::PluginNotLoaded((*n).to_owned()))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        self.sort_plugins_with_report(plugin_names)
+            .map(SortReport::into_load_order)
+    }
 
-        let database = self.database.read()?;
+    /// Sort the given plugins, returning their sorted load order along with
+    /// the interactions that decided each plugin's position in it.
+    ///
+    /// This behaves in the same way as [Game::sort_plugins], but the returned
+    /// report also gives the path between each pair of adjacent plugins in the
+    /// sorted load order, and the path that caused each plugin that now loads
+    /// earlier than it did in the current load order to move.
+    pub fn sort_plugins_with_report(
+        &self,
+        plugin_names: &[&str],
+    ) -> Result<SortReport, SortPluginsError> {
+        self.sort_plugins_common(plugin_names, &SortOptions::default(), None, None)
+    }
 
-        let plugins_sorting_data = plugins
-            .into_iter()
-            .enumerate()
-            .map(|(i, p)| to_plugin_sorting_data(&database, p, i))
-            .collect::<Result<Vec<_>, _>>()?;
+    /// Sort the given plugins using the given options, returning their sorted
+    /// load order along with the interactions that decided each plugin's
+    /// position in it.
+    ///
+    /// This behaves in the same way as [Game::sort_plugins_with_report], except
+    /// as configured by the given options.
+    pub fn sort_plugins_with_options(
+        &self,
+        plugin_names: &[&str],
+        options: &SortOptions,
+    ) -> Result<SortReport, SortPluginsError> {
+        self.sort_plugins_common(plugin_names, options, None, None)
+    }
 
+    /// Sort the given plugins as if the given changes had been made to the
+    /// loaded user metadata.
+    ///
+    /// This behaves in the same way as [Game::sort_plugins_with_options],
+    /// except that the overlay's changes are applied on top of the loaded
+    /// userlist. The game's database is not changed, and is only read from
+    /// while sorting, so this can be used to preview the effect of a change to
+    /// user metadata before making it. Any cycles that the change would cause
+    /// are returned as errors in the same way as when sorting normally.
+    pub fn sort_plugins_with_overlay(
+        &self,
+        plugin_names: &[&str],
+        overlay: &MetadataOverlay,
+        options: &SortOptions,
+    ) -> Result<SortReport, SortPluginsError> {
+        self.sort_plugins_common(plugin_names, options, Some(overlay), None)
+    }
+
+    /// Sort the given plugins, reusing and updating state cached in the
+    /// given session.
+    ///
+    /// This behaves in the same way as [Game::sort_plugins_with_options], but
+    /// the session's cached plugin overlap results are used instead of
+    /// checking the overlap of plugins that haven't changed since the last
+    /// sort that used the same session. This makes re-sorting after a change
+    /// to a small number of plugins much faster.
+    pub fn sort_plugins_in_session(
+        &self,
+        session: &mut SortingSession,
+        plugin_names: &[&str],
+        options: &SortOptions,
+    ) -> Result<SortReport, SortPluginsError> {
+        self.sort_plugins_common(
+            plugin_names,
+            options,
+            None,
+            Some(session.overlap_cache_mut()),
+        )
+    }
+
+    fn sort_plugins_common(
+        &self,
+        plugin_names: &[&str],
+        options: &SortOptions,
+        overlay: Option<&MetadataOverlay>,
+        overlap_cache: Option<&mut OverlapCache>,
+    ) -> Result<SortReport, SortPluginsError> {
         if is_log_enabled(LogLevel::Debug) {
             logging::debug!("Current load order:");
             for plugin_name in plugin_names {
@@ -472,22 +545,87 @@ impl Game {
             }
         }
 
-        let groups_graph = build_groups_graph(&database.groups(false), database.user_groups())?;
+        let (plugins_sorting_data, groups_graph, metadata_layers) =
+            self.sorting_data(plugin_names, overlay)?;
 
-        let new_load_order = sort_plugins(
+        let report = sort_plugins(
             plugins_sorting_data,
             &groups_graph,
+            &metadata_layers,
             self.load_order.game_settings().early_loading_plugins(),
+            options,
+            overlap_cache,
         )?;
 
         if is_log_enabled(LogLevel::Debug) {
             logging::debug!("Sorted load order:");
-            for plugin_name in &new_load_order {
+            for plugin_name in report.load_order() {
                 logging::debug!("\t{plugin_name}");
             }
         }
 
-        Ok(new_load_order)
+        Ok(report)
+    }
+
+    /// Build the graph of plugins and their interactions that would be used to
+    /// sort the given plugins.
+    ///
+    /// The graph can be serialised to help debug metadata, e.g. to see why a
+    /// cyclic interaction occurs. Unlike [Game::sort_plugins], no error occurs
+    /// if the graph contains a cycle: instead the graph will only contain the
+    /// edges that were added before the cycle was detected.
+    ///
+    /// The order in which plugins are listed in `plugin_names` is used as
+    /// their current load order. All given plugins must have been already been
+    /// loaded using [Game::load_plugins] or [Game::load_plugin_headers].
+    pub fn sorting_graph(&self, plugin_names: &[&str]) -> Result<SortingGraph, SortPluginsError> {
+        let (plugins_sorting_data, groups_graph, metadata_layers) =
+            self.sorting_data(plugin_names, None)?;
+
+        build_plugins_graph(
+            plugins_sorting_data,
+            &groups_graph,
+            &metadata_layers,
+            self.load_order.game_settings().early_loading_plugins(),
+        )
+        .map_err(Into::into)
+    }
+
+    fn sorting_data(
+        &self,
+        plugin_names: &[&str],
+        overlay: Option<&MetadataOverlay>,
+    ) -> Result<SortingData<'_>, SortPluginsError> {
+        let plugins = plugin_names
+            .iter()
+            .map(|n| {
+                self.cache
+                    .plugin(n)
+                    .ok_or_else(|| SortPluginsError::PluginNotLoaded((*n).to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let database = self.database.read()?;
+
+        let overlaid_userlist = overlay.map(|o| database.overlaid_userlist(o));
+
+        let plugins_sorting_data = plugins
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| to_plugin_sorting_data(&database, overlaid_userlist.as_ref(), p, i))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let user_groups = overlaid_userlist
+            .as_ref()
+            .map_or_else(|| database.user_groups(), MetadataDocument::groups);
+
+        let groups_graph = database.groups_graph(user_groups)?;
+
+        Ok((
+            plugins_sorting_data,
+            groups_graph,
+            database.metadata_layer_names(),
+        ))
     }
 
     /// Load the current load order state, discarding any previously held state.
@@ -547,6 +685,279 @@ impl Game {
         self.load_order.save()?;
         Ok(())
     }
+
+    /// Create a report of which loaded plugins are dirty and which have been
+    /// verified as clean.
+    ///
+    /// The report is created from all loaded metadata after evaluating its
+    /// conditions, so only cleaning data that matches each plugin's current
+    /// CRC is included. Detail text is selected for the given language using
+    /// [select_message_content][crate::metadata::select_message_content].
+    pub fn cleaning_report(&self, language: &str) -> Result<CleaningReport, PluginReportError> {
+        let database = self.database.read()?;
+
+        let mut entries = Vec::new();
+        for plugin in self.loaded_plugins_in_load_order() {
+            if let Some(metadata) = database.plugin_metadata(plugin.name(), true, true)? {
+                entries.extend(cleaning_report_entries(plugin.name(), &metadata, language));
+            }
+        }
+
+        Ok(CleaningReport::new(entries))
+    }
+
+    /// Resolve the Bash Tags of all loaded plugins that have any Bash Tag
+    /// suggestions.
+    ///
+    /// Each plugin's tags are resolved from the tags listed in its
+    /// description, then the tag suggestions in the masterlist, any
+    /// additional metadata layers and the userlist, in that order of
+    /// increasing precedence. Metadata conditions are evaluated before the
+    /// suggestions are used.
+    pub fn bash_tag_report(&self) -> Result<BashTagReport, PluginReportError> {
+        let database = self.database.read()?;
+
+        let mut plugins = Vec::new();
+        for plugin in self.loaded_plugins_in_load_order() {
+            let masterlist = database.masterlist_plugin_metadata(plugin.name())?;
+            let layers = database.plugin_layers_metadata(plugin.name())?;
+            let userlist = database.plugin_user_metadata(plugin.name(), true)?;
+
+            let masterlist_tags = masterlist
+                .as_ref()
+                .map(|m| (BashTagSource::Masterlist, m.tags()));
+            let layers_tags = layers
+                .iter()
+                .map(|(n, m)| (BashTagSource::Layer(n.to_string()), m.tags()));
+            let userlist_tags = userlist
+                .as_ref()
+                .map(|m| (BashTagSource::Userlist, m.tags()));
+
+            let tags = resolve_bash_tags(
+                plugin.name(),
+                plugin.bash_tags(),
+                masterlist_tags
+                    .into_iter()
+                    .chain(layers_tags)
+                    .chain(userlist_tags),
+            );
+
+            if !tags.tags().is_empty() {
+                plugins.push(tags);
+            }
+        }
+
+        Ok(BashTagReport::new(plugins))
+    }
+
+    /// Find out whether a loaded plugin could be a light or medium plugin,
+    /// and if not, which of its new records have FormIDs that are out of
+    /// range.
+    ///
+    /// The whole plugin must have been loaded, as the FormIDs of its records
+    /// are needed, so an error is returned if only its header was loaded or
+    /// if it's a Morrowind or OpenMW plugin, as they don't use FormIDs. For
+    /// Starfield, all the plugin's masters must also be loaded, as their
+    /// scales affect which of the plugin's records are new.
+    pub fn plugin_eligibility(
+        &self,
+        plugin_name: &str,
+    ) -> Result<PluginEligibility, CheckPluginError> {
+        let plugin = self
+            .cache
+            .plugin(plugin_name)
+            .ok_or_else(|| CheckPluginError::PluginNotLoaded(plugin_name.to_owned()))?;
+
+        plugin_eligibility(plugin, |m| self.master_scale(m)).map_err(Into::into)
+    }
+
+    /// Find out whether each loaded plugin could be a light or medium plugin,
+    /// as [Game::plugin_eligibility] does, and which plugins could be flagged
+    /// as such.
+    pub fn eligibility_report(&self) -> Result<EligibilityReport, PluginReportError> {
+        let plugins = self
+            .loaded_plugins_in_load_order()
+            .into_iter()
+            .map(|p| plugin_eligibility(p, |m| self.master_scale(m)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(EligibilityReport::new(plugins))
+    }
+
+    /// Find the slots that the active plugins in the current load order
+    /// occupy, and whether there are more active full, medium or light
+    /// plugins than the game supports.
+    ///
+    /// Each plugin's type is decided by its light and medium plugin flags, so
+    /// all active plugins must be loaded, though loading their headers is
+    /// enough. OpenMW script files are skipped, as they contain no records.
+    /// The current load order state is used, so
+    /// [Game::load_current_load_order_state] should be called first.
+    pub fn load_order_slots(&self) -> Result<LoadOrderSlots, PluginReportError> {
+        let plugins = self
+            .load_order
+            .active_plugin_names()
+            .into_iter()
+            .filter(|name| {
+                self.game_type() != GameType::OpenMW
+                    || !has_ascii_extension(Path::new(name), "omwscripts")
+            })
+            .map(|name| {
+                self.cache
+                    .plugin(name)
+                    .map(|p| (p.name(), PluginScale::of(p)))
+                    .ok_or_else(|| PluginReportError::PluginNotLoaded(name.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(load_order_slots(self.game_type(), plugins))
+    }
+
+    fn master_scale(&self, master_name: &str) -> Option<PluginScale> {
+        self.cache
+            .plugin(master_name)
+            .map(|p| PluginScale::of(p.as_ref()))
+    }
+
+    /// Get the loaded plugins in their current load order, followed by any
+    /// loaded plugins that are not in the load order in alphabetical order.
+    fn loaded_plugins_in_load_order(&self) -> Vec<&Arc<Plugin>> {
+        let key = |plugin: &Arc<Plugin>| {
+            (
+                self.load_order
+                    .index_of(plugin.name())
+                    .unwrap_or(usize::MAX),
+                unicase::UniCase::new(plugin.name().to_owned()),
+            )
+        };
+
+        let mut plugins: Vec<_> = self.cache.plugins_iter().collect();
+        plugins.sort_by_cached_key(|p| key(p));
+
+        plugins
+    }
+
+    /// Check a loaded plugin for problems that would stop it from working
+    /// correctly.
+    ///
+    /// The plugin's masters are checked to see if any are missing, inactive
+    /// while the plugin is active, or load after the plugin. The plugin's
+    /// requirements and incompatibilities are taken from all loaded metadata
+    /// after evaluating its conditions and constraints, and checked to see if
+    /// any requirements are missing or inactive while the plugin is active,
+    /// or if any incompatibilities are active while the plugin is active.
+    ///
+    /// The plugin is checked against the current load order state, so
+    /// [Game::load_current_load_order_state] should be called first. The
+    /// problems found are returned in the order that their files are listed.
+    pub fn check_plugin(&self, plugin_name: &str) -> Result<Vec<PluginProblem>, CheckPluginError> {
+        let plugin = self
+            .cache
+            .plugin(plugin_name)
+            .ok_or_else(|| CheckPluginError::PluginNotLoaded(plugin_name.to_owned()))?;
+
+        let database = self.database.read()?;
+        let state = self.install_state();
+
+        find_plugin_problems(plugin, &database, &state)
+    }
+
+    /// Check all loaded plugins for problems, as [Game::check_plugin] does.
+    ///
+    /// Each plugin is paired with the problems found for it, including
+    /// plugins that have no problems, and plugins are listed in load order.
+    pub fn check_plugins(&self) -> Result<Vec<(String, Vec<PluginProblem>)>, CheckPluginError> {
+        let database = self.database.read()?;
+        let state = self.install_state();
+
+        self.loaded_plugins_in_load_order()
+            .into_iter()
+            .map(|p| find_plugin_problems(p, &database, &state).map(|e| (p.name().to_owned(), e)))
+            .collect()
+    }
+
+    fn install_state(&self) -> GameInstallState<'_> {
+        GameInstallState {
+            game_type: self.game_type(),
+            data_path: data_path(self.base_type, &self.install_path),
+            additional_data_paths: self.additional_data_paths(),
+            load_order: self.load_order.as_ref(),
+        }
+    }
+}
+
+fn find_plugin_problems(
+    plugin: &Plugin,
+    database: &Database,
+    state: &GameInstallState,
+) -> Result<Vec<PluginProblem>, CheckPluginError> {
+    let masters = plugin.masters()?;
+    let metadata = database
+        .plugin_metadata(plugin.name(), true, true)?
+        .map(|m| m.filter_by_constraints(database))
+        .transpose()?;
+
+    Ok(check_plugin(
+        plugin.name(),
+        &masters,
+        metadata.as_ref(),
+        state,
+    ))
+}
+
+struct GameInstallState<'a> {
+    game_type: GameType,
+    data_path: PathBuf,
+    additional_data_paths: &'a [PathBuf],
+    load_order: &'a (dyn WritableLoadOrder + Send + Sync + 'static),
+}
+
+impl InstallState for GameInstallState<'_> {
+    fn file_exists(&self, file_name: &str) -> bool {
+        file_exists(
+            self.game_type,
+            &self.data_path,
+            self.additional_data_paths,
+            file_name,
+        )
+    }
+
+    fn load_order_index(&self, plugin_name: &str) -> Option<usize> {
+        self.load_order.index_of(plugin_name)
+    }
+
+    fn is_active(&self, plugin_name: &str) -> bool {
+        self.load_order.is_active(plugin_name)
+    }
+}
+
+/// Check if a file exists in the data path or any of the additional data
+/// paths, in the same way as the `file()` metadata condition, so plugins may
+/// also be ghosted, unless the game is OpenMW.
+fn file_exists(
+    game_type: GameType,
+    data_path: &Path,
+    additional_data_paths: &[PathBuf],
+    file_name: &str,
+) -> bool {
+    let file_path = Path::new(file_name);
+    let may_be_ghosted =
+        game_type != GameType::OpenMW && has_plugin_file_extension(game_type, file_path);
+
+    additional_data_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(std::iter::once(data_path))
+        .any(|parent_path| {
+            let path = parent_path.join(file_path);
+            if path.exists() {
+                return true;
+            }
+
+            let mut ghosted_path = path.into_os_string();
+            ghosted_path.push(GHOST_FILE_EXTENSION);
+            may_be_ghosted && Path::new(&ghosted_path).exists()
+        })
 }
 
 fn resolve_path(path: &Path) -> PathBuf {
@@ -747,18 +1158,39 @@ fn update_loaded_plugin_state<'a>(
     }
 }
 
+/// The loaded plugins' sorting data, the groups graph and the names of the
+/// additional metadata layers from lowest to highest precedence.
+type SortingData<'a> = (
+    Vec<PluginSortingData<'a, Plugin>>,
+    GroupsGraph,
+    Vec<Arc<str>>,
+);
+
+/// Gets a plugin's sorting data, using `overlaid_userlist` as the source of
+/// user metadata if it is given.
 fn to_plugin_sorting_data<'a>(
     database: &Database,
+    overlaid_userlist: Option<&MetadataDocument>,
     plugin: &'a Arc<Plugin>,
     load_order_index: usize,
 ) -> Result<PluginSortingData<'a, Plugin>, SortPluginsError> {
     let masterlist_metadata = database
-        .plugin_metadata(plugin.name(), false, true)?
+        .masterlist_plugin_metadata(plugin.name())?
         .map(|m| m.filter_by_constraints(database))
         .transpose()?;
 
-    let user_metadata = database
-        .plugin_user_metadata(plugin.name(), true)?
+    let layers_metadata = database
+        .plugin_layers_metadata(plugin.name())?
+        .into_iter()
+        .map(|(i, m)| m.filter_by_constraints(database).map(|m| (i, m)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let user_metadata = match overlaid_userlist {
+        Some(userlist) => database.plugin_metadata_from(userlist, plugin.name())?,
+        None => database.plugin_user_metadata(plugin.name(), true)?,
+    };
+
+    let user_metadata = user_metadata
         .map(|m| m.filter_by_constraints(database))
         .transpose()?;
 
@@ -768,6 +1200,7 @@ fn to_plugin_sorting_data<'a>(
         user_metadata.as_ref(),
         load_order_index,
     )
+    .map(|d| d.with_layer_metadata(&layers_metadata))
     .map_err(Into::into)
 }
 
@@ -818,7 +1251,7 @@ mod tests {
     use parameterized_test::parameterized_test;
 
     use crate::{
-        metadata::{File, PluginMetadata},
+        metadata::{File, MessageContent, PluginCleaningData, PluginMetadata, Tag, TagSuggestion},
         tests::{
             ALL_GAME_TYPES, BLANK_DIFFERENT_ESM, BLANK_DIFFERENT_ESP, BLANK_ESM, BLANK_ESP,
             BLANK_MASTER_DEPENDENT_ESM, Fixture,
@@ -1960,6 +2393,43 @@ mod tests {
 
                 assert!(game.sort_plugins(&[BLANK_ESP]).is_err());
             }
+
+            #[test]
+            fn with_overlay_should_use_the_overlay_without_changing_the_database() {
+                let fixture = Fixture::new(GameType::Oblivion);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                load_all_installed_plugins(&mut game, &fixture);
+
+                let mut metadata = PluginMetadata::new(BLANK_ESP).unwrap();
+                metadata.set_load_after_files(vec![File::new(BLANK_DIFFERENT_ESP.into())]);
+                let overlay = MetadataOverlay::new().with_plugin_user_metadata(metadata);
+
+                let report = game
+                    .sort_plugins_with_overlay(
+                        &[BLANK_ESP, BLANK_DIFFERENT_ESP],
+                        &overlay,
+                        &SortOptions::default(),
+                    )
+                    .unwrap();
+
+                assert_eq!(&[BLANK_DIFFERENT_ESP, BLANK_ESP], report.load_order());
+
+                let database = game.database();
+                let database = database.read().unwrap();
+                assert!(
+                    database
+                        .plugin_user_metadata(BLANK_ESP, false)
+                        .unwrap()
+                        .is_none()
+                );
+            }
         }
 
         mod is_plugin_active {
@@ -1994,6 +2464,316 @@ mod tests {
             }
         }
 
+        #[test]
+        fn cleaning_report_should_only_list_cleaning_data_that_matches_loaded_plugins() {
+            let fixture = Fixture::new(GameType::Oblivion);
+
+            let mut game =
+                Game::with_local_path(fixture.game_type, &fixture.game_path, &fixture.local_path)
+                    .unwrap();
+
+            game.load_current_load_order_state().unwrap();
+            game.load_plugin_headers(&[Path::new(BLANK_ESM), Path::new(BLANK_ESP)])
+                .unwrap();
+
+            let mut metadata = PluginMetadata::new(BLANK_ESM).unwrap();
+            metadata.set_dirty_info(vec![
+                PluginCleaningData::new(0x374E_2A6F, "utility1".into()),
+                PluginCleaningData::new(0xDEAD_BEEF, "utility2".into()),
+            ]);
+            game.database()
+                .write()
+                .unwrap()
+                .set_plugin_user_metadata(metadata);
+
+            let report = game
+                .cleaning_report(MessageContent::DEFAULT_LANGUAGE)
+                .unwrap();
+
+            assert_eq!(1, report.entries().len());
+            assert_eq!(BLANK_ESM, report.entries()[0].plugin_name());
+            assert_eq!(
+                "utility1",
+                report.entries()[0].cleaning_data().cleaning_utility()
+            );
+        }
+
+        #[test]
+        fn bash_tag_report_should_only_list_loaded_plugins_with_bash_tags() {
+            let fixture = Fixture::new(GameType::Oblivion);
+
+            let mut game =
+                Game::with_local_path(fixture.game_type, &fixture.game_path, &fixture.local_path)
+                    .unwrap();
+
+            game.load_current_load_order_state().unwrap();
+            game.load_plugin_headers(&[Path::new(BLANK_ESM), Path::new(BLANK_ESP)])
+                .unwrap();
+
+            let mut metadata = PluginMetadata::new(BLANK_ESM).unwrap();
+            metadata.set_tags(vec![Tag::new("Relev".into(), TagSuggestion::Addition)]);
+            game.database()
+                .write()
+                .unwrap()
+                .set_plugin_user_metadata(metadata);
+
+            let report = game.bash_tag_report().unwrap();
+
+            assert_eq!(1, report.plugins().len());
+            assert_eq!(BLANK_ESM, report.plugins()[0].plugin_name());
+            assert_eq!(
+                &[crate::BashTagSource::Userlist],
+                report.plugins()[0].tags()[0].added_by()
+            );
+        }
+
+        mod eligibility_report {
+            use crate::tests::initial_load_order;
+
+            use super::*;
+
+            #[parameterized_test(ALL_GAME_TYPES)]
+            fn should_agree_with_plugin_validity_checks(game_type: GameType) {
+                let fixture = Fixture::new(game_type);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                let load_order = initial_load_order(fixture.game_type);
+                let plugins: Vec<_> = load_order.iter().map(|(n, _)| Path::new(n)).collect();
+
+                game.load_current_load_order_state().unwrap();
+                game.load_plugins(&plugins).unwrap();
+
+                if matches!(game_type, GameType::Morrowind | GameType::OpenMW) {
+                    assert!(game.eligibility_report().is_err());
+                    return;
+                }
+
+                let report = game.eligibility_report().unwrap();
+
+                assert_eq!(game.loaded_plugins().len(), report.plugins().len());
+                for eligibility in report.plugins() {
+                    let plugin = game.plugin(eligibility.plugin_name()).unwrap();
+
+                    assert_eq!(
+                        plugin.is_valid_as_light_plugin().unwrap(),
+                        eligibility.light().is_eligible()
+                    );
+                    assert_eq!(
+                        plugin.is_valid_as_medium_plugin().unwrap(),
+                        eligibility.medium().is_eligible()
+                    );
+                }
+            }
+
+            #[test]
+            fn plugin_eligibility_should_error_if_only_the_plugin_header_is_loaded() {
+                let fixture = Fixture::new(GameType::SkyrimSE);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+                game.load_plugin_headers(&[Path::new(BLANK_ESP)]).unwrap();
+
+                assert!(matches!(
+                    game.plugin_eligibility(BLANK_ESP),
+                    Err(CheckPluginError::PluginDataError(_))
+                ));
+            }
+
+            #[test]
+            fn plugin_eligibility_should_error_if_the_plugin_is_not_loaded() {
+                let fixture = Fixture::new(GameType::SkyrimSE);
+
+                let game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                assert!(matches!(
+                    game.plugin_eligibility(BLANK_ESP),
+                    Err(CheckPluginError::PluginNotLoaded(_))
+                ));
+            }
+        }
+
+        mod load_order_slots {
+            use crate::tests::initial_load_order;
+
+            use super::*;
+
+            #[parameterized_test(ALL_GAME_TYPES)]
+            fn should_assign_a_slot_to_each_active_plugin_in_load_order(game_type: GameType) {
+                let fixture = Fixture::new(game_type);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                let load_order = initial_load_order(fixture.game_type);
+                let plugins: Vec<_> = load_order.iter().map(|(n, _)| Path::new(n)).collect();
+
+                game.load_current_load_order_state().unwrap();
+                game.load_plugin_headers(&plugins).unwrap();
+
+                let slots = game.load_order_slots().unwrap();
+
+                let active_plugins: Vec<_> = game
+                    .load_order()
+                    .into_iter()
+                    .filter(|p| game.is_plugin_active(p) && !p.ends_with(".omwscripts"))
+                    .collect();
+                let slot_plugins: Vec<_> = slots
+                    .plugins()
+                    .iter()
+                    .map(crate::ActivePluginSlot::plugin_name)
+                    .collect();
+
+                assert_eq!(active_plugins, slot_plugins);
+                assert_eq!(
+                    active_plugins.len(),
+                    slots.full().used() + slots.medium().used() + slots.light().used()
+                );
+                assert_eq!("00", slots.plugins()[0].slot().to_string());
+                assert!(!slots.is_any_limit_exceeded());
+            }
+
+            #[test]
+            fn should_error_if_an_active_plugin_is_not_loaded() {
+                let fixture = Fixture::new(GameType::SkyrimSE);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                game.load_current_load_order_state().unwrap();
+
+                assert!(matches!(
+                    game.load_order_slots(),
+                    Err(PluginReportError::PluginNotLoaded(_))
+                ));
+            }
+        }
+
+        mod check_plugin {
+            use crate::{PluginProblemKind, tests::BLANK_DIFFERENT_MASTER_DEPENDENT_ESP};
+
+            use super::*;
+
+            #[test]
+            fn should_error_if_the_plugin_is_not_loaded() {
+                let fixture = Fixture::new(GameType::Oblivion);
+
+                let game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                assert!(matches!(
+                    game.check_plugin(BLANK_ESP),
+                    Err(CheckPluginError::PluginNotLoaded(_))
+                ));
+            }
+
+            #[test]
+            fn should_check_masters_and_evaluated_metadata_against_the_load_order() {
+                let fixture = Fixture::new(GameType::Oblivion);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                game.load_current_load_order_state().unwrap();
+                game.load_plugin_headers(&[Path::new(BLANK_DIFFERENT_MASTER_DEPENDENT_ESP)])
+                    .unwrap();
+
+                let mut metadata =
+                    PluginMetadata::new(BLANK_DIFFERENT_MASTER_DEPENDENT_ESP).unwrap();
+                metadata.set_requirements(vec![
+                    File::new("missing.esp".into()),
+                    File::new("other.esp".into()).with_condition("file(\"missing.esp\")".into()),
+                ]);
+                metadata.set_incompatibilities(vec![File::new(BLANK_ESM.into())]);
+                game.database()
+                    .write()
+                    .unwrap()
+                    .set_plugin_user_metadata(metadata);
+
+                let problems: Vec<_> = game
+                    .check_plugin(BLANK_DIFFERENT_MASTER_DEPENDENT_ESP)
+                    .unwrap()
+                    .iter()
+                    .map(|p| (p.kind(), p.display_name().to_owned()))
+                    .collect();
+
+                assert_eq!(
+                    vec![
+                        (
+                            PluginProblemKind::InactiveMaster,
+                            BLANK_DIFFERENT_ESM.to_owned()
+                        ),
+                        (
+                            PluginProblemKind::MissingRequirement,
+                            "missing.esp".to_owned()
+                        ),
+                        (
+                            PluginProblemKind::ActiveIncompatibility,
+                            BLANK_ESM.to_owned()
+                        ),
+                    ],
+                    problems
+                );
+            }
+
+            #[test]
+            fn check_plugins_should_check_each_loaded_plugin_in_load_order() {
+                let fixture = Fixture::new(GameType::Oblivion);
+
+                let mut game = Game::with_local_path(
+                    fixture.game_type,
+                    &fixture.game_path,
+                    &fixture.local_path,
+                )
+                .unwrap();
+
+                game.load_current_load_order_state().unwrap();
+                game.load_plugin_headers(&[
+                    Path::new(BLANK_DIFFERENT_MASTER_DEPENDENT_ESP),
+                    Path::new(BLANK_ESM),
+                ])
+                .unwrap();
+
+                let results = game.check_plugins().unwrap();
+
+                let names: Vec<_> = results.iter().map(|(n, _)| n.as_str()).collect();
+                assert_eq!(vec![BLANK_ESM, BLANK_DIFFERENT_MASTER_DEPENDENT_ESP], names);
+                for (name, problems) in &results {
+                    assert_eq!(&game.check_plugin(name).unwrap(), problems);
+                }
+            }
+        }
+
         #[test]
         fn set_load_order_should_persist_the_given_load_order() {
             let fixture = Fixture::new(GameType::Oblivion);
@@ -2089,7 +2869,7 @@ mod tests {
 
         database.set_plugin_user_metadata(user_metadata);
 
-        let data = to_plugin_sorting_data(&database, &plugin, 0).unwrap();
+        let data = to_plugin_sorting_data(&database, None, &plugin, 0).unwrap();
 
         assert_eq!(["A.esp".to_owned()], *data.masterlist_load_after);
         assert_eq!(["C.esp".to_owned()], *data.masterlist_req);
@@ -2097,6 +2877,68 @@ mod tests {
         assert_eq!([BLANK_DIFFERENT_ESP.to_owned()], *data.user_req);
     }
 
+    mod file_exists {
+        use super::*;
+
+        #[test]
+        fn should_treat_file_names_as_paths_and_not_conditions() {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let file_name = "a \") or file(\"b.esp";
+            std::fs::write(tmp_dir.path().join(file_name), "").unwrap();
+
+            assert!(file_exists(
+                GameType::Oblivion,
+                tmp_dir.path(),
+                &[],
+                file_name
+            ));
+            assert!(!file_exists(
+                GameType::Oblivion,
+                tmp_dir.path(),
+                &[],
+                "a \""
+            ));
+        }
+
+        #[test]
+        fn should_find_ghosted_plugins_unless_the_game_is_openmw() {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            std::fs::write(tmp_dir.path().join("a.esp.ghost"), "").unwrap();
+            std::fs::write(tmp_dir.path().join("a.txt.ghost"), "").unwrap();
+
+            assert!(file_exists(
+                GameType::Oblivion,
+                tmp_dir.path(),
+                &[],
+                "a.esp"
+            ));
+            assert!(!file_exists(
+                GameType::Oblivion,
+                tmp_dir.path(),
+                &[],
+                "a.txt"
+            ));
+            assert!(!file_exists(GameType::OpenMW, tmp_dir.path(), &[], "a.esp"));
+        }
+
+        #[test]
+        fn should_check_additional_data_paths() {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let data_path = tmp_dir.path().join("Data");
+            let other_path = tmp_dir.path().join("Other");
+            std::fs::create_dir_all(&data_path).unwrap();
+            std::fs::create_dir_all(&other_path).unwrap();
+            std::fs::write(other_path.join("a.esp"), "").unwrap();
+
+            assert!(file_exists(
+                GameType::Starfield,
+                &data_path,
+                &[other_path],
+                "a.esp"
+            ));
+        }
+    }
+
     mod game_cache {
         use super::*;
 
@@ -0,0 +1,331 @@
+use crate::metadata::{File, MessageContent, PluginMetadata};
+
+/// The kinds of problem that can stop a plugin from working correctly.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum PluginProblemKind {
+    /// One of the plugin's masters is not installed.
+    MissingMaster,
+    /// The plugin is active, but one of its masters is not.
+    InactiveMaster,
+    /// One of the plugin's masters loads after it.
+    MasterLoadsAfter,
+    /// A file that the plugin's metadata says it requires is not installed.
+    MissingRequirement,
+    /// The plugin is active, but a plugin that its metadata says it requires
+    /// is not.
+    InactiveRequirement,
+    /// The plugin is active, and so is a file that its metadata says it is
+    /// incompatible with. Files that are not plugins are active if they are
+    /// installed.
+    ActiveIncompatibility,
+}
+
+/// A problem with a plugin's installation, found by
+/// [Game::check_plugin][crate::Game::check_plugin].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PluginProblem {
+    kind: PluginProblemKind,
+    file: File,
+}
+
+impl PluginProblem {
+    fn new(kind: PluginProblemKind, file: File) -> Self {
+        Self { kind, file }
+    }
+
+    /// Get the kind of problem.
+    pub fn kind(&self) -> PluginProblemKind {
+        self.kind
+    }
+
+    /// Get the file that the problem involves. If the file is one of the
+    /// plugin's masters, it only has a name.
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Get the name to display for the file that the problem involves. This
+    /// is the file's display name if it has one, and its name otherwise.
+    pub fn display_name(&self) -> &str {
+        self.file
+            .display_name()
+            .unwrap_or_else(|| self.file.name().as_str())
+    }
+
+    /// Get the detail message content of the file that the problem involves,
+    /// which should be appended to any message displayed for the problem.
+    pub fn detail(&self) -> &[MessageContent] {
+        self.file.detail()
+    }
+}
+
+impl std::fmt::Display for PluginProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.display_name();
+        match self.kind {
+            PluginProblemKind::MissingMaster | PluginProblemKind::MissingRequirement => write!(
+                f,
+                "this plugin requires \"{name}\" to be installed, but it is missing"
+            ),
+            PluginProblemKind::InactiveMaster | PluginProblemKind::InactiveRequirement => write!(
+                f,
+                "this plugin requires \"{name}\" to be active, but it is inactive"
+            ),
+            PluginProblemKind::MasterLoadsAfter => {
+                write!(f, "this plugin loads before its master \"{name}\"")
+            }
+            PluginProblemKind::ActiveIncompatibility => write!(
+                f,
+                "this plugin is incompatible with \"{name}\", but both are active"
+            ),
+        }
+    }
+}
+
+/// The state of the installed files and load order that plugins are checked
+/// against.
+pub(crate) trait InstallState {
+    /// Check if a file is installed. Plugins may be ghosted.
+    fn file_exists(&self, file_name: &str) -> bool;
+
+    /// Get the plugin's position in the load order, if it is in the load
+    /// order.
+    fn load_order_index(&self, plugin_name: &str) -> Option<usize>;
+
+    fn is_active(&self, plugin_name: &str) -> bool;
+}
+
+/// Check the given plugin's masters and its evaluated metadata's
+/// requirements and incompatibilities against the given install state.
+pub(crate) fn check_plugin<T: InstallState>(
+    plugin_name: &str,
+    masters: &[String],
+    metadata: Option<&PluginMetadata>,
+    state: &T,
+) -> Vec<PluginProblem> {
+    let is_active = state.is_active(plugin_name);
+    let index = state.load_order_index(plugin_name);
+
+    let mut problems = Vec::new();
+    for master in masters {
+        let kind = if !state.file_exists(master) {
+            Some(PluginProblemKind::MissingMaster)
+        } else if is_active && !state.is_active(master) {
+            Some(PluginProblemKind::InactiveMaster)
+        } else if index.is_some_and(|i| state.load_order_index(master).is_some_and(|m| m > i)) {
+            Some(PluginProblemKind::MasterLoadsAfter)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            problems.push(PluginProblem::new(kind, File::new(master.clone())));
+        }
+    }
+
+    let Some(metadata) = metadata else {
+        return problems;
+    };
+
+    for file in metadata.requirements() {
+        let name = file.name().as_str();
+        let kind = if !state.file_exists(name) {
+            Some(PluginProblemKind::MissingRequirement)
+        } else if is_active && state.load_order_index(name).is_some() && !state.is_active(name) {
+            Some(PluginProblemKind::InactiveRequirement)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            problems.push(PluginProblem::new(kind, file.clone()));
+        }
+    }
+
+    if is_active {
+        for file in metadata.incompatibilities() {
+            let name = file.name().as_str();
+            let is_incompatible_file_active = if state.load_order_index(name).is_some() {
+                state.is_active(name)
+            } else {
+                state.file_exists(name)
+            };
+
+            if is_incompatible_file_active {
+                problems.push(PluginProblem::new(
+                    PluginProblemKind::ActiveIncompatibility,
+                    file.clone(),
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestInstallState {
+        load_order: Vec<&'static str>,
+        active: Vec<&'static str>,
+        other_files: Vec<&'static str>,
+    }
+
+    impl InstallState for TestInstallState {
+        fn file_exists(&self, file_name: &str) -> bool {
+            self.load_order.contains(&file_name) || self.other_files.contains(&file_name)
+        }
+
+        fn load_order_index(&self, plugin_name: &str) -> Option<usize> {
+            self.load_order.iter().position(|p| *p == plugin_name)
+        }
+
+        fn is_active(&self, plugin_name: &str) -> bool {
+            self.active.contains(&plugin_name)
+        }
+    }
+
+    fn kinds(problems: &[PluginProblem]) -> Vec<(PluginProblemKind, &str)> {
+        problems
+            .iter()
+            .map(|p| (p.kind(), p.file().name().as_str()))
+            .collect()
+    }
+
+    mod check_plugin {
+        use super::*;
+
+        #[test]
+        fn should_find_no_problems_if_masters_are_installed_active_and_load_earlier() {
+            let state = TestInstallState {
+                load_order: vec!["A.esm", "B.esp"],
+                active: vec!["A.esm", "B.esp"],
+                ..Default::default()
+            };
+
+            let problems = check_plugin("B.esp", &["A.esm".into()], None, &state);
+
+            assert!(problems.is_empty());
+        }
+
+        #[test]
+        fn should_report_missing_inactive_and_later_loading_masters() {
+            let state = TestInstallState {
+                load_order: vec!["A.esm", "C.esp", "D.esp"],
+                active: vec!["C.esp", "D.esp"],
+                ..Default::default()
+            };
+            let masters = ["A.esm".into(), "B.esm".into(), "D.esp".into()];
+
+            let problems = check_plugin("C.esp", &masters, None, &state);
+
+            assert_eq!(
+                vec![
+                    (PluginProblemKind::InactiveMaster, "A.esm"),
+                    (PluginProblemKind::MissingMaster, "B.esm"),
+                    (PluginProblemKind::MasterLoadsAfter, "D.esp"),
+                ],
+                kinds(&problems)
+            );
+        }
+
+        #[test]
+        fn should_not_report_inactive_masters_of_an_inactive_plugin() {
+            let state = TestInstallState {
+                load_order: vec!["A.esm", "B.esp"],
+                ..Default::default()
+            };
+
+            let problems = check_plugin("B.esp", &["A.esm".into()], None, &state);
+
+            assert!(problems.is_empty());
+        }
+
+        #[test]
+        fn should_report_missing_and_inactive_requirements() {
+            let state = TestInstallState {
+                load_order: vec!["A.esp", "B.esp"],
+                active: vec!["B.esp"],
+                other_files: vec!["C.dll"],
+            };
+            let mut metadata = PluginMetadata::new("B.esp").unwrap();
+            metadata.set_requirements(vec![
+                File::new("A.esp".into()),
+                File::new("C.dll".into()),
+                File::new("D.dll".into()),
+            ]);
+
+            let problems = check_plugin("B.esp", &[], Some(&metadata), &state);
+
+            assert_eq!(
+                vec![
+                    (PluginProblemKind::InactiveRequirement, "A.esp"),
+                    (PluginProblemKind::MissingRequirement, "D.dll"),
+                ],
+                kinds(&problems)
+            );
+        }
+
+        #[test]
+        fn should_report_active_incompatibilities_only_if_the_plugin_is_active() {
+            let mut state = TestInstallState {
+                load_order: vec!["A.esp", "B.esp", "C.esp"],
+                active: vec!["A.esp"],
+                other_files: vec!["D.dll"],
+            };
+            let mut metadata = PluginMetadata::new("C.esp").unwrap();
+            metadata.set_incompatibilities(vec![
+                File::new("A.esp".into()),
+                File::new("B.esp".into()),
+                File::new("D.dll".into()),
+                File::new("E.dll".into()),
+            ]);
+
+            let problems = check_plugin("C.esp", &[], Some(&metadata), &state);
+            assert!(problems.is_empty());
+
+            state.active.push("C.esp");
+            let problems = check_plugin("C.esp", &[], Some(&metadata), &state);
+
+            assert_eq!(
+                vec![
+                    (PluginProblemKind::ActiveIncompatibility, "A.esp"),
+                    (PluginProblemKind::ActiveIncompatibility, "D.dll"),
+                ],
+                kinds(&problems)
+            );
+        }
+    }
+
+    mod plugin_problem {
+        use super::*;
+
+        #[test]
+        fn display_name_should_prefer_the_file_display_name() {
+            let file = File::new("A.esp".into()).with_display_name("Plugin A".into());
+            let problem = PluginProblem::new(PluginProblemKind::MissingRequirement, file);
+
+            assert_eq!("Plugin A", problem.display_name());
+            assert_eq!(
+                "this plugin requires \"Plugin A\" to be installed, but it is missing",
+                problem.to_string()
+            );
+        }
+
+        #[test]
+        fn detail_should_be_the_file_detail() {
+            let detail = vec![MessageContent::new("detail".into())];
+            let file = File::new("A.esp".into())
+                .with_detail(detail.clone())
+                .unwrap();
+            let problem = PluginProblem::new(PluginProblemKind::ActiveIncompatibility, file);
+
+            assert_eq!(detail, problem.detail());
+            assert_eq!("A.esp", problem.display_name());
+        }
+    }
+}
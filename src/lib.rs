@@ -108,12 +108,17 @@
 )]
 
 mod archive;
+mod bash_tags;
+mod cleaning;
 mod database;
+mod eligibility;
 pub mod error;
 mod game;
+mod health;
 mod logging;
 pub mod metadata;
 mod plugin;
+mod slots;
 mod sorting;
 #[cfg(test)]
 mod tests;
@@ -123,11 +128,30 @@ use std::{path::Path, slice::EscapeAscii};
 
 use fancy_regex::{Error as RegexImplError, Regex, RegexBuilder};
 
-pub use database::{Database, WriteMode};
+pub use bash_tags::{BashTagReport, BashTagSource, PluginBashTags, ResolvedBashTag};
+pub use cleaning::{CleaningReport, CleaningReportEntry, CleaningStatus};
+pub use database::{CycleFix, Database, MetadataChange, MetadataOverlay, WriteMode};
+pub use eligibility::{EligibilityReport, FlagEligibility, PluginEligibility};
 pub use game::{Game, GameType};
+pub use health::{PluginProblem, PluginProblemKind};
 pub use logging::{LogLevel, set_log_level, set_logging_callback};
 pub use plugin::Plugin;
-pub use sorting::vertex::{EdgeType, Vertex};
+pub use slots::{ActivePluginSlot, LoadOrderSlots, PluginSlot, SlotUsage};
+pub use sorting::{
+    custom::{SortingMetadata, sort_plugins},
+    diff::{LoadOrderDiff, PluginMove},
+    export::{SortingGraph, SortingGraphEdge, SortingGraphNode},
+    options::SortOptions,
+    overlap::{
+        CountOverlapPolicy, OverlapEdge, OverlapPair, OverlapPlugin, OverlapPolicy, OverlapType,
+    },
+    pins::{Pin, PinPosition},
+    plugins::SortingPlugin,
+    progress::{CancellationToken, ProgressCallback, SortPhase},
+    report::{IgnoredEdge, IgnoredEdgeReason, MoveDirection, MovedPlugin, SortReport},
+    session::SortingSession,
+    vertex::{EdgeType, Vertex},
+};
 pub use version::{
     LIBLOOT_VERSION_MAJOR, LIBLOOT_VERSION_MINOR, LIBLOOT_VERSION_PATCH, is_compatible,
     libloot_revision, libloot_version,
@@ -92,13 +92,19 @@ impl ParseMetadataError {
 
 impl std::fmt::Display for ParseMetadataError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "encountered a YAML parsing error at line {} column {}: {}",
-            self.marker.line(),
-            self.marker.col(),
-            self.reason
-        )
+        // Lines are counted from 1, so line 0 means that the metadata wasn't
+        // parsed from YAML text, e.g. because it was deserialised from JSON.
+        if self.marker.line() == 0 {
+            write!(f, "encountered a metadata parsing error: {}", self.reason)
+        } else {
+            write!(
+                f,
+                "encountered a YAML parsing error at line {} column {}: {}",
+                self.marker.line(),
+                self.marker.col(),
+                self.reason
+            )
+        }
     }
 }
 
@@ -222,33 +228,40 @@ impl From<Box<RegexImplError>> for RegexError {
     }
 }
 
-/// Represents an error encountered while loading metadata from a file.
+/// Represents an error encountered while loading metadata from a file, a
+/// string or a reader.
 #[derive(Debug)]
 pub struct LoadMetadataError {
-    path: PathBuf,
+    path: Option<PathBuf>,
     reason: MetadataDocumentParsingError,
 }
 
 impl LoadMetadataError {
     pub(super) fn new(path: PathBuf, reason: MetadataDocumentParsingError) -> Self {
-        Self { path, reason }
+        Self {
+            path: Some(path),
+            reason,
+        }
     }
 
     pub(super) fn from_io_error(path: PathBuf, error: std::io::Error) -> Self {
         Self {
-            path,
+            path: Some(path),
             reason: MetadataDocumentParsingError::IoError(error),
         }
     }
+
+    pub(super) fn without_path(reason: MetadataDocumentParsingError) -> Self {
+        Self { path: None, reason }
+    }
 }
 
 impl std::fmt::Display for LoadMetadataError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "failed to parse the file at \"{}\"",
-            escape_ascii(&self.path)
-        )
+        match &self.path {
+            Some(path) => write!(f, "failed to parse the file at \"{}\"", escape_ascii(path)),
+            None => write!(f, "failed to parse metadata"),
+        }
     }
 }
 
@@ -267,6 +280,8 @@ pub(super) enum MetadataDocumentParsingError {
     IoError(std::io::Error),
     MetadataParsingError(ParseMetadataError),
     YamlMergeKeyError(YamlMergeKeyError),
+    #[cfg(feature = "json")]
+    JsonError(serde_json::Error),
 }
 
 impl std::fmt::Display for MetadataDocumentParsingError {
@@ -280,6 +295,8 @@ impl std::fmt::Display for MetadataDocumentParsingError {
             Self::YamlMergeKeyError(_) => {
                 write!(f, "an error occurred while resolving YAML merge keys",)
             }
+            #[cfg(feature = "json")]
+            Self::JsonError(_) => write!(f, "a JSON parsing error occurred"),
         }
     }
 }
@@ -291,6 +308,8 @@ impl std::error::Error for MetadataDocumentParsingError {
             Self::IoError(e) => Some(e),
             Self::MetadataParsingError(e) => Some(e),
             Self::YamlMergeKeyError(e) => Some(e),
+            #[cfg(feature = "json")]
+            Self::JsonError(e) => Some(e),
         }
     }
 }
@@ -349,6 +368,14 @@ impl YamlMergeKeyError {
             yaml,
         }
     }
+
+    pub(super) fn start(&self) -> Marker {
+        self.start
+    }
+
+    pub(super) fn yaml(&self) -> &str {
+        &self.yaml
+    }
 }
 
 impl std::fmt::Display for YamlMergeKeyError {
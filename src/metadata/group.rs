@@ -1,7 +1,10 @@
+use std::{path::Path, sync::Arc};
+
 use saphyr::MarkedYaml;
 
 use super::{
     error::ParseMetadataError,
+    provenance::{MetadataSource, Provenance, ProvenanceField},
     yaml::{
         EmitYaml, TryFromYaml, YamlEmitter, YamlObjectType, as_mapping, get_required_string_value,
         get_string_value, get_strings_vec_value,
@@ -14,6 +17,7 @@ pub struct Group {
     name: Box<str>,
     description: Option<Box<str>>,
     after_groups: Box<[String]>,
+    provenance: ProvenanceField,
 }
 
 impl Group {
@@ -57,6 +61,15 @@ impl Group {
     pub fn after_groups(&self) -> &[String] {
         &self.after_groups
     }
+
+    /// Get where the group was defined, if it was loaded from a metadata file.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.get()
+    }
+
+    pub(super) fn set_provenance_file(&mut self, source: MetadataSource, path: Option<&Arc<Path>>) {
+        self.provenance.set_file(source, path);
+    }
 }
 
 impl std::default::Default for Group {
@@ -67,6 +80,7 @@ impl std::default::Default for Group {
             name: Group::DEFAULT_NAME.into(),
             description: Option::default(),
             after_groups: Box::default(),
+            provenance: ProvenanceField::default(),
         }
     }
 }
@@ -86,6 +100,7 @@ impl TryFromYaml for Group {
             name: name.into(),
             description: description.map(|d| d.1.into()),
             after_groups: after.into_iter().map(str::to_owned).collect(),
+            provenance: ProvenanceField::new(value.span.start),
         })
     }
 }
@@ -0,0 +1,201 @@
+use saphyr::{LoadableYamlNode, MarkedYaml, Scalar, Yaml, YamlData};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+
+use super::{
+    file::File,
+    group::Group,
+    location::Location,
+    message::{Message, MessageContent},
+    plugin_cleaning_data::PluginCleaningData,
+    plugin_metadata::PluginMetadata,
+    tag::Tag,
+    yaml::{EmitYaml, TryFromYaml, YamlEmitter},
+};
+
+/// Convert a JSON value into a YAML node so that it can be validated and
+/// parsed in exactly the same way as metadata read from YAML. The node and its
+/// children have no position information.
+pub(super) fn to_marked_yaml(value: &serde_json::Value) -> MarkedYaml<'_> {
+    use serde_json::Value;
+
+    let data = match value {
+        Value::Null => YamlData::Value(Scalar::Null),
+        Value::Bool(b) => YamlData::Value(Scalar::Boolean(*b)),
+        // Let the YAML parser decide whether the number is an integer or a
+        // float.
+        Value::Number(n) => return MarkedYaml::scalar_from_string(n.to_string()),
+        Value::String(s) => YamlData::Value(Scalar::String(s.as_str().into())),
+        Value::Array(a) => YamlData::Sequence(a.iter().map(to_marked_yaml).collect()),
+        Value::Object(o) => YamlData::Mapping(
+            o.iter()
+                .map(|(k, v)| {
+                    (
+                        MarkedYaml::from(YamlData::Value(Scalar::String(k.as_str().into()))),
+                        to_marked_yaml(v),
+                    )
+                })
+                .collect(),
+        ),
+    };
+
+    MarkedYaml::from(data)
+}
+
+/// Serialise the given YAML text's document, so that metadata is written to
+/// JSON with the same structure as it is written to YAML.
+pub(super) fn serialize_yaml<S: Serializer>(yaml: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let document = Yaml::load_from_str(yaml)
+        .map_err(ser::Error::custom)?
+        .pop()
+        .ok_or_else(|| ser::Error::custom("no YAML document found"))?;
+
+    YamlValue(&document).serialize(serializer)
+}
+
+struct YamlValue<'a, 'b>(&'a Yaml<'b>);
+
+impl Serialize for YamlValue<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Yaml::Value(Scalar::Null) => serializer.serialize_unit(),
+            Yaml::Value(Scalar::Boolean(b)) => serializer.serialize_bool(*b),
+            Yaml::Value(Scalar::Integer(i)) => serializer.serialize_i64(*i),
+            Yaml::Value(Scalar::FloatingPoint(f)) => serializer.serialize_f64(f.0),
+            Yaml::Value(Scalar::String(s)) => serializer.serialize_str(s),
+            Yaml::Sequence(s) => serializer.collect_seq(s.iter().map(YamlValue)),
+            Yaml::Mapping(m) => {
+                serializer.collect_map(m.iter().map(|(k, v)| (YamlValue(k), YamlValue(v))))
+            }
+            _ => Err(ser::Error::custom("unexpected YAML node")),
+        }
+    }
+}
+
+fn serialize_metadata<T: EmitYaml, S: Serializer>(
+    metadata: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut emitter = YamlEmitter::new();
+    metadata.emit_yaml(&mut emitter);
+
+    serialize_yaml(&emitter.into_string(), serializer)
+}
+
+fn deserialize_metadata<'de, T: TryFromYaml, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    T::try_from_yaml(&to_marked_yaml(&value)).map_err(de::Error::custom)
+}
+
+macro_rules! impl_serde {
+    ($($type:ty),+) => {
+        $(
+            impl Serialize for $type {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize_metadata(self, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $type {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserialize_metadata(deserializer)
+                }
+            }
+        )+
+    };
+}
+
+impl_serde!(
+    File,
+    Group,
+    Location,
+    Message,
+    MessageContent,
+    PluginCleaningData,
+    PluginMetadata,
+    Tag
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::MessageType;
+
+    #[test]
+    fn plugin_metadata_should_round_trip_through_json() {
+        let mut plugin = PluginMetadata::new("Blank.esp").unwrap();
+        plugin.set_group("group1".into());
+        plugin.set_load_after_files(vec![
+            File::new("A.esp".into()),
+            File::new("B.esp".into()).with_condition("file(\"C.esp\")".into()),
+        ]);
+        plugin.set_messages(vec![Message::new(MessageType::Warn, "text".into())]);
+        plugin.set_tags(vec![Tag::new(
+            "Relev".into(),
+            crate::metadata::TagSuggestion::Addition,
+        )]);
+        plugin.set_dirty_info(vec![PluginCleaningData::new(0x1234_5678, "cleaner".into())]);
+
+        let json = serde_json::to_string(&plugin).unwrap();
+        let deserialized: PluginMetadata = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(plugin, deserialized);
+    }
+
+    #[test]
+    fn serialize_should_use_the_same_structure_as_yaml() {
+        let file = File::new("A.esp".into());
+        let data = PluginCleaningData::new(0x1234_5678, "cleaner".into());
+
+        assert_eq!("\"A.esp\"", serde_json::to_string(&file).unwrap());
+        assert_eq!(
+            r#"{"crc":305419896,"util":"cleaner"}"#,
+            serde_json::to_string(&data).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_should_accept_the_same_short_forms_as_yaml() {
+        let plugin: PluginMetadata = serde_json::from_str(
+            r#"{"name": "Blank.esp", "after": ["A.esp"], "tag": ["-Relev"], "url": ["https://www.example.com"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(&[File::new("A.esp".into())], plugin.load_after_files());
+        assert!(!plugin.tags()[0].is_addition());
+        assert_eq!("https://www.example.com", plugin.locations()[0].url());
+    }
+
+    #[test]
+    fn deserialize_should_error_if_a_condition_is_invalid() {
+        let result = serde_json::from_str::<File>(r#"{"name": "A.esp", "condition": "invalid"}"#);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn deserialize_should_error_if_a_required_field_is_missing() {
+        let result = serde_json::from_str::<Group>(r#"{"description": "text"}"#);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn deserialize_should_error_if_a_crc_is_given_as_a_string() {
+        let result = serde_json::from_str::<PluginCleaningData>(
+            r#"{"crc": "0x12345678", "util": "cleaner"}"#,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn deserialized_metadata_should_have_no_provenance() {
+        let group: Group = serde_json::from_str(r#"{"name": "group1"}"#).unwrap();
+
+        assert!(group.provenance().is_none());
+    }
+}
@@ -0,0 +1,873 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    str::FromStr,
+};
+
+use loot_condition_interpreter::Expression;
+use petgraph::{Graph, algo::tarjan_scc, graph::NodeIndex};
+use saphyr::{AnnotatedMapping, LoadableYamlNode, MarkedYaml, Marker, Scalar, YamlData};
+use saphyr_parser::{Event, Parser};
+
+use crate::{escape_ascii, logging};
+
+use super::{
+    error::{LoadMetadataError, MetadataDocumentParsingError},
+    group::Group,
+    plugin_metadata::PluginMetadata,
+    tag::Tag,
+    yaml::{TryFromYaml, get_value, process_merge_keys},
+};
+
+/// A problem found while linting a metadata file, and where it was found.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LintIssue {
+    line: usize,
+    column: usize,
+    kind: LintIssueKind,
+}
+
+impl LintIssue {
+    fn new(marker: Marker, kind: LintIssueKind) -> Self {
+        Self {
+            line: marker.line(),
+            column: marker.col(),
+            kind,
+        }
+    }
+
+    /// Get the kind of problem that was found.
+    pub fn kind(&self) -> &LintIssueKind {
+        &self.kind
+    }
+
+    /// Get the line in the YAML that the problem was found at. Lines are
+    /// counted from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the column in the YAML that the problem was found at. Columns are
+    /// counted from 0.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+/// The kinds of problem that linting a metadata file can find.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum LintIssueKind {
+    /// The file could not be parsed as a metadata document, so no other
+    /// checks could be run on it.
+    InvalidYaml(String),
+    /// A plugin or group refers to a group that is not defined.
+    UndefinedGroup(String),
+    /// The named groups load after each other in a cycle.
+    GroupCycle(Vec<String>),
+    /// A condition string could not be parsed.
+    InvalidCondition { condition: String, error: String },
+    /// A regex plugin entry matches the name of a plugin that also has an
+    /// exact entry, so both entries' metadata will be applied to the plugin.
+    ShadowedPlugin { regex: String, plugin: String },
+    /// The same tag suggestion is given more than once.
+    DuplicateTag(String),
+    /// Message content is given in one or more languages, but not in English.
+    MissingEnglishContent,
+    /// An anchor is defined but never referred to by an alias.
+    UnusedAnchor(String),
+    /// A CRC is not a 32-bit unsigned integer.
+    InvalidCrc(String),
+}
+
+impl std::fmt::Display for LintIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidYaml(e) => write!(f, "the metadata could not be parsed: {e}"),
+            Self::UndefinedGroup(g) => write!(f, "the group \"{g}\" is not defined"),
+            Self::GroupCycle(groups) => {
+                write!(f, "the groups \"{}\" form a cycle", groups.join("\", \""))
+            }
+            Self::InvalidCondition { condition, error } => {
+                write!(
+                    f,
+                    "the condition string \"{condition}\" is invalid: {error}"
+                )
+            }
+            Self::ShadowedPlugin { regex, plugin } => write!(
+                f,
+                "the regex entry \"{regex}\" matches \"{plugin}\", which also has an exact entry"
+            ),
+            Self::DuplicateTag(t) => write!(f, "the tag \"{t}\" is suggested more than once"),
+            Self::MissingEnglishContent => write!(f, "the message content has no English text"),
+            Self::UnusedAnchor(a) => write!(f, "the anchor \"{a}\" is never used"),
+            Self::InvalidCrc(c) => write!(f, "the CRC \"{c}\" is not a 32-bit unsigned integer"),
+        }
+    }
+}
+
+/// Check the given masterlist or userlist YAML for problems that may not stop
+/// it from loading, but which are probably mistakes.
+///
+/// `external_groups` are groups that are defined outside of the YAML, e.g.
+/// masterlist groups when linting a userlist, and which the YAML may refer to.
+/// The `default` group is always defined.
+///
+/// If the YAML cannot be parsed as a metadata document, the only issues
+/// returned are a [LintIssueKind::InvalidYaml] issue and any unused anchors
+/// found before the parsing error. Issues are sorted by their position in the
+/// YAML.
+pub fn lint_metadata(yaml: &str, external_groups: &[Group]) -> Vec<LintIssue> {
+    let mut linter = Linter::default();
+
+    linter.check_anchors(yaml);
+
+    match load_document(yaml) {
+        Ok(doc) => linter.check_document(&doc, external_groups),
+        Err(issue) => linter.issues.push(issue),
+    }
+
+    let mut issues = linter.issues;
+    issues.sort();
+    issues.dedup();
+    issues
+}
+
+/// Check the metadata file at the given path for problems, as
+/// [lint_metadata] does.
+pub fn lint_metadata_file(
+    path: &Path,
+    external_groups: &[Group],
+) -> Result<Vec<LintIssue>, LoadMetadataError> {
+    if !path.exists() {
+        return Err(LoadMetadataError::new(
+            path.into(),
+            MetadataDocumentParsingError::PathNotFound,
+        ));
+    }
+
+    logging::trace!("Linting file at \"{}\"", escape_ascii(path));
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| LoadMetadataError::from_io_error(path.into(), e))?;
+
+    Ok(lint_metadata(&content, external_groups))
+}
+
+fn load_document(yaml: &str) -> Result<MarkedYaml<'_>, LintIssue> {
+    let mut docs = MarkedYaml::load_from_str(yaml).map_err(|e| {
+        LintIssue::new(*e.marker(), LintIssueKind::InvalidYaml(e.info().to_owned()))
+    })?;
+
+    let mut docs = docs.drain(..);
+    let Some(doc) = docs.next() else {
+        return Err(LintIssue::new(
+            Marker::new(0, 1, 0),
+            LintIssueKind::InvalidYaml("the file contains no YAML documents".into()),
+        ));
+    };
+    if let Some(next_doc) = docs.next() {
+        return Err(LintIssue::new(
+            next_doc.span.start,
+            LintIssueKind::InvalidYaml("the file contains more than one YAML document".into()),
+        ));
+    }
+
+    let doc = process_merge_keys(doc).map_err(|e| {
+        LintIssue::new(
+            e.start(),
+            LintIssueKind::InvalidYaml(format!("invalid YAML merge key value: {}", e.yaml())),
+        )
+    })?;
+
+    if doc.data.as_mapping().is_some() {
+        Ok(doc)
+    } else {
+        Err(LintIssue::new(
+            doc.span.start,
+            LintIssueKind::InvalidYaml("the document is not a map".into()),
+        ))
+    }
+}
+
+struct GroupDefinition<'a> {
+    name: &'a str,
+    marker: Option<Marker>,
+    after_groups: Vec<(&'a str, Option<Marker>)>,
+}
+
+#[derive(Default)]
+struct Linter {
+    issues: Vec<LintIssue>,
+}
+
+impl Linter {
+    fn push(&mut self, marker: Marker, kind: LintIssueKind) {
+        self.issues.push(LintIssue::new(marker, kind));
+    }
+
+    /// Anchors and aliases are resolved when YAML is loaded, so look at the
+    /// parser's events to find them.
+    fn check_anchors(&mut self, yaml: &str) {
+        let mut anchors = Vec::new();
+        let mut used_anchor_ids = HashSet::new();
+
+        for result in Parser::new_from_str(yaml) {
+            let Ok((event, span)) = result else {
+                break;
+            };
+
+            match event {
+                Event::Scalar(_, _, id, _)
+                | Event::SequenceStart(id, _)
+                | Event::MappingStart(id, _)
+                    if id != 0 =>
+                {
+                    anchors.push((id, span.start));
+                }
+                Event::Alias(id) => {
+                    used_anchor_ids.insert(id);
+                }
+                _ => {}
+            }
+        }
+
+        let unused_nodes: Vec<_> = anchors
+            .into_iter()
+            .filter(|(id, _)| !used_anchor_ids.contains(id))
+            .map(|(_, marker)| marker)
+            .collect();
+
+        for (marker, name) in find_anchors(yaml, &unused_nodes) {
+            self.push(marker, LintIssueKind::UnusedAnchor(name));
+        }
+    }
+
+    fn check_document(&mut self, doc: &MarkedYaml, external_groups: &[Group]) {
+        self.check_values(doc);
+
+        let Some(doc) = doc.data.as_mapping() else {
+            return;
+        };
+
+        let groups = self.check_groups(doc, external_groups);
+        self.check_plugins(doc, &groups);
+        self.check_string_duplicates(get_value(doc, "bash_tags"));
+    }
+
+    /// Check values that can appear in many places in a document.
+    fn check_values(&mut self, yaml: &MarkedYaml) {
+        match &yaml.data {
+            YamlData::Mapping(mapping) => {
+                for (key, value) in mapping {
+                    match key.data.as_str() {
+                        Some("condition" | "constraint") => self.check_condition(value),
+                        Some("content" | "detail") => self.check_english_content(value),
+                        _ => {}
+                    }
+                    self.check_values(value);
+                }
+            }
+            YamlData::Sequence(sequence) => {
+                for value in sequence {
+                    self.check_values(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_condition(&mut self, value: &MarkedYaml) {
+        if let Some(condition) = value.data.as_str()
+            && let Err(e) = Expression::from_str(condition)
+        {
+            self.push(
+                value.span.start,
+                LintIssueKind::InvalidCondition {
+                    condition: condition.to_owned(),
+                    error: e.to_string(),
+                },
+            );
+        }
+    }
+
+    fn check_english_content(&mut self, value: &MarkedYaml) {
+        let Some(contents) = value.data.as_vec() else {
+            return;
+        };
+
+        // Content without a language is English.
+        let has_english = contents.iter().any(|c| {
+            c.data
+                .as_mapping()
+                .and_then(|m| get_value(m, "lang"))
+                .is_none_or(|l| l.data.as_str() == Some("en"))
+        });
+
+        if !contents.is_empty() && !has_english {
+            self.push(value.span.start, LintIssueKind::MissingEnglishContent);
+        }
+    }
+
+    fn check_groups<'a>(
+        &mut self,
+        doc: &'a AnnotatedMapping<MarkedYaml>,
+        external_groups: &'a [Group],
+    ) -> HashSet<&'a str> {
+        let mut definitions: Vec<GroupDefinition> = external_groups
+            .iter()
+            .map(|g| GroupDefinition {
+                name: g.name(),
+                marker: None,
+                after_groups: g
+                    .after_groups()
+                    .iter()
+                    .map(|a| (a.as_str(), None))
+                    .collect(),
+            })
+            .collect();
+
+        definitions.push(GroupDefinition {
+            name: Group::DEFAULT_NAME,
+            marker: None,
+            after_groups: Vec::new(),
+        });
+
+        for group in yaml_sequence(get_value(doc, "groups")) {
+            let Some(mapping) = group.data.as_mapping() else {
+                continue;
+            };
+            let Some(name) = get_value(mapping, "name").and_then(|n| n.data.as_str()) else {
+                continue;
+            };
+
+            let after_groups = yaml_sequence(get_value(mapping, "after"))
+                .iter()
+                .filter_map(|a| a.data.as_str().map(|s| (s, Some(a.span.start))))
+                .collect();
+
+            definitions.push(GroupDefinition {
+                name,
+                marker: Some(group.span.start),
+                after_groups,
+            });
+        }
+
+        let mut graph: Graph<&str, ()> = Graph::new();
+        let mut nodes: HashMap<&str, NodeIndex> = HashMap::new();
+        for definition in &definitions {
+            nodes
+                .entry(definition.name)
+                .or_insert_with(|| graph.add_node(definition.name));
+        }
+
+        for definition in &definitions {
+            let Some(node) = nodes.get(definition.name) else {
+                continue;
+            };
+
+            for (after_group, marker) in &definition.after_groups {
+                if let Some(after_node) = nodes.get(after_group) {
+                    graph.add_edge(*after_node, *node, ());
+                } else if let Some(marker) = marker {
+                    self.push(
+                        *marker,
+                        LintIssueKind::UndefinedGroup((*after_group).to_owned()),
+                    );
+                }
+            }
+        }
+
+        for mut component in tarjan_scc(&graph) {
+            let is_cycle = match component.as_slice() {
+                [node] => graph.contains_edge(*node, *node),
+                _ => true,
+            };
+            if !is_cycle {
+                continue;
+            }
+
+            component.sort_unstable();
+            let names: Vec<&str> = component
+                .iter()
+                .filter_map(|n| graph.node_weight(*n).copied())
+                .collect();
+
+            // Only report cycles that involve this document's groups.
+            let marker = definitions
+                .iter()
+                .find(|d| d.marker.is_some() && names.contains(&d.name))
+                .and_then(|d| d.marker);
+
+            if let Some(marker) = marker {
+                self.push(
+                    marker,
+                    LintIssueKind::GroupCycle(names.into_iter().map(str::to_owned).collect()),
+                );
+            }
+        }
+
+        definitions.iter().map(|d| d.name).collect()
+    }
+
+    fn check_plugins(&mut self, doc: &AnnotatedMapping<MarkedYaml>, groups: &HashSet<&str>) {
+        let mut exact_plugins = Vec::new();
+        let mut regex_plugins = Vec::new();
+
+        for plugin in yaml_sequence(get_value(doc, "plugins")) {
+            let Some(mapping) = plugin.data.as_mapping() else {
+                continue;
+            };
+
+            if let Some(group) = get_value(mapping, "group")
+                && let Some(name) = group.data.as_str()
+                && !groups.contains(name)
+            {
+                self.push(
+                    group.span.start,
+                    LintIssueKind::UndefinedGroup(name.to_owned()),
+                );
+            }
+
+            self.check_tags(get_value(mapping, "tag"));
+
+            for key in ["dirty", "clean"] {
+                for info in yaml_sequence(get_value(mapping, key)) {
+                    if let Some(crc) = info.data.as_mapping().and_then(|m| get_value(m, "crc")) {
+                        self.check_crc(crc);
+                    }
+                }
+            }
+
+            if let Some(name) = get_value(mapping, "name").and_then(|n| n.data.as_str())
+                && let Ok(metadata) = PluginMetadata::new(name)
+            {
+                if metadata.is_regex_plugin() {
+                    regex_plugins.push((metadata, plugin.span.start));
+                } else {
+                    exact_plugins.push(name);
+                }
+            }
+        }
+
+        for (regex_plugin, marker) in regex_plugins {
+            for plugin in &exact_plugins {
+                if regex_plugin.name_matches(plugin) {
+                    self.push(
+                        marker,
+                        LintIssueKind::ShadowedPlugin {
+                            regex: regex_plugin.name().to_owned(),
+                            plugin: (*plugin).to_owned(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_tags(&mut self, tags: Option<&MarkedYaml>) {
+        let mut seen = Vec::new();
+        for tag_yaml in yaml_sequence(tags) {
+            // Invalid tags are reported by other checks or not at all.
+            let Ok(tag) = Tag::try_from_yaml(tag_yaml) else {
+                continue;
+            };
+
+            if seen.contains(&tag) {
+                self.push(
+                    tag_yaml.span.start,
+                    LintIssueKind::DuplicateTag(tag.name().to_owned()),
+                );
+            } else {
+                seen.push(tag);
+            }
+        }
+    }
+
+    fn check_string_duplicates(&mut self, values: Option<&MarkedYaml>) {
+        let mut seen = HashSet::new();
+        for value in yaml_sequence(values) {
+            if let Some(s) = value.data.as_str()
+                && !seen.insert(s)
+            {
+                self.push(value.span.start, LintIssueKind::DuplicateTag(s.to_owned()));
+            }
+        }
+    }
+
+    fn check_crc(&mut self, crc: &MarkedYaml) {
+        let value = match &crc.data {
+            YamlData::Value(Scalar::Integer(i)) => {
+                if u32::try_from(*i).is_ok() {
+                    return;
+                }
+                i.to_string()
+            }
+            YamlData::Value(Scalar::String(s)) => s.to_string(),
+            YamlData::Value(Scalar::FloatingPoint(f)) => f.to_string(),
+            YamlData::Value(Scalar::Boolean(b)) => b.to_string(),
+            _ => String::new(),
+        };
+
+        self.push(crc.span.start, LintIssueKind::InvalidCrc(value));
+    }
+}
+
+fn yaml_sequence<'a, 'b>(value: Option<&'a MarkedYaml<'b>>) -> &'a [MarkedYaml<'b>] {
+    value
+        .and_then(|v| v.data.as_vec())
+        .map_or(&[], Vec::as_slice)
+}
+
+/// The parser gives the positions of anchored nodes, not their anchors, so
+/// scan through the YAML once to find the position and name of the anchor
+/// that precedes each node. The nodes must be in the order that they appear
+/// in the YAML.
+fn find_anchors(yaml: &str, nodes: &[Marker]) -> Vec<(Marker, String)> {
+    let mut anchors = Vec::with_capacity(nodes.len());
+    let mut nodes = nodes.iter().peekable();
+    let mut last_anchor: Option<(Marker, String)> = None;
+    let mut is_reading_name = false;
+    let mut line = 1;
+    let mut col = 0;
+
+    for (index, c) in yaml.chars().enumerate() {
+        while let Some(node) = nodes.next_if(|n| n.index() <= index) {
+            anchors.push(last_anchor.clone().unwrap_or((*node, String::new())));
+        }
+
+        if nodes.peek().is_none() {
+            break;
+        }
+
+        if c == '&' {
+            last_anchor = Some((Marker::new(index, line, col), String::new()));
+            is_reading_name = true;
+        } else if is_reading_name {
+            if c.is_whitespace() || matches!(c, ',' | '[' | ']' | '{' | '}') {
+                is_reading_name = false;
+            } else if let Some((_, name)) = &mut last_anchor {
+                name.push(c);
+            }
+        }
+
+        if c == '\n' {
+            line += 1;
+            col = 0;
+        } else {
+            col += 1;
+        }
+    }
+
+    anchors.extend(nodes.map(|node| last_anchor.clone().unwrap_or((*node, String::new()))));
+
+    anchors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(yaml: &str) -> Vec<LintIssueKind> {
+        lint_metadata(yaml, &[])
+            .into_iter()
+            .map(|i| i.kind)
+            .collect()
+    }
+
+    mod lint_metadata {
+        use super::*;
+
+        #[test]
+        fn should_return_no_issues_for_valid_metadata() {
+            let yaml = "
+groups:
+  - name: early
+  - name: late
+    after: [early, default]
+plugins:
+  - name: Blank.esp
+    group: late
+    tag: [Relev, -Delev]
+    dirty:
+      - crc: 0xFFFFFFFF
+        util: xEdit
+        detail:
+          - lang: de
+            text: Deutsch
+          - lang: en
+            text: English
+";
+            assert!(lint_metadata(yaml, &[]).is_empty());
+        }
+
+        #[test]
+        fn should_report_invalid_yaml() {
+            let issues = lint_metadata("plugins: [", &[]);
+
+            assert_eq!(1, issues.len());
+            assert!(matches!(issues[0].kind(), LintIssueKind::InvalidYaml(_)));
+        }
+
+        #[test]
+        fn should_report_undefined_groups_with_their_position() {
+            let yaml = "
+groups:
+  - name: a
+    after: [missing]
+plugins:
+  - name: Blank.esp
+    group: other
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(2, issues.len());
+            assert_eq!(
+                &LintIssueKind::UndefinedGroup("missing".into()),
+                issues[0].kind()
+            );
+            assert_eq!(4, issues[0].line());
+            assert_eq!(12, issues[0].column());
+            assert_eq!(
+                &LintIssueKind::UndefinedGroup("other".into()),
+                issues[1].kind()
+            );
+            assert_eq!(7, issues[1].line());
+            assert_eq!(11, issues[1].column());
+        }
+
+        #[test]
+        fn should_use_external_groups_as_defined_groups() {
+            let yaml = "
+groups:
+  - name: a
+    after: [b]
+plugins:
+  - name: Blank.esp
+    group: b
+";
+            let issues = lint_metadata(yaml, &[Group::new("b".into())]);
+
+            assert!(issues.is_empty());
+        }
+
+        #[test]
+        fn should_report_group_cycles() {
+            let yaml = "
+groups:
+  - name: a
+    after: [c]
+  - name: b
+    after: [a]
+  - name: c
+    after: [b]
+  - name: d
+    after: [d]
+";
+            let kinds = kinds(yaml);
+
+            assert_eq!(
+                vec![
+                    LintIssueKind::GroupCycle(vec!["a".into(), "b".into(), "c".into()]),
+                    LintIssueKind::GroupCycle(vec!["d".into()]),
+                ],
+                kinds
+            );
+        }
+
+        #[test]
+        fn should_report_group_cycles_that_involve_external_groups() {
+            let yaml = "
+groups:
+  - name: b
+    after: [a]
+";
+            let external_groups = [Group::new("a".into()).with_after_groups(vec!["b".into()])];
+            let issues = lint_metadata(yaml, &external_groups);
+
+            assert_eq!(1, issues.len());
+            assert_eq!(
+                &LintIssueKind::GroupCycle(vec!["a".into(), "b".into()]),
+                issues[0].kind()
+            );
+            assert_eq!(3, issues[0].line());
+        }
+
+        #[test]
+        fn should_report_invalid_conditions() {
+            let yaml = "
+globals:
+  - type: say
+    content: text
+    condition: 'file(\"a.esp\"'
+plugins:
+  - name: Blank.esp
+    after:
+      - name: Other.esp
+        constraint: 'invalid'
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(2, issues.len());
+            assert!(matches!(
+                issues[0].kind(),
+                LintIssueKind::InvalidCondition { condition, .. } if condition == "file(\"a.esp\""
+            ));
+            assert_eq!(5, issues[0].line());
+            assert!(matches!(
+                issues[1].kind(),
+                LintIssueKind::InvalidCondition { condition, .. } if condition == "invalid"
+            ));
+            assert_eq!(10, issues[1].line());
+        }
+
+        #[test]
+        fn should_report_regex_entries_that_match_exact_entries() {
+            let yaml = "
+plugins:
+  - name: Blank.esp
+  - name: 'Blank\\.es(p|m)'
+  - name: 'Other\\.esp'
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(1, issues.len());
+            assert_eq!(
+                &LintIssueKind::ShadowedPlugin {
+                    regex: "Blank\\.es(p|m)".into(),
+                    plugin: "Blank.esp".into()
+                },
+                issues[0].kind()
+            );
+            assert_eq!(4, issues[0].line());
+        }
+
+        #[test]
+        fn should_report_duplicate_tags() {
+            let yaml = "
+bash_tags: [Relev, Delev, Relev]
+plugins:
+  - name: Blank.esp
+    tag:
+      - Relev
+      - -Relev
+      - Relev
+      - name: Relev
+        condition: 'file(\"a.esp\")'
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(2, issues.len());
+            assert_eq!(
+                &LintIssueKind::DuplicateTag("Relev".into()),
+                issues[0].kind()
+            );
+            assert_eq!(2, issues[0].line());
+            assert_eq!(
+                &LintIssueKind::DuplicateTag("Relev".into()),
+                issues[1].kind()
+            );
+            assert_eq!(8, issues[1].line());
+        }
+
+        #[test]
+        fn should_report_content_with_no_english_text() {
+            let yaml = "
+globals:
+  - type: say
+    content:
+      - lang: de
+        text: Deutsch
+      - lang: fr
+        text: Francais
+  - type: say
+    content:
+      - text: English
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(1, issues.len());
+            assert_eq!(&LintIssueKind::MissingEnglishContent, issues[0].kind());
+            assert_eq!(5, issues[0].line());
+        }
+
+        #[test]
+        fn should_report_unused_anchors() {
+            let yaml = "
+common:
+  - &used 'file(\"a.esp\")'
+  - &unused
+    type: say
+    content: text
+plugins:
+  - name: Blank.esp
+    after:
+      - name: Other.esp
+        condition: *used
+";
+            let issues = lint_metadata(yaml, &[]);
+
+            assert_eq!(1, issues.len());
+            assert_eq!(
+                &LintIssueKind::UnusedAnchor("unused".into()),
+                issues[0].kind()
+            );
+            assert_eq!(4, issues[0].line());
+            assert_eq!(4, issues[0].column());
+        }
+
+        #[test]
+        fn should_report_each_unused_anchor_at_its_own_position() {
+            let yaml = "common: [&first 1, &second {a: &third 2}]";
+            let issues: Vec<_> = lint_metadata(yaml, &[])
+                .into_iter()
+                .map(|i| (i.line(), i.column(), i.kind))
+                .collect();
+
+            assert_eq!(
+                vec![
+                    (1, 9, LintIssueKind::UnusedAnchor("first".into())),
+                    (1, 19, LintIssueKind::UnusedAnchor("second".into())),
+                    (1, 31, LintIssueKind::UnusedAnchor("third".into())),
+                ],
+                issues
+            );
+        }
+
+        #[test]
+        fn should_report_invalid_crcs() {
+            let yaml = "
+plugins:
+  - name: Blank.esp
+    dirty:
+      - crc: 0x100000000
+        util: xEdit
+    clean:
+      - crc: -1
+        util: xEdit
+      - crc: abc
+        util: xEdit
+";
+            let kinds = kinds(yaml);
+
+            assert_eq!(
+                vec![
+                    LintIssueKind::InvalidCrc("4294967296".into()),
+                    LintIssueKind::InvalidCrc("-1".into()),
+                    LintIssueKind::InvalidCrc("abc".into()),
+                ],
+                kinds
+            );
+        }
+    }
+}
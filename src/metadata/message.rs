@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, path::Path, sync::Arc};
 
 use saphyr::{MarkedYaml, Scalar, YamlData};
 
@@ -7,6 +7,7 @@ use super::{
         ExpectedType, MetadataParsingErrorReason, MultilingualMessageContentsError,
         ParseMetadataError,
     },
+    provenance::{MetadataSource, Provenance, ProvenanceField},
     yaml::{
         EmitYaml, TryFromYaml, YamlEmitter, YamlObjectType, as_mapping, get_required_string_value,
         get_strings_vec_value, get_value, parse_condition,
@@ -153,6 +154,7 @@ pub struct Message {
     level: MessageType,
     content: Box<[MessageContent]>,
     condition: Option<Box<str>>,
+    provenance: ProvenanceField,
 }
 
 impl Message {
@@ -164,6 +166,7 @@ impl Message {
             level: message_type,
             content: Box::new([MessageContent::new(content)]),
             condition: None,
+            provenance: ProvenanceField::default(),
         }
     }
 
@@ -180,6 +183,7 @@ impl Message {
             level: message_type,
             content: content.into_boxed_slice(),
             condition: None,
+            provenance: ProvenanceField::default(),
         })
     }
 
@@ -204,6 +208,16 @@ impl Message {
     pub fn condition(&self) -> Option<&str> {
         self.condition.as_deref()
     }
+
+    /// Get where the message was loaded from, if it was loaded from a
+    /// metadata file.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.get()
+    }
+
+    pub(super) fn set_provenance_file(&mut self, source: MetadataSource, path: Option<&Arc<Path>>) {
+        self.provenance.set_file(source, path);
+    }
 }
 
 pub(crate) fn validate_message_contents(
@@ -308,6 +322,7 @@ impl TryFromYaml for Message {
             level: message_type,
             content,
             condition,
+            provenance: ProvenanceField::new(value.span.start),
         })
     }
 }
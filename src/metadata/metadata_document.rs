@@ -1,6 +1,8 @@
 use std::{
     collections::{HashMap, HashSet},
+    io::Read,
     path::Path,
+    sync::Arc,
 };
 
 use saphyr::{LoadableYamlNode, MarkedYaml, YamlData};
@@ -16,21 +18,35 @@ use super::{
     group::Group,
     message::Message,
     plugin_metadata::PluginMetadata,
+    provenance::MetadataSource,
+    round_trip::patch_yaml,
     yaml::{
         EmitYaml, TryFromYaml, YamlEmitter, YamlObjectType, get_slice_value, process_merge_keys,
     },
 };
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct MetadataDocument {
     bash_tags: Vec<String>,
     groups: Vec<Group>,
     messages: Vec<Message>,
     plugins: HashMap<Filename, PluginMetadata>,
     regex_plugins: Vec<PluginMetadata>,
+    source: MetadataSource,
+    /// The YAML that the metadata was loaded from, used to preserve its
+    /// formatting when the metadata is saved. Only the userlist is saved, so
+    /// other documents don't keep their YAML.
+    original_yaml: Option<String>,
 }
 
 impl MetadataDocument {
+    pub fn new(source: MetadataSource) -> Self {
+        Self {
+            source,
+            ..Default::default()
+        }
+    }
+
     pub fn load(&mut self, file_path: &Path) -> Result<(), LoadMetadataError> {
         if !file_path.exists() {
             return Err(LoadMetadataError::new(
@@ -44,7 +60,7 @@ impl MetadataDocument {
         let content = std::fs::read_to_string(file_path)
             .map_err(|e| LoadMetadataError::from_io_error(file_path.into(), e))?;
 
-        self.load_from_str(&content)
+        self.load_from_str_with_path(&content, Some(file_path))
             .map_err(|e| LoadMetadataError::new(file_path.into(), e))?;
 
         logging::trace!(
@@ -82,7 +98,7 @@ impl MetadataDocument {
 
         let masterlist = replace_prelude(masterlist, &prelude);
 
-        self.load_from_str(&masterlist)
+        self.load_from_str_with_path(&masterlist, Some(masterlist_path))
             .map_err(|e| LoadMetadataError::new(masterlist_path.into(), e))?;
 
         logging::trace!(
@@ -93,7 +109,47 @@ impl MetadataDocument {
         Ok(())
     }
 
-    fn load_from_str(&mut self, string: &str) -> Result<(), MetadataDocumentParsingError> {
+    pub fn load_from_str(&mut self, string: &str) -> Result<(), LoadMetadataError> {
+        self.load_from_str_with_path(string, None)
+            .map_err(LoadMetadataError::without_path)?;
+
+        logging::trace!("Successfully loaded metadata from a string.");
+
+        Ok(())
+    }
+
+    pub fn load_from_reader<R: Read>(&mut self, reader: R) -> Result<(), LoadMetadataError> {
+        let content = read_to_string(reader)?;
+
+        self.load_from_str(&content)
+    }
+
+    pub fn load_with_prelude_from_str(
+        &mut self,
+        masterlist: &str,
+        prelude: &str,
+    ) -> Result<(), LoadMetadataError> {
+        let masterlist = replace_prelude(masterlist.to_owned(), prelude);
+
+        self.load_from_str(&masterlist)
+    }
+
+    pub fn load_with_prelude_from_reader<M: Read, P: Read>(
+        &mut self,
+        masterlist: M,
+        prelude: P,
+    ) -> Result<(), LoadMetadataError> {
+        let masterlist = read_to_string(masterlist)?;
+        let prelude = read_to_string(prelude)?;
+
+        self.load_with_prelude_from_str(&masterlist, &prelude)
+    }
+
+    fn load_from_str_with_path(
+        &mut self,
+        string: &str,
+        path: Option<&Path>,
+    ) -> Result<(), MetadataDocumentParsingError> {
         let mut docs = MarkedYaml::load_from_str(string)?;
 
         let doc = docs
@@ -106,7 +162,55 @@ impl MetadataDocument {
         }
         let doc = process_merge_keys(doc)?;
 
-        let YamlData::Mapping(doc) = doc.data else {
+        self.load_from_yaml(&doc, path)?;
+        if self.source == MetadataSource::Userlist {
+            self.original_yaml = Some(string.to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Load metadata from JSON that follows the same structure as the YAML
+    /// metadata format.
+    #[cfg(feature = "json")]
+    pub fn load_from_json_str(&mut self, string: &str) -> Result<(), LoadMetadataError> {
+        let value: serde_json::Value = serde_json::from_str(string).map_err(|e| {
+            LoadMetadataError::without_path(MetadataDocumentParsingError::JsonError(e))
+        })?;
+
+        self.load_from_yaml(&super::json::to_marked_yaml(&value), None)
+            .map_err(LoadMetadataError::without_path)?;
+
+        logging::trace!("Successfully loaded metadata from a JSON string.");
+
+        Ok(())
+    }
+
+    /// Serialise the metadata as JSON with the same structure as the YAML
+    /// that [Self::save] would write if there was no original YAML.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        struct Document<'a>(&'a MetadataDocument);
+
+        impl serde::Serialize for Document<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                super::json::serialize_yaml(&self.0.to_yaml(), serializer)
+            }
+        }
+
+        serde_json::to_string_pretty(&Document(self))
+    }
+
+    /// Replace the document's metadata with the metadata in the given YAML
+    /// node, which must have had its merge keys processed.
+    fn load_from_yaml(
+        &mut self,
+        doc: &MarkedYaml,
+        path: Option<&Path>,
+    ) -> Result<(), MetadataDocumentParsingError> {
+        let path: Option<Arc<Path>> = path.map(Arc::from);
+
+        let YamlData::Mapping(doc) = &doc.data else {
             return Err(ParseMetadataError::unexpected_type(
                 doc.span.start,
                 YamlObjectType::MetadataDocument,
@@ -117,8 +221,9 @@ impl MetadataDocument {
 
         let mut plugins: HashMap<Filename, PluginMetadata> = HashMap::new();
         let mut regex_plugins: Vec<PluginMetadata> = Vec::new();
-        for plugin_yaml in get_slice_value(&doc, "plugins", YamlObjectType::MetadataDocument)? {
-            let plugin = PluginMetadata::try_from_yaml(plugin_yaml)?;
+        for plugin_yaml in get_slice_value(doc, "plugins", YamlObjectType::MetadataDocument)? {
+            let mut plugin = PluginMetadata::try_from_yaml(plugin_yaml)?;
+            plugin.set_provenance_file(self.source, path.as_ref());
             if plugin.is_regex_plugin() {
                 regex_plugins.push(plugin);
             } else {
@@ -134,14 +239,19 @@ impl MetadataDocument {
             }
         }
 
-        let messages = get_slice_value(&doc, "globals", YamlObjectType::MetadataDocument)?
+        let messages = get_slice_value(doc, "globals", YamlObjectType::MetadataDocument)?
             .iter()
-            .map(Message::try_from_yaml)
+            .map(|m| {
+                Message::try_from_yaml(m).map(|mut message| {
+                    message.set_provenance_file(self.source, path.as_ref());
+                    message
+                })
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         let mut bash_tags = Vec::new();
         let mut str_set = HashSet::new();
-        for bash_tag_yaml in get_slice_value(&doc, "bash_tags", YamlObjectType::MetadataDocument)? {
+        for bash_tag_yaml in get_slice_value(doc, "bash_tags", YamlObjectType::MetadataDocument)? {
             let bash_tag: &str = match bash_tag_yaml.data.as_str() {
                 Some(b) => b,
                 None => {
@@ -169,8 +279,9 @@ impl MetadataDocument {
 
         let mut group_names = HashSet::new();
         let mut groups = Vec::new();
-        for group_yaml in get_slice_value(&doc, "groups", YamlObjectType::MetadataDocument)? {
-            let group = Group::try_from_yaml(group_yaml)?;
+        for group_yaml in get_slice_value(doc, "groups", YamlObjectType::MetadataDocument)? {
+            let mut group = Group::try_from_yaml(group_yaml)?;
+            group.set_provenance_file(self.source, path.as_ref());
 
             let name = group.name().to_owned();
             if group_names.contains(&name) {
@@ -195,13 +306,47 @@ impl MetadataDocument {
         self.messages = messages;
         self.bash_tags = bash_tags;
         self.groups = groups;
+        self.original_yaml = None;
 
         Ok(())
     }
 
+    /// Save the metadata to the given path.
+    ///
+    /// If the metadata is a userlist that was loaded from YAML, only the plugin
+    /// and group entries that have changed since then are rewritten, so that
+    /// the YAML's comments, anchors and formatting are otherwise preserved. If
+    /// that's not possible, all the metadata is written out afresh.
     pub fn save(&self, file_path: &Path) -> Result<(), WriteMetadataError> {
         logging::trace!("Saving metadata list to: \"{}\"", escape_ascii(file_path));
 
+        let contents = self.patch_original_yaml().unwrap_or_else(|| self.to_yaml());
+
+        std::fs::write(file_path, contents)
+            .map_err(|e| WriteMetadataError::new(file_path.into(), e.into()))?;
+
+        Ok(())
+    }
+
+    fn patch_original_yaml(&self) -> Option<String> {
+        let patched = patch_yaml(self.original_yaml.as_deref()?, self)?;
+
+        let mut patched_document = MetadataDocument::new(self.source);
+        if patched_document
+            .load_from_str_with_path(&patched, None)
+            .is_ok()
+            && patched_document == *self
+        {
+            Some(patched)
+        } else {
+            logging::debug!(
+                "Could not apply metadata changes to the original YAML, writing all metadata instead"
+            );
+            None
+        }
+    }
+
+    fn to_yaml(&self) -> String {
         let mut emitter = YamlEmitter::new();
 
         if !self.bash_tags.is_empty() {
@@ -240,15 +385,12 @@ impl MetadataDocument {
             emitter.end_array();
         }
 
-        let mut contents = emitter.into_string();
+        let contents = emitter.into_string();
         if contents.is_empty() {
-            contents = "{}".into();
+            "{}".into()
+        } else {
+            contents
         }
-
-        std::fs::write(file_path, contents)
-            .map_err(|e| WriteMetadataError::new(file_path.into(), e.into()))?;
-
-        Ok(())
     }
 
     pub fn bash_tags(&self) -> &[String] {
@@ -287,6 +429,23 @@ impl MetadataDocument {
         }
     }
 
+    /// Get the entries that [MetadataDocument::find_plugin] merges for the
+    /// given plugin, i.e. its exact entry followed by any matching regex
+    /// entries, without merging them.
+    pub fn plugin_entries<'a>(
+        &'a self,
+        plugin_name: &'a str,
+    ) -> impl Iterator<Item = &'a PluginMetadata> {
+        self.plugins
+            .get(&Filename::new(plugin_name.to_owned()))
+            .into_iter()
+            .chain(
+                self.regex_plugins
+                    .iter()
+                    .filter(move |p| p.name_matches(plugin_name)),
+            )
+    }
+
     pub fn set_groups(&mut self, groups: Vec<Group>) {
         // Ensure that the default group is present.
         let default_group_exists = groups.iter().any(|g| g.name() == Group::DEFAULT_NAME);
@@ -302,7 +461,15 @@ impl MetadataDocument {
 
     pub fn set_plugin_metadata(&mut self, plugin_metadata: PluginMetadata) {
         if plugin_metadata.is_regex_plugin() {
-            self.regex_plugins.push(plugin_metadata);
+            if let Some(existing) = self
+                .regex_plugins
+                .iter_mut()
+                .find(|p| p.name() == plugin_metadata.name())
+            {
+                *existing = plugin_metadata;
+            } else {
+                self.regex_plugins.push(plugin_metadata);
+            }
         } else {
             self.plugins.insert(
                 Filename::new(plugin_metadata.name().to_owned()),
@@ -321,9 +488,24 @@ impl MetadataDocument {
         self.messages.clear();
         self.plugins.clear();
         self.regex_plugins.clear();
+        self.original_yaml = None;
     }
 }
 
+/// Documents are equal if they hold the same metadata, no matter where it was
+/// loaded from.
+impl PartialEq for MetadataDocument {
+    fn eq(&self, other: &Self) -> bool {
+        self.bash_tags == other.bash_tags
+            && self.groups == other.groups
+            && self.messages == other.messages
+            && self.plugins == other.plugins
+            && self.regex_plugins == other.regex_plugins
+    }
+}
+
+impl Eq for MetadataDocument {}
+
 impl std::default::Default for MetadataDocument {
     fn default() -> Self {
         Self {
@@ -332,10 +514,47 @@ impl std::default::Default for MetadataDocument {
             messages: Vec::default(),
             plugins: HashMap::default(),
             regex_plugins: Vec::default(),
+            source: MetadataSource::default(),
+            original_yaml: None,
         }
     }
 }
 
+/// Write a metadata list that only holds the given plugins' metadata, in the
+/// given order.
+pub(crate) fn plugins_to_yaml(plugins: &[PluginMetadata]) -> String {
+    let mut emitter = YamlEmitter::new();
+
+    let plugins: Vec<_> = plugins.iter().filter(|p| !p.has_name_only()).collect();
+    if !plugins.is_empty() {
+        emitter.map_key("plugins");
+
+        emitter.begin_array();
+
+        for plugin in plugins {
+            plugin.emit_yaml(&mut emitter);
+        }
+
+        emitter.end_array();
+    }
+
+    let contents = emitter.into_string();
+    if contents.is_empty() {
+        "{}".into()
+    } else {
+        contents
+    }
+}
+
+fn read_to_string<R: Read>(mut reader: R) -> Result<String, LoadMetadataError> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .map_err(|e| LoadMetadataError::without_path(e.into()))?;
+
+    Ok(content)
+}
+
 fn replace_prelude(masterlist: String, prelude: &str) -> String {
     if let Some((start, end)) = split_on_prelude(&masterlist) {
         let prelude = indent_prelude(prelude);
@@ -600,6 +819,100 @@ plugins:
             );
         }
 
+        #[test]
+        fn load_with_prelude_from_str_should_merge_docs() {
+            let masterlist = "prelude:\n  - &ref\n    type: say\n    content: Loaded from same file\nglobals:\n  - *ref";
+            let prelude = "common:\n  - &ref\n    type: say\n    content: Loaded from prelude";
+
+            let mut metadata_list = MetadataDocument::default();
+            metadata_list
+                .load_with_prelude_from_str(masterlist, prelude)
+                .unwrap();
+
+            assert_eq!(
+                [Message::new(
+                    MessageType::Say,
+                    "Loaded from prelude".to_owned()
+                )],
+                metadata_list.messages()
+            );
+        }
+
+        #[test]
+        fn load_with_prelude_from_reader_should_merge_docs() {
+            let masterlist = "prelude:\n  - &ref\n    type: say\n    content: Loaded from same file\nglobals:\n  - *ref";
+            let prelude = "common:\n  - &ref\n    type: say\n    content: Loaded from prelude";
+
+            let mut metadata_list = MetadataDocument::default();
+            metadata_list
+                .load_with_prelude_from_reader(masterlist.as_bytes(), prelude.as_bytes())
+                .unwrap();
+
+            assert_eq!(
+                [Message::new(
+                    MessageType::Say,
+                    "Loaded from prelude".to_owned()
+                )],
+                metadata_list.messages()
+            );
+        }
+
+        #[test]
+        fn load_from_reader_should_deserialise_metadata() {
+            let mut metadata_list = MetadataDocument::default();
+            metadata_list
+                .load_from_reader(METADATA_LIST_YAML.as_bytes())
+                .unwrap();
+
+            let mut expected = MetadataDocument::default();
+            expected.load_from_str(METADATA_LIST_YAML).unwrap();
+
+            assert_eq!(expected, metadata_list);
+            assert_eq!(&["C.Climate", "Relev"], metadata_list.bash_tags());
+        }
+
+        #[test]
+        fn load_from_str_should_error_without_a_path_if_the_metadata_is_invalid() {
+            let mut metadata_list = MetadataDocument::default();
+            let error = metadata_list.load_from_str("plugins: [").unwrap_err();
+
+            assert_eq!("failed to parse metadata", error.to_string());
+            assert!(std::error::Error::source(&error).is_some());
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn load_from_json_str_should_load_metadata_written_by_to_json() {
+            let mut metadata_list = MetadataDocument::default();
+            metadata_list.load_from_str(METADATA_LIST_YAML).unwrap();
+
+            let json = metadata_list.to_json().unwrap();
+
+            let mut json_metadata_list = MetadataDocument::default();
+            json_metadata_list.load_from_json_str(&json).unwrap();
+
+            assert_eq!(metadata_list, json_metadata_list);
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn load_from_json_str_should_validate_metadata_in_the_same_way_as_yaml() {
+            let mut metadata_list = MetadataDocument::default();
+
+            let error = metadata_list
+                .load_from_json_str(r#"{"plugins": [{"name": "A.esp"}, {"name": "a.esp"}]}"#)
+                .unwrap_err();
+            let source = std::error::Error::source(&error).unwrap();
+            assert!(
+                std::error::Error::source(source)
+                    .unwrap()
+                    .to_string()
+                    .starts_with("encountered a metadata parsing error:")
+            );
+
+            assert!(metadata_list.load_from_json_str("{\"plugins\": [").is_err());
+        }
+
         #[test]
         fn load_with_prelude_should_merge_docs_with_lf_line_endings() {
             let tmp_dir = tempdir().unwrap();
@@ -662,6 +975,173 @@ plugins:
             );
         }
 
+        #[test]
+        fn load_should_record_where_metadata_was_loaded_from() {
+            let tmp_dir = tempdir().unwrap();
+
+            let path = tmp_dir.path().join("userlist.yaml");
+            std::fs::write(
+                &path,
+                "groups:
+  - name: group1
+globals:
+  - type: say
+    content: message
+plugins:
+  - name: Blank.esp
+    tag:
+      - Relev
+",
+            )
+            .unwrap();
+
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load(&path).unwrap();
+
+            let group = &metadata.groups()[1];
+            let provenance = group.provenance().unwrap();
+            assert_eq!(MetadataSource::Userlist, provenance.source());
+            assert_eq!(Some(path.as_path()), provenance.path());
+            assert_eq!((2, 4), (provenance.line(), provenance.column()));
+
+            let provenance = metadata.messages()[0].provenance().unwrap();
+            assert_eq!((4, 4), (provenance.line(), provenance.column()));
+
+            let plugin = metadata.find_plugin("Blank.esp").unwrap().unwrap();
+            let provenance = plugin.provenance().unwrap();
+            assert_eq!(Some(path.as_path()), provenance.path());
+            assert_eq!((7, 4), (provenance.line(), provenance.column()));
+
+            let provenance = plugin.tags()[0].provenance().unwrap();
+            assert_eq!(MetadataSource::Userlist, provenance.source());
+            assert_eq!((9, 8), (provenance.line(), provenance.column()));
+        }
+
+        const USERLIST_YAML: &str = "# My userlist
+common:
+  - &quest 'file(\"Quest.esp\")'
+
+groups:
+  - name: early  # Loads first
+  - name: late
+    after: [early]
+
+plugins:
+  # Keep this one early.
+  - name: A.esp
+    group: early
+
+  # Only when the quest is installed.
+  - name: B.esp
+    after:
+      - name: A.esp
+        condition: *quest
+";
+
+        fn save_and_read(metadata: &MetadataDocument) -> String {
+            let tmp_dir = tempdir().unwrap();
+            let path = tmp_dir.path().join("userlist.yaml");
+
+            metadata.save(&path).unwrap();
+
+            std::fs::read_to_string(&path).unwrap()
+        }
+
+        #[test]
+        fn save_should_write_the_original_yaml_if_nothing_has_changed() {
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load_from_str(USERLIST_YAML).unwrap();
+
+            assert_eq!(USERLIST_YAML, save_and_read(&metadata));
+        }
+
+        #[test]
+        fn save_should_replace_only_changed_entries() {
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load_from_str(USERLIST_YAML).unwrap();
+
+            let mut plugin = PluginMetadata::new("A.esp").unwrap();
+            plugin.set_group("late".into());
+            metadata.set_plugin_metadata(plugin);
+
+            let expected = USERLIST_YAML.replace(
+                "  - name: A.esp\n    group: early\n",
+                "  - name: 'A.esp'\n    group: 'late'\n",
+            );
+            assert_eq!(expected, save_and_read(&metadata));
+        }
+
+        #[test]
+        fn save_should_remove_discarded_entries_and_append_new_entries() {
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load_from_str(USERLIST_YAML).unwrap();
+
+            metadata.remove_plugin_metadata("A.esp");
+            let mut plugin = PluginMetadata::new("C.esp").unwrap();
+            plugin.set_group("early".into());
+            metadata.set_plugin_metadata(plugin);
+            let mut groups = metadata.groups().to_vec();
+            groups.push(Group::new("latest".into()).with_after_groups(vec!["late".into()]));
+            metadata.set_groups(groups);
+
+            let expected = USERLIST_YAML
+                .replace(
+                    "  # Keep this one early.\n  - name: A.esp\n    group: early\n",
+                    "",
+                )
+                .replace(
+                    "    after: [early]\n",
+                    "    after: [early]\n  - name: 'latest'\n    after:\n      - late\n",
+                )
+                + "  - name: 'C.esp'\n    group: 'early'\n";
+            assert_eq!(expected, save_and_read(&metadata));
+        }
+
+        #[test]
+        fn save_should_add_missing_lists_to_the_end_of_the_original_yaml() {
+            let yaml = "# Empty\nbash_tags: [Relev]";
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load_from_str(yaml).unwrap();
+
+            let mut plugin = PluginMetadata::new("B.esp").unwrap();
+            plugin.set_group("default".into());
+            metadata.set_plugin_metadata(plugin);
+
+            assert_eq!(
+                "# Empty\nbash_tags: [Relev]\nplugins:\n  - name: 'B.esp'\n    group: 'default'\n",
+                save_and_read(&metadata)
+            );
+        }
+
+        #[test]
+        fn save_should_rewrite_all_metadata_if_entries_use_flow_style() {
+            let yaml = "plugins: [{name: A.esp, group: early}] # Comment";
+            let mut metadata = MetadataDocument::new(MetadataSource::Userlist);
+            metadata.load_from_str(yaml).unwrap();
+
+            let mut plugin = PluginMetadata::new("A.esp").unwrap();
+            plugin.set_group("late".into());
+            metadata.set_plugin_metadata(plugin);
+
+            let output = save_and_read(&metadata);
+
+            assert!(!output.contains("Comment"));
+            let mut other_metadata = MetadataDocument::default();
+            other_metadata.load_from_str(&output).unwrap();
+            assert_eq!(metadata, other_metadata);
+        }
+
+        #[test]
+        fn save_should_only_preserve_the_original_yaml_of_a_userlist() {
+            let mut metadata = MetadataDocument::new(MetadataSource::Masterlist);
+            metadata.load_from_str(USERLIST_YAML).unwrap();
+
+            let output = save_and_read(&metadata);
+
+            assert!(metadata.original_yaml.is_none());
+            assert!(!output.contains("# My userlist"));
+        }
+
         #[test]
         fn save_should_write_the_loaded_metadata() {
             let tmp_dir = tempdir().unwrap();
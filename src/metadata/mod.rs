@@ -1,21 +1,68 @@
 //! Holds all types related to LOOT metadata.
+//!
+//! # JSON
+//!
+//! If the `json` feature is enabled, [File], [Group], [Location], [Message],
+//! [MessageContent], [PluginCleaningData], [PluginMetadata] and [Tag]
+//! implement serde's `Serialize` and `Deserialize` traits, and userlists can
+//! be loaded from and written as JSON using
+//! [Database::load_userlist_from_json][crate::Database::load_userlist_from_json]
+//! and [Database::user_metadata_to_json][crate::Database::user_metadata_to_json].
+//!
+//! The JSON has the same structure as LOOT's YAML metadata format, and is
+//! validated in the same way, though YAML anchors, aliases and merge keys
+//! have no JSON equivalent. In summary:
+//!
+//! - A metadata list is an object with the optional keys `bash_tags` (an
+//!   array of strings), `groups` (an array of groups), `globals` (an array of
+//!   messages) and `plugins` (an array of plugin metadata).
+//! - Plugin metadata is an object with a required `name` string and the
+//!   optional keys `group` (a string), `after`, `req` and `inc` (arrays of
+//!   files), `msg` (an array of messages), `tag` (an array of tags), `dirty`
+//!   and `clean` (arrays of cleaning data) and `url` (an array of locations).
+//! - A group is an object with a required `name` string and the optional
+//!   keys `description` (a string) and `after` (an array of group names).
+//! - A file is a filename string, or an object with a required `name` string
+//!   and the optional keys `display`, `condition` and `constraint` (strings)
+//!   and `detail` (message content).
+//! - A message is an object with required `type` (`say`, `warn` or `error`)
+//!   and `content` keys, and an optional `condition` string. Message content
+//!   is a string, or an array of objects with `lang` and `text` strings.
+//! - A tag is a tag name string, prefixed with `-` if the tag should be
+//!   removed, or an object with a required `name` string in the same form
+//!   and an optional `condition` string.
+//! - Cleaning data is an object with a required `crc` number and `util`
+//!   string, the optional counts `itm`, `udr` and `nav` (numbers) and
+//!   optional `detail` message content.
+//! - A location is a URL string, or an object with required `link` and
+//!   `name` strings.
+//!
+//! Serialised values use the shorter string forms where possible, as is done
+//! when writing YAML.
 pub mod error;
 mod file;
 mod group;
+#[cfg(feature = "json")]
+mod json;
+mod lint;
 mod location;
 mod message;
 pub(crate) mod metadata_document;
 mod plugin_cleaning_data;
 pub(crate) mod plugin_metadata;
+mod provenance;
+mod round_trip;
 mod tag;
 mod yaml;
 
 pub use file::{File, Filename};
 pub use group::Group;
+pub use lint::{LintIssue, LintIssueKind, lint_metadata, lint_metadata_file};
 pub use location::Location;
 pub use message::{Message, MessageContent, MessageType, select_message_content};
 pub use plugin_cleaning_data::PluginCleaningData;
 pub use plugin_metadata::PluginMetadata;
+pub use provenance::{MetadataSource, Provenance};
 pub use tag::{Tag, TagSuggestion};
 
 #[cfg(test)]
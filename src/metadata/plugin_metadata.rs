@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, path::Path, sync::Arc};
 
 use fancy_regex::{Error as RegexImplError, Regex};
 use saphyr::MarkedYaml;
@@ -11,6 +11,7 @@ use super::{
     location::Location,
     message::Message,
     plugin_cleaning_data::PluginCleaningData,
+    provenance::{MetadataSource, Provenance, ProvenanceField},
     tag::Tag,
     yaml::{
         EmitYaml, TryFromYaml, YamlEmitter, YamlObjectType, as_mapping, get_required_string_value,
@@ -33,6 +34,7 @@ pub struct PluginMetadata {
     dirty_info: Box<[PluginCleaningData]>,
     clean_info: Box<[PluginCleaningData]>,
     locations: Box<[Location]>,
+    provenance: ProvenanceField,
 }
 
 impl PluginMetadata {
@@ -97,6 +99,17 @@ impl PluginMetadata {
         &self.locations
     }
 
+    /// Get where the plugin's metadata was loaded from, if it was loaded from
+    /// a metadata file.
+    ///
+    /// Metadata that is merged from other entries keeps its own provenance,
+    /// e.g. a message that came from a regex entry will give the position of
+    /// that message, while this gives the position of the entry that was
+    /// merged into.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.get()
+    }
+
     /// Set the plugin's group.
     pub fn set_group(&mut self, group: String) {
         self.group = Some(group.into_boxed_str());
@@ -152,12 +165,17 @@ impl PluginMetadata {
     ///
     /// If an equal metadata object already exists in this PluginMetadata
     /// object, it is not duplicated. This object's group is replaced by the
-    /// given object's group if the latter is explicit.
+    /// given object's group if the latter is explicit. If this object has no
+    /// provenance, it takes the given object's provenance.
     pub fn merge_metadata(&mut self, plugin: &PluginMetadata) {
         if plugin.has_name_only() {
             return;
         }
 
+        if self.provenance.get().is_none() {
+            self.provenance.clone_from(&plugin.provenance);
+        }
+
         if self.group.is_none() && plugin.group.is_some() {
             self.group.clone_from(&plugin.group);
         }
@@ -218,6 +236,18 @@ impl PluginMetadata {
         emitter.into_string()
     }
 
+    pub(super) fn set_provenance_file(&mut self, source: MetadataSource, path: Option<&Arc<Path>>) {
+        self.provenance.set_file(source, path);
+
+        for message in &mut self.messages {
+            message.set_provenance_file(source, path);
+        }
+
+        for tag in &mut self.tags {
+            tag.set_provenance_file(source, path);
+        }
+    }
+
     pub(crate) fn filter_by_constraints(
         mut self,
         database: &Database,
@@ -436,6 +466,7 @@ impl TryFromYaml for PluginMetadata {
             clean_info,
             tags,
             locations,
+            provenance: ProvenanceField::new(value.span.start),
         })
     }
 }
@@ -921,6 +952,21 @@ mod tests {
             assert!(PluginMetadata::try_from_yaml(&yaml).is_err());
         }
 
+        #[test]
+        fn should_record_position_without_affecting_equality() {
+            let yaml = parse("\n\n  name: 'Blank.esp'\n  group: group1");
+
+            let plugin = PluginMetadata::try_from_yaml(&yaml).unwrap();
+
+            let provenance = plugin.provenance().unwrap();
+            assert_eq!((3, 2), (provenance.line(), provenance.column()));
+            assert_eq!(None, provenance.path());
+
+            let mut expected = PluginMetadata::new("Blank.esp").unwrap();
+            expected.set_group("group1".into());
+            assert_eq!(expected, plugin);
+        }
+
         #[test]
         fn should_store_all_given_data() {
             let yaml = parse(
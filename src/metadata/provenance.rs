@@ -0,0 +1,139 @@
+use std::{cmp::Ordering, path::Path, sync::Arc};
+
+use saphyr::Marker;
+
+/// The kinds of metadata file that metadata can be loaded from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum MetadataSource {
+    #[default]
+    Masterlist,
+    /// An additional metadata layer loaded between the masterlist and the
+    /// userlist.
+    Layer,
+    Userlist,
+}
+
+impl std::fmt::Display for MetadataSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataSource::Masterlist => write!(f, "masterlist"),
+            MetadataSource::Layer => write!(f, "metadata layer"),
+            MetadataSource::Userlist => write!(f, "userlist"),
+        }
+    }
+}
+
+/// Where a metadata object was loaded from.
+///
+/// If a masterlist was loaded with a prelude, the line and column are
+/// positions in the masterlist after the prelude was substituted into it. If
+/// the object was defined using a YAML alias, the position is that of the
+/// anchored value that the alias refers to.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Provenance {
+    source: MetadataSource,
+    path: Option<Arc<Path>>,
+    line: usize,
+    column: usize,
+}
+
+impl Provenance {
+    pub(super) fn new(marker: Marker) -> Self {
+        Self {
+            source: MetadataSource::default(),
+            path: None,
+            line: marker.line(),
+            column: marker.col(),
+        }
+    }
+
+    /// Get the kind of metadata file that the object was loaded from.
+    pub fn source(&self) -> MetadataSource {
+        self.source
+    }
+
+    /// Get the path of the file that the object was loaded from, if it was
+    /// loaded from a file.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Get the line that the object starts at. Lines are counted from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the column that the object starts at. Columns are counted from 0.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "\"{}\" ", crate::escape_ascii(path))?;
+        }
+
+        write!(
+            f,
+            "({}) line {} column {}",
+            self.source, self.line, self.column
+        )
+    }
+}
+
+/// Holds a metadata object's provenance without it affecting how the object
+/// is compared or hashed, so that the same metadata loaded from different
+/// places is still equal.
+#[derive(Clone, Debug, Default)]
+pub(super) struct ProvenanceField(Option<Box<Provenance>>);
+
+impl ProvenanceField {
+    /// Create the field from the position that an object was parsed from.
+    /// Objects that weren't parsed from text (e.g. those deserialised from
+    /// JSON) have a position on line 0, and have no provenance.
+    pub(super) fn new(marker: Marker) -> Self {
+        if marker.line() == 0 {
+            Self(None)
+        } else {
+            Self(Some(Box::new(Provenance::new(marker))))
+        }
+    }
+
+    pub(super) fn get(&self) -> Option<&Provenance> {
+        self.0.as_deref()
+    }
+
+    pub(super) fn set_file(&mut self, source: MetadataSource, path: Option<&Arc<Path>>) {
+        if let Some(provenance) = &mut self.0 {
+            provenance.source = source;
+            provenance.path = path.cloned();
+        }
+    }
+}
+
+impl PartialEq for ProvenanceField {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ProvenanceField {}
+
+impl PartialOrd for ProvenanceField {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ProvenanceField {
+    fn cmp(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl std::hash::Hash for ProvenanceField {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
@@ -0,0 +1,328 @@
+use saphyr::{AnnotatedMapping, LoadableYamlNode, MarkedYaml};
+
+use super::{
+    group::Group,
+    metadata_document::MetadataDocument,
+    plugin_metadata::PluginMetadata,
+    yaml::{EmitYaml, TryFromYaml, YamlEmitter, get_value, process_merge_keys},
+};
+
+/// Apply the differences between the metadata in the `original` YAML and the
+/// metadata in `document` to the original YAML text, so that unchanged plugin
+/// and group entries keep their formatting, comments and anchors, and changed
+/// entries are replaced in place.
+///
+/// Returns `None` if the changes can't be applied to the original text, e.g.
+/// because it lists entries using flow style. The result is not guaranteed to
+/// hold the same metadata as `document`, so it should be checked before it is
+/// used.
+pub(super) fn patch_yaml(original: &str, document: &MetadataDocument) -> Option<String> {
+    let mut text = original.to_owned();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    let doc = MarkedYaml::load_from_str(&text).ok()?.pop()?;
+    let doc = process_merge_keys(doc).ok()?;
+    let mapping = doc.data.as_mapping()?;
+
+    let mut exact_plugins: Vec<&PluginMetadata> = document
+        .plugins_iter()
+        .filter(|p| !p.is_regex_plugin())
+        .collect();
+    exact_plugins.sort_by_key(|p| unicase::UniCase::new(p.name()));
+
+    let plugins: Vec<&PluginMetadata> = exact_plugins
+        .into_iter()
+        .chain(document.plugins_iter().filter(|p| p.is_regex_plugin()))
+        .collect();
+    let groups: Vec<&Group> = document.groups().iter().collect();
+
+    let mut patcher = Patcher::new(&text, mapping);
+    patcher.patch_entries("groups", &groups)?;
+    patcher.patch_entries("plugins", &plugins)?;
+
+    patcher.apply()
+}
+
+trait Entry: TryFromYaml + EmitYaml + PartialEq {
+    /// Check if the given object is for the same plugin or group as this
+    /// object.
+    fn is_same_entry(&self, other: &Self) -> bool;
+
+    /// Check if the entry is written to YAML when the whole document is.
+    fn is_written(&self) -> bool;
+}
+
+impl Entry for PluginMetadata {
+    fn is_same_entry(&self, other: &Self) -> bool {
+        if self.is_regex_plugin() {
+            other.is_regex_plugin() && self.name() == other.name()
+        } else {
+            !other.is_regex_plugin() && unicase::eq(self.name(), other.name())
+        }
+    }
+
+    fn is_written(&self) -> bool {
+        !self.has_name_only()
+    }
+}
+
+impl Entry for Group {
+    fn is_same_entry(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+
+    fn is_written(&self) -> bool {
+        // The default group is always defined, so doesn't need to be written
+        // unless it has been changed.
+        *self != Group::default()
+    }
+}
+
+struct OriginalEntry<T> {
+    entry: T,
+    /// The index of the first line of the comments directly above the entry,
+    /// which are removed with it.
+    comment_start: usize,
+    /// The index of the entry's first line.
+    start: usize,
+    /// One past the index of the entry's last line, not including any
+    /// trailing blank or comment lines.
+    end: usize,
+    /// The column of the entry's "-" indicator.
+    indent: usize,
+}
+
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+struct Patcher<'a, 'b> {
+    lines: Vec<&'a str>,
+    newline: &'static str,
+    mapping: &'a AnnotatedMapping<'b, MarkedYaml<'b>>,
+    edits: Vec<Edit>,
+    appended: String,
+}
+
+impl<'a, 'b> Patcher<'a, 'b> {
+    fn new(text: &'a str, mapping: &'a AnnotatedMapping<'b, MarkedYaml<'b>>) -> Self {
+        Self {
+            lines: text.split_inclusive('\n').collect(),
+            newline: if text.contains("\r\n") { "\r\n" } else { "\n" },
+            mapping,
+            edits: Vec::new(),
+            appended: String::new(),
+        }
+    }
+
+    fn patch_entries<T: Entry>(&mut self, key: &'static str, current: &[&T]) -> Option<()> {
+        let Some(value) = get_value(self.mapping, key) else {
+            let added: Vec<&T> = current.iter().copied().filter(|e| e.is_written()).collect();
+            if !added.is_empty() {
+                let text = self.emit_entries(key, &added, None);
+                self.appended.push_str(&text);
+            }
+            return Some(());
+        };
+
+        let original: Vec<OriginalEntry<T>> = self.find_entries(key, value.data.as_vec()?)?;
+
+        let mut used = vec![false; current.len()];
+        for entry in &original {
+            let index = current
+                .iter()
+                .zip(&used)
+                .position(|(c, used)| !used && entry.entry.is_same_entry(c));
+
+            let Some(index) = index else {
+                self.edits.push(Edit {
+                    start: entry.comment_start,
+                    end: entry.end,
+                    text: String::new(),
+                });
+                continue;
+            };
+
+            *used.get_mut(index)? = true;
+            let current_entry = *current.get(index)?;
+            if entry.entry != *current_entry {
+                self.edits.push(Edit {
+                    start: entry.start,
+                    end: entry.end,
+                    text: self.emit_entries(key, &[current_entry], Some(entry.indent)),
+                });
+            }
+        }
+
+        let added: Vec<&T> = current
+            .iter()
+            .zip(&used)
+            .filter(|(e, used)| !**used && e.is_written())
+            .map(|(e, _)| *e)
+            .collect();
+
+        if !added.is_empty() {
+            // There's nowhere to add entries to if the original list is empty.
+            let last = original.last()?;
+            let text = self.emit_entries(key, &added, Some(last.indent));
+            self.edits.push(Edit {
+                start: last.end,
+                end: last.end,
+                text,
+            });
+        }
+
+        Some(())
+    }
+
+    fn find_entries<T: Entry>(
+        &self,
+        key: &'static str,
+        items: &[MarkedYaml],
+    ) -> Option<Vec<OriginalEntry<T>>> {
+        let mut entries: Vec<OriginalEntry<T>> = Vec::new();
+        for item in items {
+            let start = item.span.start.line().checked_sub(1)?;
+
+            // Only block sequences of entries that start on the same line as
+            // their "-" indicator are supported.
+            let prefix: String = self
+                .lines
+                .get(start)?
+                .chars()
+                .take(item.span.start.col())
+                .collect();
+            let indent = prefix.trim_end().strip_suffix('-')?;
+            if !indent.chars().all(char::is_whitespace) {
+                return None;
+            }
+
+            if entries.last().is_some_and(|e| e.start >= start) {
+                return None;
+            }
+
+            entries.push(OriginalEntry {
+                entry: T::try_from_yaml(item).ok()?,
+                comment_start: start,
+                start,
+                end: start,
+                indent: indent.chars().count(),
+            });
+        }
+
+        let next_starts: Vec<usize> = entries
+            .iter()
+            .skip(1)
+            .map(|e| e.start)
+            .chain(std::iter::once(self.next_key_line(key)))
+            .collect();
+
+        let mut previous_end = 0;
+        for (entry, next_start) in entries.iter_mut().zip(next_starts) {
+            if next_start <= entry.start {
+                return None;
+            }
+            entry.end = self.trim_trailing_lines(entry.start, next_start);
+
+            while entry.comment_start > previous_end
+                && self
+                    .lines
+                    .get(entry.comment_start - 1)
+                    .is_some_and(|l| l.trim_start().starts_with('#'))
+            {
+                entry.comment_start -= 1;
+            }
+            previous_end = entry.end;
+        }
+
+        Some(entries)
+    }
+
+    /// Get the index of the line that the key after the given key starts on,
+    /// or the number of lines if there is no key after the given key.
+    fn next_key_line(&self, key: &str) -> usize {
+        self.mapping
+            .keys()
+            .skip_while(|k| k.data.as_str() != Some(key))
+            .nth(1)
+            .and_then(|k| k.span.start.line().checked_sub(1))
+            .unwrap_or(self.lines.len())
+    }
+
+    fn trim_trailing_lines(&self, start: usize, mut end: usize) -> usize {
+        while end > start + 1
+            && self.lines.get(end - 1).is_some_and(|l| {
+                let l = l.trim();
+                l.is_empty() || l.starts_with('#')
+            })
+        {
+            end -= 1;
+        }
+
+        end
+    }
+
+    /// Emit the given entries as a list. If an indent is given, the list is
+    /// emitted without its key and with its "-" indicators at that column.
+    fn emit_entries<T: Entry>(
+        &self,
+        key: &'static str,
+        entries: &[&T],
+        indent: Option<usize>,
+    ) -> String {
+        let mut emitter = YamlEmitter::new();
+        emitter.map_key(key);
+        emitter.begin_array();
+        for entry in entries {
+            entry.emit_yaml(&mut emitter);
+        }
+        emitter.end_array();
+
+        let yaml = emitter.into_string();
+
+        let mut text = String::new();
+        match indent {
+            None => {
+                for line in yaml.lines() {
+                    text.push_str(line);
+                    text.push_str(self.newline);
+                }
+            }
+            Some(indent) => {
+                let mut lines = yaml.lines().skip(1).peekable();
+                let emitted_indent = lines
+                    .peek()
+                    .map_or(0, |l| l.len() - l.trim_start_matches(' ').len());
+
+                for line in lines {
+                    let line = line.get(emitted_indent..).unwrap_or(line);
+                    text.push_str(&" ".repeat(indent));
+                    text.push_str(line);
+                    text.push_str(self.newline);
+                }
+            }
+        }
+
+        text
+    }
+
+    fn apply(mut self) -> Option<String> {
+        self.edits.sort_by_key(|e| (e.start, e.end));
+
+        let mut output = String::new();
+        let mut line = 0;
+        for edit in self.edits {
+            output.extend(self.lines.get(line..edit.start)?.iter().copied());
+            output.push_str(&edit.text);
+            line = edit.end;
+        }
+        output.extend(self.lines.get(line..)?.iter().copied());
+        output.push_str(&self.appended);
+
+        Some(output)
+    }
+}
@@ -1,7 +1,10 @@
+use std::{path::Path, sync::Arc};
+
 use saphyr::{MarkedYaml, Scalar, YamlData};
 
 use super::{
     error::{ExpectedType, ParseMetadataError},
+    provenance::{MetadataSource, Provenance, ProvenanceField},
     yaml::{
         EmitYaml, TryFromYaml, YamlEmitter, YamlObjectType, get_required_string_value,
         parse_condition,
@@ -22,6 +25,7 @@ pub struct Tag {
     name: Box<str>,
     suggestion: TagSuggestion,
     condition: Option<Box<str>>,
+    provenance: ProvenanceField,
 }
 
 impl Tag {
@@ -32,6 +36,7 @@ impl Tag {
             name: name.into_boxed_str(),
             suggestion,
             condition: None,
+            provenance: ProvenanceField::default(),
         }
     }
 
@@ -56,6 +61,16 @@ impl Tag {
     pub fn condition(&self) -> Option<&str> {
         self.condition.as_deref()
     }
+
+    /// Get where the tag suggestion was loaded from, if it was loaded from a
+    /// metadata file.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.get()
+    }
+
+    pub(super) fn set_provenance_file(&mut self, source: MetadataSource, path: Option<&Arc<Path>>) {
+        self.provenance.set_file(source, path);
+    }
 }
 
 impl TryFromYaml for Tag {
@@ -67,6 +82,7 @@ impl TryFromYaml for Tag {
                     name,
                     suggestion,
                     condition: None,
+                    provenance: ProvenanceField::new(value.span.start),
                 })
             }
             YamlData::Mapping(h) => {
@@ -80,6 +96,7 @@ impl TryFromYaml for Tag {
                     name,
                     suggestion,
                     condition,
+                    provenance: ProvenanceField::new(value.span.start),
                 })
             }
             _ => Err(ParseMetadataError::unexpected_type(
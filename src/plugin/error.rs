@@ -6,26 +6,51 @@ use crate::escape_ascii;
 
 /// Represents an error that occurred while reading a parsed plugin's data.
 #[derive(Debug)]
-pub struct PluginDataError(esplugin::Error);
+pub struct PluginDataError(PluginDataErrorReason);
+
+impl PluginDataError {
+    pub(crate) fn record_ids_not_loaded(plugin_name: String) -> Self {
+        PluginDataError(PluginDataErrorReason::RecordIdsNotLoaded(plugin_name))
+    }
+}
 
 impl std::fmt::Display for PluginDataError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "failed to read plugin data")
+        match &self.0 {
+            PluginDataErrorReason::Esplugin(_) => write!(f, "failed to read plugin data"),
+            PluginDataErrorReason::RecordIdsNotLoaded(n) => {
+                write!(
+                    f,
+                    "the record IDs of the plugin \"{n}\" have not been loaded"
+                )
+            }
+        }
     }
 }
 
 impl std::error::Error for PluginDataError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(&self.0)
+        match &self.0 {
+            PluginDataErrorReason::Esplugin(e) => Some(e),
+            PluginDataErrorReason::RecordIdsNotLoaded(_) => None,
+        }
     }
 }
 
 impl From<esplugin::Error> for PluginDataError {
     fn from(value: esplugin::Error) -> Self {
-        PluginDataError(value)
+        PluginDataError(PluginDataErrorReason::Esplugin(value))
     }
 }
 
+#[derive(Debug)]
+enum PluginDataErrorReason {
+    Esplugin(esplugin::Error),
+    /// The plugin's header was loaded without its records, or the plugin has
+    /// no FormIDs to load.
+    RecordIdsNotLoaded(String),
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub(crate) enum LoadPluginError {
@@ -0,0 +1,130 @@
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom};
+
+const GROUP_TYPE: &[u8] = b"GRUP";
+const DATA_SIZE_OFFSET: usize = 4;
+const FORM_ID_OFFSET: usize = 12;
+const MAX_HEADER_LENGTH: usize = 24;
+
+/// Read the raw FormIDs of all the records in a plugin, other than its header
+/// record, without reading any of the records' data.
+///
+/// `header_length` is the length of the game's record and group headers,
+/// which must be 20 or 24 bytes, as games with shorter headers don't use
+/// FormIDs. An error is returned if the data ends part of the way through a
+/// record.
+pub(super) fn read_form_ids<R: Read + Seek>(
+    mut reader: R,
+    header_length: usize,
+) -> std::io::Result<Vec<u32>> {
+    let mut buffer = [0; MAX_HEADER_LENGTH];
+    let Some(header) = buffer.get_mut(..header_length) else {
+        return Err(ErrorKind::InvalidInput.into());
+    };
+
+    let length = reader.seek(SeekFrom::End(0))?;
+    reader.rewind()?;
+    let mut reader = BufReader::new(reader);
+
+    let mut form_ids = Vec::new();
+    let mut is_header_record = true;
+    while !reader.fill_buf()?.is_empty() {
+        reader.read_exact(header)?;
+
+        // A group's records and subgroups follow its header, so there's
+        // nothing to skip over.
+        if header.starts_with(GROUP_TYPE) {
+            continue;
+        }
+
+        let form_id = read_u32(header, FORM_ID_OFFSET);
+        if !is_header_record && form_id != 0 {
+            form_ids.push(form_id);
+        }
+        is_header_record = false;
+
+        reader.seek_relative(i64::from(read_u32(header, DATA_SIZE_OFFSET)))?;
+        if reader.stream_position()? > length {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+    }
+
+    Ok(form_ids)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .map_or(0, u32::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    fn record(record_type: [u8; 4], form_id: u32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = record_type.to_vec();
+        bytes.extend(u32::try_from(data.len()).unwrap().to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(form_id.to_le_bytes());
+        bytes.extend([0; 8]);
+        bytes.extend(data);
+        bytes
+    }
+
+    fn group(contents: &[u8]) -> Vec<u8> {
+        let mut bytes = GROUP_TYPE.to_vec();
+        bytes.extend(u32::try_from(contents.len() + 24).unwrap().to_le_bytes());
+        bytes.extend([0; 16]);
+        bytes.extend(contents);
+        bytes
+    }
+
+    #[test]
+    fn read_form_ids_should_read_the_form_ids_of_records_in_nested_groups() {
+        let mut inner = record(*b"REFR", 0x0000_0802, b"data");
+        inner.extend(record(*b"REFR", 0x0100_0803, &[]));
+
+        let mut outer = record(*b"CELL", 0x0000_0801, b"more data");
+        outer.extend(group(&inner));
+
+        let mut plugin = record(*b"TES4", 0, b"header");
+        plugin.extend(group(&record(*b"GMST", 0x0000_0800, b"GMST data")));
+        plugin.extend(group(&outer));
+
+        let form_ids = read_form_ids(Cursor::new(plugin), 24).unwrap();
+
+        assert_eq!(
+            vec![0x0000_0800, 0x0000_0801, 0x0000_0802, 0x0100_0803],
+            form_ids
+        );
+    }
+
+    #[test]
+    fn read_form_ids_should_error_if_the_data_ends_within_a_record_header() {
+        let mut plugin = record(*b"TES4", 0, b"header");
+        plugin.extend(&record(*b"GMST", 0x0000_0800, b"GMST data")[..10]);
+
+        let error = read_form_ids(Cursor::new(plugin), 24).unwrap_err();
+
+        assert_eq!(ErrorKind::UnexpectedEof, error.kind());
+    }
+
+    #[test]
+    fn read_form_ids_should_error_if_the_data_ends_within_a_record() {
+        let mut plugin = record(*b"TES4", 0, b"header");
+        let gmst = record(*b"GMST", 0x0000_0800, b"GMST data");
+        plugin.extend(&gmst[..gmst.len() - 1]);
+
+        let error = read_form_ids(Cursor::new(plugin), 24).unwrap_err();
+
+        assert_eq!(ErrorKind::UnexpectedEof, error.kind());
+    }
+
+    #[test]
+    fn read_form_ids_should_error_if_the_header_length_is_too_long() {
+        assert!(read_form_ids(Cursor::new(Vec::new()), 32).is_err());
+    }
+}
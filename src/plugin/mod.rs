@@ -1,4 +1,5 @@
 pub mod error;
+mod form_ids;
 
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -45,6 +46,9 @@ impl std::fmt::Display for LoadScope {
 pub struct Plugin {
     name: String,
     data: Option<esplugin::Plugin>,
+    /// The raw FormIDs of the plugin's records other than its header record,
+    /// if they were loaded.
+    form_ids: Option<Box<[u32]>>,
     game_type: GameType,
     crc: Option<u32>,
     version: Option<String>,
@@ -73,6 +77,7 @@ impl Plugin {
         let mut tags = Box::default();
         let mut archive_paths = Box::default();
         let mut archive_assets = BTreeMap::new();
+        let mut form_ids = None;
         let plugin =
             if game_type != GameType::OpenMW || !has_ascii_extension(plugin_path, "omwscripts") {
                 let mut plugin = esplugin::Plugin::new(game_type.into(), plugin_path);
@@ -88,6 +93,7 @@ impl Plugin {
 
                 if load_scope == LoadScope::WholePlugin {
                     archive_assets = assets_in_archives(&archive_paths);
+                    form_ids = read_form_ids(game_type, plugin_path)?;
                 }
 
                 Some(plugin)
@@ -98,6 +104,7 @@ impl Plugin {
         Ok(Self {
             name,
             data: plugin,
+            form_ids,
             game_type,
             crc,
             version,
@@ -255,6 +262,20 @@ impl Plugin {
         }
     }
 
+    /// Get the raw FormIDs of the plugin's records, other than its header
+    /// record, as they were when the plugin was loaded. They're only loaded
+    /// when the whole plugin is loaded, and Morrowind and OpenMW plugins don't
+    /// have any.
+    pub(crate) fn form_ids(&self) -> Result<&[u32], PluginDataError> {
+        self.form_ids
+            .as_deref()
+            .ok_or_else(|| PluginDataError::record_ids_not_loaded(self.name.clone()))
+    }
+
+    pub(crate) fn game_type(&self) -> GameType {
+        self.game_type
+    }
+
     pub(crate) fn override_record_count(&self) -> Result<usize, PluginDataError> {
         self.data
             .as_ref()
@@ -310,7 +331,7 @@ pub(crate) fn validate_plugin_path_and_header(
     }
 }
 
-fn has_plugin_file_extension(game_type: GameType, plugin_path: &Path) -> bool {
+pub(crate) fn has_plugin_file_extension(game_type: GameType, plugin_path: &Path) -> bool {
     let extension = if game_type != GameType::OpenMW && has_ascii_extension(plugin_path, "ghost") {
         plugin_path
             .file_stem()
@@ -370,6 +391,19 @@ fn name_string(game_type: GameType, path: &Path) -> Result<String, LoadPluginErr
     }
 }
 
+/// Read the raw FormIDs of a plugin's records, or `None` if the game's plugins
+/// don't use FormIDs.
+fn read_form_ids(game_type: GameType, path: &Path) -> std::io::Result<Option<Box<[u32]>>> {
+    let header_length = match game_type {
+        GameType::Morrowind | GameType::OpenMW => return Ok(None),
+        GameType::Oblivion => 20,
+        _ => 24,
+    };
+
+    let file = File::open(path)?;
+    form_ids::read_form_ids(file, header_length).map(|f| Some(f.into_boxed_slice()))
+}
+
 fn calculate_crc(path: &Path) -> std::io::Result<u32> {
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
@@ -0,0 +1,302 @@
+use crate::{GameType, eligibility::PluginScale};
+
+/// The mod index that medium plugins share, followed by their own index.
+const MEDIUM_PLUGIN_MOD_INDEX: &str = "FD";
+/// The mod index that light plugins share, followed by their own index.
+const LIGHT_PLUGIN_MOD_INDEX: &str = "FE";
+
+/// The slot that an active plugin occupies, which decides the mod index that
+/// the game gives its records.
+///
+/// The slot is displayed as its hexadecimal load order index, e.g. `0A` for a
+/// full plugin, `FD 0A` for a medium plugin or `FE 00A` for a light plugin.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PluginSlot {
+    /// A full plugin's slot, with its index among active full plugins.
+    Full(usize),
+    /// A medium plugin's slot, with its index among active medium plugins.
+    Medium(usize),
+    /// A light plugin's slot, with its index among active light plugins.
+    Light(usize),
+}
+
+impl std::fmt::Display for PluginSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginSlot::Full(index) => write!(f, "{index:02X}"),
+            PluginSlot::Medium(index) => write!(f, "{MEDIUM_PLUGIN_MOD_INDEX} {index:02X}"),
+            PluginSlot::Light(index) => write!(f, "{LIGHT_PLUGIN_MOD_INDEX} {index:03X}"),
+        }
+    }
+}
+
+/// An active plugin and the slot that it occupies.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ActivePluginSlot {
+    plugin_name: String,
+    slot: PluginSlot,
+    over_limit: bool,
+}
+
+impl ActivePluginSlot {
+    /// Get the name of the plugin.
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin_name
+    }
+
+    /// Get the slot that the plugin occupies.
+    pub fn slot(&self) -> PluginSlot {
+        self.slot
+    }
+
+    /// Check if the plugin's slot is beyond the game's limit for its type of
+    /// plugin, in which case the game won't load it correctly.
+    pub fn is_over_limit(&self) -> bool {
+        self.over_limit
+    }
+}
+
+/// How many slots of one type are used, and how many the game has.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SlotUsage {
+    used: usize,
+    limit: Option<usize>,
+}
+
+impl SlotUsage {
+    /// Get the number of active plugins that use this type of slot.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Get the number of slots of this type that the game has, or `None` if
+    /// it has no limit. Games that don't support a type of plugin have no
+    /// slots for it.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Check if more slots are used than the game has.
+    pub fn is_exceeded(&self) -> bool {
+        self.limit.is_some_and(|l| self.used > l)
+    }
+}
+
+/// The slots used by the active plugins in the current load order, as found
+/// by [Game::load_order_slots][crate::Game::load_order_slots].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LoadOrderSlots {
+    plugins: Vec<ActivePluginSlot>,
+    full: SlotUsage,
+    medium: SlotUsage,
+    light: SlotUsage,
+}
+
+impl LoadOrderSlots {
+    /// Get the active plugins and their slots, in load order.
+    pub fn plugins(&self) -> &[ActivePluginSlot] {
+        &self.plugins
+    }
+
+    /// Get the usage of full plugin slots.
+    pub fn full(&self) -> SlotUsage {
+        self.full
+    }
+
+    /// Get the usage of medium plugin slots.
+    pub fn medium(&self) -> SlotUsage {
+        self.medium
+    }
+
+    /// Get the usage of light plugin slots.
+    pub fn light(&self) -> SlotUsage {
+        self.light
+    }
+
+    /// Check if any type of slot has more active plugins than the game
+    /// supports.
+    pub fn is_any_limit_exceeded(&self) -> bool {
+        self.full.is_exceeded() || self.medium.is_exceeded() || self.light.is_exceeded()
+    }
+}
+
+/// Assign slots to the given active plugins, which must be in load order.
+pub(crate) fn load_order_slots<'a>(
+    game_type: GameType,
+    active_plugins: impl IntoIterator<Item = (&'a str, PluginScale)>,
+) -> LoadOrderSlots {
+    let mut full = SlotUsage {
+        used: 0,
+        limit: full_slot_limit(game_type),
+    };
+    let mut medium = SlotUsage {
+        used: 0,
+        limit: Some(medium_slot_limit(game_type)),
+    };
+    let mut light = SlotUsage {
+        used: 0,
+        limit: Some(light_slot_limit(game_type)),
+    };
+
+    let plugins = active_plugins
+        .into_iter()
+        .map(|(name, scale)| {
+            let (usage, to_slot): (_, fn(usize) -> PluginSlot) = match scale {
+                PluginScale::Full => (&mut full, PluginSlot::Full),
+                PluginScale::Medium => (&mut medium, PluginSlot::Medium),
+                PluginScale::Small => (&mut light, PluginSlot::Light),
+            };
+            let slot = to_slot(usage.used);
+            let over_limit = usage.limit.is_some_and(|l| usage.used >= l);
+            usage.used += 1;
+
+            ActivePluginSlot {
+                plugin_name: name.to_owned(),
+                slot,
+                over_limit,
+            }
+        })
+        .collect();
+
+    LoadOrderSlots {
+        plugins,
+        full,
+        medium,
+        light,
+    }
+}
+
+/// Get the number of full plugin slots, which is the number of mod indexes
+/// that aren't reserved. `FF` is reserved for records created while the game
+/// runs, and `FE` and `FD` are reserved for light and medium plugins in games
+/// that support them.
+fn full_slot_limit(game_type: GameType) -> Option<usize> {
+    match game_type {
+        GameType::OpenMW => None,
+        GameType::Morrowind
+        | GameType::Oblivion
+        | GameType::OblivionRemastered
+        | GameType::Skyrim
+        | GameType::Fallout3
+        | GameType::FalloutNV => Some(0xFF),
+        GameType::SkyrimSE | GameType::SkyrimVR | GameType::Fallout4 | GameType::Fallout4VR => {
+            Some(0xFE)
+        }
+        GameType::Starfield => Some(0xFD),
+    }
+}
+
+fn medium_slot_limit(game_type: GameType) -> usize {
+    match game_type {
+        GameType::Starfield => 0x100,
+        _ => 0,
+    }
+}
+
+fn light_slot_limit(game_type: GameType) -> usize {
+    match game_type {
+        GameType::SkyrimSE
+        | GameType::SkyrimVR
+        | GameType::Fallout4
+        | GameType::Fallout4VR
+        | GameType::Starfield => 0x1000,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod plugin_slot {
+        use super::*;
+
+        #[test]
+        fn display_should_write_the_hexadecimal_load_order_index() {
+            assert_eq!("0A", PluginSlot::Full(10).to_string());
+            assert_eq!("FD 0A", PluginSlot::Medium(10).to_string());
+            assert_eq!("FE 012", PluginSlot::Light(18).to_string());
+        }
+    }
+
+    mod load_order_slots {
+        use super::*;
+
+        #[test]
+        fn should_index_each_type_of_plugin_separately_in_load_order() {
+            let slots = load_order_slots(
+                GameType::Starfield,
+                [
+                    ("Starfield.esm", PluginScale::Full),
+                    ("A.esm", PluginScale::Small),
+                    ("B.esm", PluginScale::Medium),
+                    ("C.esp", PluginScale::Full),
+                    ("D.esp", PluginScale::Small),
+                ],
+            );
+
+            let indexes: Vec<_> = slots
+                .plugins()
+                .iter()
+                .map(|p| (p.plugin_name(), p.slot().to_string()))
+                .collect();
+
+            assert_eq!(
+                vec![
+                    ("Starfield.esm", "00".to_owned()),
+                    ("A.esm", "FE 000".to_owned()),
+                    ("B.esm", "FD 00".to_owned()),
+                    ("C.esp", "01".to_owned()),
+                    ("D.esp", "FE 001".to_owned()),
+                ],
+                indexes
+            );
+            assert_eq!(2, slots.full().used());
+            assert_eq!(1, slots.medium().used());
+            assert_eq!(2, slots.light().used());
+            assert!(!slots.is_any_limit_exceeded());
+        }
+
+        #[test]
+        fn should_reserve_mod_indexes_for_the_types_of_plugin_that_the_game_supports() {
+            assert_eq!(
+                Some(0xFF),
+                load_order_slots(GameType::Skyrim, []).full().limit()
+            );
+            assert_eq!(
+                Some(0xFE),
+                load_order_slots(GameType::SkyrimSE, []).full().limit()
+            );
+            assert_eq!(
+                Some(0xFD),
+                load_order_slots(GameType::Starfield, []).full().limit()
+            );
+            assert_eq!(None, load_order_slots(GameType::OpenMW, []).full().limit());
+
+            let slots = load_order_slots(GameType::Fallout4, []);
+            assert_eq!(Some(0x1000), slots.light().limit());
+            assert_eq!(Some(0), slots.medium().limit());
+        }
+
+        #[test]
+        fn should_flag_plugins_beyond_the_limit_for_their_type() {
+            let names: Vec<_> = (0..0x100_u32).map(|i| format!("{i}.esp")).collect();
+            let slots = load_order_slots(
+                GameType::SkyrimSE,
+                names.iter().map(|n| (n.as_str(), PluginScale::Full)),
+            );
+
+            assert_eq!(0x100, slots.full().used());
+            assert!(slots.full().is_exceeded());
+            assert!(slots.is_any_limit_exceeded());
+
+            let over_limit: Vec<_> = slots
+                .plugins()
+                .iter()
+                .filter(|p| p.is_over_limit())
+                .map(|p| p.slot().to_string())
+                .collect();
+            assert_eq!(vec!["FE", "FF"], over_limit);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    error::{MetadataRetrievalError, SortPluginsError},
+    metadata::{Group, PluginMetadata},
+};
+
+use super::{
+    groups::build_groups_graph,
+    options::SortOptions,
+    plugins::{self, PluginSortingData, SortingPlugin},
+    report::SortReport,
+};
+
+/// The metadata to use when sorting plugins using [sort_plugins].
+///
+/// The metadata is used as given, so any conditions and constraints that it
+/// has are ignored. Metadata that has had its conditions evaluated can be
+/// obtained using [Database::plugin_metadata][crate::Database::plugin_metadata]
+/// and [Database::plugin_user_metadata][crate::Database::plugin_user_metadata].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SortingMetadata {
+    masterlist_plugins: Vec<PluginMetadata>,
+    user_plugins: Vec<PluginMetadata>,
+    masterlist_groups: Vec<Group>,
+    user_groups: Vec<Group>,
+}
+
+impl SortingMetadata {
+    /// Create an object with no metadata.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the plugin metadata that comes from the masterlist.
+    ///
+    /// If more than one entry matches a plugin, they are merged in the order
+    /// that they are given.
+    #[must_use]
+    pub fn with_masterlist_plugins(mut self, plugins: Vec<PluginMetadata>) -> Self {
+        self.masterlist_plugins = plugins;
+        self
+    }
+
+    /// Set the plugin metadata that comes from the userlist.
+    ///
+    /// If more than one entry matches a plugin, they are merged in the order
+    /// that they are given.
+    #[must_use]
+    pub fn with_user_plugins(mut self, plugins: Vec<PluginMetadata>) -> Self {
+        self.user_plugins = plugins;
+        self
+    }
+
+    /// Set the group definitions that come from the masterlist.
+    ///
+    /// The default group is always defined, even if it is not given.
+    #[must_use]
+    pub fn with_masterlist_groups(mut self, groups: Vec<Group>) -> Self {
+        self.masterlist_groups = groups;
+        self
+    }
+
+    /// Set the group definitions that come from the userlist.
+    #[must_use]
+    pub fn with_user_groups(mut self, groups: Vec<Group>) -> Self {
+        self.user_groups = groups;
+        self
+    }
+
+    /// Get the plugin metadata that comes from the masterlist.
+    pub fn masterlist_plugins(&self) -> &[PluginMetadata] {
+        &self.masterlist_plugins
+    }
+
+    /// Get the plugin metadata that comes from the userlist.
+    pub fn user_plugins(&self) -> &[PluginMetadata] {
+        &self.user_plugins
+    }
+
+    /// Get the group definitions that come from the masterlist.
+    pub fn masterlist_groups(&self) -> &[Group] {
+        &self.masterlist_groups
+    }
+
+    /// Get the group definitions that come from the userlist.
+    pub fn user_groups(&self) -> &[Group] {
+        &self.user_groups
+    }
+}
+
+/// Calculates a sorted load order for the given plugins using the given
+/// metadata, without needing a [Game][crate::Game].
+///
+/// This can be used to sort plugins that are represented by your own types,
+/// e.g. plugins that are stored somewhere other than a game's data folder. The
+/// order in which plugins are given is used as their current load order.
+///
+/// `early_loading_plugins` are the plugins that the game hardcodes to load
+/// before all others, in the order that they load.
+pub fn sort_plugins<T: SortingPlugin>(
+    plugins: &[T],
+    early_loading_plugins: &[String],
+    metadata: &SortingMetadata,
+    options: &SortOptions,
+) -> Result<SortReport, SortPluginsError> {
+    let plugins_sorting_data = plugins
+        .iter()
+        .enumerate()
+        .map(|(i, p)| to_plugin_sorting_data(metadata, p, i))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let masterlist_groups = with_default_group(&metadata.masterlist_groups);
+    let groups_graph = build_groups_graph(&masterlist_groups, &metadata.user_groups)?;
+
+    plugins::sort_plugins(
+        plugins_sorting_data,
+        &groups_graph,
+        &[],
+        early_loading_plugins,
+        options,
+        None,
+    )
+    .map_err(Into::into)
+}
+
+fn to_plugin_sorting_data<'a, T: SortingPlugin>(
+    metadata: &SortingMetadata,
+    plugin: &'a T,
+    load_order_index: usize,
+) -> Result<PluginSortingData<'a, T>, SortPluginsError> {
+    let masterlist_metadata = find_plugin(&metadata.masterlist_plugins, plugin.name())?;
+    let user_metadata = find_plugin(&metadata.user_plugins, plugin.name())?;
+
+    PluginSortingData::new(
+        plugin,
+        masterlist_metadata.as_ref(),
+        user_metadata.as_ref(),
+        load_order_index,
+    )
+    .map_err(Into::into)
+}
+
+fn find_plugin(
+    plugins: &[PluginMetadata],
+    plugin_name: &str,
+) -> Result<Option<PluginMetadata>, MetadataRetrievalError> {
+    let mut matching_plugins = plugins.iter().filter(|p| p.name_matches(plugin_name));
+
+    let Some(first) = matching_plugins.next() else {
+        return Ok(None);
+    };
+
+    // Use the plugin's name instead of the metadata's name in case it's a
+    // regex.
+    let mut metadata = PluginMetadata::new(plugin_name)?;
+    metadata.merge_metadata(first);
+    for plugin in matching_plugins {
+        metadata.merge_metadata(plugin);
+    }
+
+    Ok(Some(metadata))
+}
+
+fn with_default_group(groups: &[Group]) -> Vec<Group> {
+    if groups.iter().any(|g| g.name() == Group::DEFAULT_NAME) {
+        groups.to_vec()
+    } else {
+        std::iter::once(Group::default())
+            .chain(groups.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{metadata::File, sorting::test::TestPlugin};
+
+    fn plugin_with_load_after(name: &str, load_after: &str) -> PluginMetadata {
+        let mut metadata = PluginMetadata::new(name).unwrap();
+        metadata.set_load_after_files(vec![File::new(load_after.into())]);
+        metadata
+    }
+
+    #[test]
+    fn sort_plugins_should_use_the_given_plugin_metadata() {
+        let plugins = [TestPlugin::new("A.esp"), TestPlugin::new("B.esp")];
+        let metadata = SortingMetadata::new()
+            .with_masterlist_plugins(vec![plugin_with_load_after("A.esp", "B.esp")]);
+
+        let report = sort_plugins(&plugins, &[], &metadata, &SortOptions::default()).unwrap();
+
+        assert_eq!(["B.esp", "A.esp"], report.load_order());
+    }
+
+    #[test]
+    fn sort_plugins_should_match_regex_plugin_metadata() {
+        let plugins = [TestPlugin::new("A.esp"), TestPlugin::new("B.esp")];
+        let metadata = SortingMetadata::new()
+            .with_user_plugins(vec![plugin_with_load_after("A\\.esp", "B.esp")]);
+
+        let report = sort_plugins(&plugins, &[], &metadata, &SortOptions::default()).unwrap();
+
+        assert_eq!(["B.esp", "A.esp"], report.load_order());
+    }
+
+    #[test]
+    fn sort_plugins_should_use_the_given_groups() {
+        let plugins = [TestPlugin::new("A.esp"), TestPlugin::new("B.esp")];
+
+        let mut a_metadata = PluginMetadata::new("A.esp").unwrap();
+        a_metadata.set_group("late".into());
+
+        let metadata = SortingMetadata::new()
+            .with_masterlist_plugins(vec![a_metadata])
+            .with_user_groups(vec![
+                Group::new("late".into()).with_after_groups(vec![Group::DEFAULT_NAME.into()]),
+            ]);
+
+        let report = sort_plugins(&plugins, &[], &metadata, &SortOptions::default()).unwrap();
+
+        assert_eq!(["B.esp", "A.esp"], report.load_order());
+    }
+
+    #[test]
+    fn sort_plugins_should_error_if_a_plugin_is_in_an_undefined_group() {
+        let plugins = [TestPlugin::new("A.esp")];
+
+        let mut a_metadata = PluginMetadata::new("A.esp").unwrap();
+        a_metadata.set_group("missing".into());
+
+        let metadata = SortingMetadata::new().with_user_plugins(vec![a_metadata]);
+
+        match sort_plugins(&plugins, &[], &metadata, &SortOptions::default()) {
+            Err(SortPluginsError::UndefinedGroup(g)) => assert_eq!("missing", g),
+            r => panic!("Expected an undefined group error, got {r:?}"),
+        }
+    }
+}
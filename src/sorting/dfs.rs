@@ -97,6 +97,39 @@ pub fn find_cycle<N>(
     None
 }
 
+/// Finds every strongly connected component in the graph that contains a
+/// cycle, returning the names of the nodes in each component (in lexicographical
+/// order) along with one cycle that passes through that component.
+pub fn find_all_cycles<N>(
+    graph: &Graph<N, EdgeType>,
+    mut node_mapper: impl FnMut(&N) -> String,
+) -> Vec<(Vec<String>, Vec<Vertex>)> {
+    petgraph::algo::tarjan_scc(graph)
+        .into_iter()
+        .filter(|component| match component.as_slice() {
+            [node_index] => graph.contains_edge(*node_index, *node_index),
+            _ => true,
+        })
+        .filter_map(|component| {
+            let component_nodes: HashSet<_> = component.iter().copied().collect();
+
+            // Search for a cycle in a graph of only the component's nodes, so
+            // that the cycle found is in the component.
+            let subgraph = graph.filter_map(
+                |i, n| component_nodes.contains(&i).then_some(n),
+                |_, e| Some(e.clone()),
+            );
+
+            let cycle = find_cycle(&subgraph, |n| node_mapper(n))?;
+
+            let mut names: Vec<_> = component.iter().map(|i| node_mapper(&graph[*i])).collect();
+            names.sort();
+
+            Some((names, cycle))
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[non_exhaustive]
 pub enum Colour {
@@ -191,7 +224,7 @@ impl<'a, N, F: FnMut(&N) -> String> DfsVisitor<'a> for CycleDetector<'a, N, F> {
 
         let source = edge_ref.source();
         let name = (self.get_node_name)(&self.graph[source]);
-        let edge_type = *edge_ref.weight();
+        let edge_type = edge_ref.weight().clone();
 
         let vertex = Vertex::new(name).with_out_edge_type(edge_type);
 
@@ -0,0 +1,318 @@
+use rustc_hash::FxHashMap as HashMap;
+use unicase::UniCase;
+
+use crate::Vertex;
+
+use super::report::SortReport;
+
+/// The difference between a current load order and a sorted load order, which
+/// can be used to preview the effect of sorting before setting the sorted load
+/// order.
+///
+/// Only plugins that are in the sorted load order are compared, so indices are
+/// positions in the current and sorted load orders after any plugins that were
+/// not sorted have been removed. Any sorted plugins that are not in the current
+/// load order are treated as if they were at the end of the current load order,
+/// in their sorted order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct LoadOrderDiff {
+    moved_plugins: Vec<PluginMove>,
+    moves: Vec<PluginMove>,
+    unchanged_count: usize,
+}
+
+impl LoadOrderDiff {
+    /// Compare the given current load order against the load order in the
+    /// given sort report.
+    ///
+    /// Plugin names are compared case-insensitively.
+    pub fn new(current_load_order: &[&str], sort_report: &SortReport) -> Self {
+        let sorted_load_order = sort_report.load_order();
+
+        let new_indices: HashMap<_, _> = sorted_load_order
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (UniCase::new(name.as_str()), i))
+            .collect();
+
+        // The new indices of the sorted plugins, in their current order.
+        let mut current_order = Vec::with_capacity(sorted_load_order.len());
+        let mut is_in_current_order = vec![false; sorted_load_order.len()];
+        for name in current_load_order {
+            if let Some(new_index) = new_indices.get(&UniCase::new(*name))
+                && let Some(is_present @ false) = is_in_current_order.get_mut(*new_index)
+            {
+                *is_present = true;
+                current_order.push(*new_index);
+            }
+        }
+        current_order.extend(
+            is_in_current_order
+                .iter()
+                .enumerate()
+                .filter(|(_, is_present)| !**is_present)
+                .map(|(i, _)| i),
+        );
+
+        // The old indices of the sorted plugins, in their sorted order.
+        let mut old_indices = vec![0; sorted_load_order.len()];
+        for (old_index, new_index) in current_order.iter().enumerate() {
+            if let Some(i) = old_indices.get_mut(*new_index) {
+                *i = old_index;
+            }
+        }
+
+        // The plugins in the longest run of plugins that are already in
+        // sorted order don't need to move, and moving all the others is the
+        // smallest set of moves that gives the sorted load order.
+        let is_unmoved = longest_increasing_subsequence(&old_indices);
+
+        let mut diff = LoadOrderDiff::default();
+        for (new_index, (name, old_index)) in sorted_load_order.iter().zip(old_indices).enumerate()
+        {
+            let plugin_move = || PluginMove {
+                name: name.clone(),
+                old_index,
+                new_index,
+                load_after: new_index
+                    .checked_sub(1)
+                    .and_then(|i| sorted_load_order.get(i))
+                    .cloned(),
+                path: explaining_path(sort_report, new_index),
+            };
+
+            if old_index == new_index {
+                diff.unchanged_count += 1;
+            } else {
+                diff.moved_plugins.push(plugin_move());
+            }
+
+            if !is_unmoved.get(new_index).is_some_and(|b| *b) {
+                diff.moves.push(plugin_move());
+            }
+        }
+
+        diff
+    }
+
+    /// Get the plugins that have a different index in the sorted load order
+    /// than in the current load order, in their sorted order.
+    pub fn moved_plugins(&self) -> &[PluginMove] {
+        &self.moved_plugins
+    }
+
+    /// Get the smallest set of plugin moves that will turn the current load
+    /// order into the sorted load order.
+    ///
+    /// The sorted load order is obtained by moving each plugin to immediately
+    /// after the plugin given by [PluginMove::load_after] (or to the start of
+    /// the load order if there is no such plugin), in the order that the moves
+    /// are given.
+    pub fn moves(&self) -> &[PluginMove] {
+        &self.moves
+    }
+
+    /// Get the number of plugins that have the same index in the sorted load
+    /// order as in the current load order.
+    pub fn unchanged_count(&self) -> usize {
+        self.unchanged_count
+    }
+
+    /// Check if the sorted load order is the same as the current load order.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
+
+/// Represents a plugin's position in the current and sorted load orders.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PluginMove {
+    name: String,
+    old_index: usize,
+    new_index: usize,
+    load_after: Option<String>,
+    path: Vec<Vertex>,
+}
+
+impl PluginMove {
+    /// Get the name of the plugin.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the plugin's index in the current load order.
+    pub fn old_index(&self) -> usize {
+        self.old_index
+    }
+
+    /// Get the plugin's index in the sorted load order.
+    pub fn new_index(&self) -> usize {
+        self.new_index
+    }
+
+    /// Get the name of the plugin that loads immediately before this plugin in
+    /// the sorted load order, or [None] if this plugin loads first.
+    pub fn load_after(&self) -> Option<&str> {
+        self.load_after.as_deref()
+    }
+
+    /// Get the path that explains the plugin's position in the sorted load
+    /// order.
+    ///
+    /// This is the path from the plugin that loads immediately before it in
+    /// the sorted load order, or if the plugin loads first, the path to the
+    /// plugin that loads immediately after it. The path is empty if the plugin
+    /// is the only plugin in the sorted load order.
+    pub fn path(&self) -> &[Vertex] {
+        &self.path
+    }
+}
+
+fn explaining_path(sort_report: &SortReport, new_index: usize) -> Vec<Vertex> {
+    let path_index = new_index.saturating_sub(1);
+
+    sort_report
+        .adjacent_plugin_paths()
+        .get(path_index)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Returns a vec with the same length as the given values, where an element
+/// is true if the value at the same index is part of a longest strictly
+/// increasing subsequence of the values.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<bool> {
+    // The index of the last value of the smallest-ending increasing
+    // subsequence of each length.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut predecessors = Vec::with_capacity(values.len());
+
+    for (i, value) in values.iter().enumerate() {
+        let position = tails.partition_point(|t| values.get(*t).is_some_and(|tail| tail < value));
+
+        predecessors.push(position.checked_sub(1).and_then(|p| tails.get(p)).copied());
+
+        if let Some(tail) = tails.get_mut(position) {
+            *tail = i;
+        } else {
+            tails.push(i);
+        }
+    }
+
+    let mut is_in_subsequence = vec![false; values.len()];
+    let mut current = tails.last().copied();
+    while let Some(i) = current {
+        if let Some(element) = is_in_subsequence.get_mut(i) {
+            *element = true;
+        }
+        current = predecessors.get(i).copied().flatten();
+    }
+
+    is_in_subsequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::EdgeType;
+
+    fn report(load_order: &[&str]) -> SortReport {
+        let load_order: Vec<String> = load_order.iter().map(|n| (*n).to_owned()).collect();
+        let paths = load_order
+            .windows(2)
+            .map(|w| {
+                vec![
+                    Vertex::new(w[0].clone()).with_out_edge_type(EdgeType::UserLoadAfter),
+                    Vertex::new(w[1].clone()),
+                ]
+            })
+            .collect();
+
+        SortReport::new(load_order, paths, Vec::new())
+    }
+
+    /// Apply the diff's moves to the given load order.
+    fn apply(current_load_order: &[&str], diff: &LoadOrderDiff) -> Vec<String> {
+        let mut load_order: Vec<String> =
+            current_load_order.iter().map(|n| (*n).to_owned()).collect();
+
+        for plugin_move in diff.moves() {
+            load_order.retain(|n| n != plugin_move.name());
+            let index = plugin_move
+                .load_after()
+                .map_or(0, |a| load_order.iter().position(|n| n == a).unwrap() + 1);
+            load_order.insert(index, plugin_move.name().to_owned());
+        }
+
+        load_order
+    }
+
+    #[test]
+    fn new_should_find_no_moves_if_the_load_order_has_not_changed() {
+        let diff = LoadOrderDiff::new(&["A.esp", "B.esp"], &report(&["A.esp", "B.esp"]));
+
+        assert!(diff.is_empty());
+        assert!(diff.moved_plugins().is_empty());
+        assert_eq!(2, diff.unchanged_count());
+    }
+
+    #[test]
+    fn new_should_only_move_plugins_that_are_out_of_order() {
+        let current = ["C.esp", "A.esp", "B.esp", "D.esp"];
+        let diff = LoadOrderDiff::new(&current, &report(&["A.esp", "B.esp", "C.esp", "D.esp"]));
+
+        assert_eq!(1, diff.moves().len());
+        assert_eq!("C.esp", diff.moves()[0].name());
+        assert_eq!(0, diff.moves()[0].old_index());
+        assert_eq!(2, diff.moves()[0].new_index());
+        assert_eq!(Some("B.esp"), diff.moves()[0].load_after());
+        assert_eq!("B.esp", diff.moves()[0].path()[0].name());
+        assert_eq!("C.esp", diff.moves()[0].path()[1].name());
+
+        let moved: Vec<_> = diff.moved_plugins().iter().map(PluginMove::name).collect();
+        assert_eq!(vec!["A.esp", "B.esp", "C.esp"], moved);
+        assert_eq!(1, diff.unchanged_count());
+    }
+
+    #[test]
+    fn new_should_give_moves_that_produce_the_sorted_load_order() {
+        let current = ["X.esp", "A.esp", "Y.esp", "B.esp"];
+        let sorted = ["A.esp", "B.esp", "X.esp", "Y.esp"];
+        let diff = LoadOrderDiff::new(&current, &report(&sorted));
+
+        assert_eq!(2, diff.moves().len());
+        assert_eq!(sorted.to_vec(), apply(&current, &diff));
+    }
+
+    #[test]
+    fn new_should_compare_plugin_names_case_insensitively_and_ignore_unsorted_plugins() {
+        let current = ["b.esp", "Z.esp", "a.esp"];
+        let diff = LoadOrderDiff::new(&current, &report(&["A.esp", "B.esp"]));
+
+        assert_eq!(1, diff.moves().len());
+        assert_eq!(1, diff.moves()[0].old_index());
+        assert_eq!(0, diff.moves()[0].new_index());
+        assert_eq!(None, diff.moves()[0].load_after());
+        assert_eq!(0, diff.unchanged_count());
+    }
+
+    #[test]
+    fn new_should_treat_plugins_missing_from_the_current_load_order_as_loading_last() {
+        let diff = LoadOrderDiff::new(&["B.esp"], &report(&["A.esp", "B.esp"]));
+
+        assert_eq!(1, diff.moves().len());
+        assert_eq!("A.esp", diff.moves()[0].name());
+        assert_eq!(1, diff.moves()[0].old_index());
+        assert_eq!(0, diff.moves()[0].new_index());
+    }
+
+    #[test]
+    fn longest_increasing_subsequence_should_mark_the_subsequence() {
+        assert_eq!(
+            vec![false, true, true, true, false],
+            longest_increasing_subsequence(&[1, 0, 3, 4, 2])
+        );
+        assert!(longest_increasing_subsequence(&[]).is_empty());
+    }
+}
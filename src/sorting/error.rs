@@ -49,6 +49,82 @@ impl Display for CyclicInteractionError {
 
 impl std::error::Error for CyclicInteractionError {}
 
+/// Represents a strongly connected component of the plugin graph, i.e. a set
+/// of plugins that all have paths to each other, and so are involved in at
+/// least one cycle.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CyclicComponent {
+    plugins: Vec<String>,
+    cycle: Vec<Vertex>,
+}
+
+impl CyclicComponent {
+    pub(crate) fn new(plugins: Vec<String>, cycle: Vec<Vertex>) -> Self {
+        Self { plugins, cycle }
+    }
+
+    /// Get the names of the plugins in the component, in lexicographical
+    /// order.
+    pub fn plugins(&self) -> &[String] {
+        &self.plugins
+    }
+
+    /// Get a cycle that passes through the component, as a path that starts
+    /// and ends with the same vertex.
+    ///
+    /// The last vertex in the path has an out edge type that goes back to the
+    /// first vertex, as with [SortPluginsError::CycleFound][crate::error::SortPluginsError::CycleFound].
+    pub fn cycle(&self) -> &[Vertex] {
+        &self.cycle
+    }
+}
+
+/// Represents a [Pin][crate::sorting::pins::Pin] that could not be applied.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum PinError {
+    /// The plugin is pinned to an index that is not within the range of
+    /// indices that plugins with its master flag can occupy.
+    IndexOutOfRange { plugin: String, index: usize },
+    /// The plugin is pinned relative to a plugin that has a different master
+    /// flag.
+    DifferentPartition {
+        plugin: String,
+        other_plugin: String,
+    },
+    /// The pin contradicts the plugins' masters or the game's hardcoded load
+    /// order. The path forms a cycle, as with
+    /// [SortPluginsError::CycleFound][crate::error::SortPluginsError::CycleFound].
+    ConflictingEdges(Vec<Vertex>),
+}
+
+impl Display for PinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IndexOutOfRange { plugin, index } => write!(
+                f,
+                "the plugin \"{plugin}\" cannot be pinned to index {index}"
+            ),
+            Self::DifferentPartition {
+                plugin,
+                other_plugin,
+            } => write!(
+                f,
+                "the plugin \"{plugin}\" cannot be pinned relative to \"{other_plugin}\" as only one of them is a master"
+            ),
+            Self::ConflictingEdges(c) => {
+                write!(
+                    f,
+                    "the pin conflicts with other edges: {}",
+                    display_cycle(c)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PinError {}
+
 pub(crate) fn display_cycle(cycle: &[Vertex]) -> String {
     cycle
         .iter()
@@ -229,7 +305,11 @@ pub(crate) enum SortingError {
     ValidationError(PluginGraphValidationError),
     UndefinedGroup(UndefinedGroupError),
     CycleFound(CyclicInteractionError),
+    CyclesFound(Vec<CyclicComponent>),
     CycleInvolving(String),
+    NonUniqueOrder(String, String),
+    InvalidPin(PinError),
+    Cancelled,
     PluginDataError(PluginDataError),
     PathfindingError(PathfindingError),
 }
@@ -240,7 +320,14 @@ impl Display for SortingError {
             Self::ValidationError(_) => write!(f, "plugin graph validation failed"),
             Self::UndefinedGroup(_) => write!(f, "found an undefined group"),
             Self::CycleFound(_) => write!(f, "found a cycle"),
+            Self::CyclesFound(c) => write!(f, "found {} cyclic components", c.len()),
             Self::CycleInvolving(n) => write!(f, "found a cycle involving \"{n}\""),
+            Self::NonUniqueOrder(a, b) => write!(
+                f,
+                "the sorted load order is not unique: no edge exists between \"{a}\" and \"{b}\""
+            ),
+            Self::InvalidPin(_) => write!(f, "found an invalid pin"),
+            Self::Cancelled => write!(f, "sorting was cancelled"),
             Self::PluginDataError(_) => write!(f, "failed to read plugin data"),
             Self::PathfindingError(_) => write!(f, "failed to find a path in the plugins graph"),
         }
@@ -253,7 +340,11 @@ impl std::error::Error for SortingError {
             Self::ValidationError(e) => Some(e),
             Self::UndefinedGroup(e) => Some(e),
             Self::CycleFound(e) => Some(e),
-            Self::CycleInvolving(_) => None,
+            Self::CyclesFound(_)
+            | Self::CycleInvolving(_)
+            | Self::NonUniqueOrder(_, _)
+            | Self::Cancelled => None,
+            Self::InvalidPin(e) => Some(e),
             Self::PluginDataError(e) => Some(e),
             Self::PathfindingError(e) => Some(e),
         }
@@ -278,6 +369,12 @@ impl From<CyclicInteractionError> for SortingError {
     }
 }
 
+impl From<PinError> for SortingError {
+    fn from(value: PinError) -> Self {
+        SortingError::InvalidPin(value)
+    }
+}
+
 impl From<PluginDataError> for SortingError {
     fn from(value: PluginDataError) -> Self {
         SortingError::PluginDataError(value)
@@ -0,0 +1,206 @@
+use crate::EdgeType;
+
+/// A graph of plugins and the interactions between them, as built when sorting
+/// those plugins.
+///
+/// Masters, non-masters and blueprint masters are sorted using separate
+/// graphs, so the graph contains no edges between plugins in different
+/// partitions.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SortingGraph {
+    nodes: Vec<SortingGraphNode>,
+    edges: Vec<SortingGraphEdge>,
+}
+
+impl SortingGraph {
+    pub(crate) fn add_node(&mut self, node: SortingGraphNode) {
+        self.nodes.push(node);
+    }
+
+    pub(crate) fn add_edge(&mut self, edge: SortingGraphEdge) {
+        self.edges.push(edge);
+    }
+
+    /// Get the graph's nodes.
+    pub fn nodes(&self) -> &[SortingGraphNode] {
+        &self.nodes
+    }
+
+    /// Get the graph's edges.
+    pub fn edges(&self) -> &[SortingGraphEdge] {
+        &self.edges
+    }
+
+    /// Serialise the graph in the Graphviz DOT language.
+    ///
+    /// Each edge is labelled with its [EdgeType].
+    pub fn to_dot(&self) -> String {
+        let nodes = self.nodes.iter().map(|n| {
+            format!(
+                "    \"{}\" [tooltip=\"{}\"];\n",
+                escape_dot_string(&n.name),
+                escape_dot_string(&n.group)
+            )
+        });
+
+        let edges = self.edges.iter().map(|e| {
+            format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape_dot_string(&e.from),
+                escape_dot_string(&e.to),
+                e.edge_type
+            )
+        });
+
+        format!("digraph {{\n{}}}", nodes.chain(edges).collect::<String>())
+    }
+
+    /// Serialise the graph as a JSON object with `nodes` and `edges` arrays.
+    ///
+    /// Each node is an object with `name` and `group` string properties, and
+    /// each edge is an object with `from`, `to` and `type` string properties,
+    /// where `from` and `to` are plugin names and `type` is the edge's
+    /// [EdgeType].
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                serde_json::json!({
+                    "name": n.name,
+                    "group": n.group,
+                })
+            })
+            .collect();
+
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "from": e.from,
+                    "to": e.to,
+                    "type": e.edge_type.to_string(),
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "nodes": nodes,
+            "edges": edges,
+        }))
+    }
+}
+
+/// A plugin in a [SortingGraph].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SortingGraphNode {
+    name: String,
+    group: String,
+}
+
+impl SortingGraphNode {
+    pub(crate) fn new(name: String, group: String) -> Self {
+        Self { name, group }
+    }
+
+    /// Get the name of the plugin.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the name of the group that the plugin belongs to.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+}
+
+/// An edge going from one plugin to another in a [SortingGraph].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SortingGraphEdge {
+    from: String,
+    to: String,
+    edge_type: EdgeType,
+}
+
+impl SortingGraphEdge {
+    pub(crate) fn new(from: String, to: String, edge_type: EdgeType) -> Self {
+        Self {
+            from,
+            to,
+            edge_type,
+        }
+    }
+
+    /// Get the name of the plugin that loads earlier.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Get the name of the plugin that loads later.
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// Get the type of the edge.
+    pub fn edge_type(&self) -> EdgeType {
+        self.edge_type.clone()
+    }
+}
+
+fn escape_dot_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> SortingGraph {
+        let mut graph = SortingGraph::default();
+        graph.add_node(SortingGraphNode::new("A.esp".into(), "default".into()));
+        graph.add_node(SortingGraphNode::new("B \"1\".esp".into(), "late".into()));
+        graph.add_edge(SortingGraphEdge::new(
+            "A.esp".into(),
+            "B \"1\".esp".into(),
+            EdgeType::RecordOverlap,
+        ));
+        graph
+    }
+
+    #[test]
+    fn to_dot_should_output_all_nodes_and_labelled_edges() {
+        let expected = r#"digraph {
+    "A.esp" [tooltip="default"];
+    "B \"1\".esp" [tooltip="late"];
+    "A.esp" -> "B \"1\".esp" [label="Record Overlap"];
+}"#;
+
+        assert_eq!(expected, graph().to_dot());
+    }
+
+    #[test]
+    fn to_dot_should_output_an_empty_digraph_if_there_are_no_nodes() {
+        assert_eq!("digraph {\n}", SortingGraph::default().to_dot());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn to_json_should_output_all_nodes_and_labelled_edges() {
+        let json: serde_json::Value = serde_json::from_str(&graph().to_json().unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::json!({
+                "nodes": [
+                    { "name": "A.esp", "group": "default" },
+                    { "name": "B \"1\".esp", "group": "late" },
+                ],
+                "edges": [
+                    { "from": "A.esp", "to": "B \"1\".esp", "type": "Record Overlap" },
+                ],
+            }),
+            json
+        );
+    }
+}
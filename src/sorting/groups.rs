@@ -1,4 +1,4 @@
-use std::cmp::Reverse;
+use std::{cmp::Reverse, sync::Arc};
 
 use rustc_hash::FxHashMap as HashMap;
 
@@ -26,6 +26,18 @@ pub type GroupsGraph = Graph<Box<str>, EdgeType>;
 pub fn build_groups_graph(
     masterlist_groups: &[Group],
     userlist_groups: &[Group],
+) -> Result<GroupsGraph, BuildGroupsGraphError> {
+    build_layered_groups_graph(masterlist_groups, &[], userlist_groups)
+}
+
+/// Build a groups graph from the masterlist's groups, the groups of each
+/// additional metadata layer in order, paired with the layer's name, and the
+/// userlist's groups. Each layer's groups can only load after groups defined
+/// in the masterlist or in the same or an earlier layer.
+pub fn build_layered_groups_graph(
+    masterlist_groups: &[Group],
+    layer_groups: &[(Arc<str>, &[Group])],
+    userlist_groups: &[Group],
 ) -> Result<GroupsGraph, BuildGroupsGraphError> {
     let masterlist_groups = sorted_by_name(masterlist_groups);
     let userlist_groups = sorted_by_name(userlist_groups);
@@ -38,15 +50,25 @@ pub fn build_groups_graph(
         &mut graph,
         &mut group_nodes,
         &masterlist_groups,
-        EdgeType::MasterlistLoadAfter,
+        &EdgeType::MasterlistLoadAfter,
     )?;
 
+    for (name, groups) in layer_groups {
+        logging::trace!("Adding metadata layer \"{name}\" groups to groups graph...");
+        add_groups(
+            &mut graph,
+            &mut group_nodes,
+            &sorted_by_name(groups),
+            &EdgeType::LayerLoadAfter(Arc::clone(name)),
+        )?;
+    }
+
     logging::trace!("Adding user groups to groups graph...");
     add_groups(
         &mut graph,
         &mut group_nodes,
         &userlist_groups,
-        EdgeType::UserLoadAfter,
+        &EdgeType::UserLoadAfter,
     )?;
 
     if let Some(cycle) = find_cycle(&graph, |node| node.clone().into_string()) {
@@ -67,7 +89,7 @@ fn add_groups<'a>(
     graph: &mut GroupsGraph,
     group_nodes: &mut HashMap<&'a str, NodeIndex>,
     groups: &[&'a Group],
-    edge_type: EdgeType,
+    edge_type: &EdgeType,
 ) -> Result<(), UndefinedGroupError> {
     for group in groups {
         let key = group.name();
@@ -96,7 +118,7 @@ fn add_groups<'a>(
 
         for other_group_name in sorted_clone(group.after_groups()) {
             if let Some(other_index) = group_nodes.get(other_group_name) {
-                graph.update_edge(*other_index, *node_index, edge_type);
+                graph.update_edge(*other_index, *node_index, edge_type.clone());
             } else {
                 return Err(UndefinedGroupError::new(other_group_name.to_owned()));
             }
@@ -176,7 +198,7 @@ pub fn find_path(
         };
 
         let vertex = Vertex::new(graph[*preceding_vertex].clone().into_string())
-            .with_out_edge_type(graph[edge]);
+            .with_out_edge_type(graph[edge].clone());
         path.push(vertex);
 
         current = *preceding_vertex;
@@ -307,6 +329,49 @@ mod tests {
             }
         }
 
+        #[test]
+        fn should_error_if_layer_group_loads_after_group_from_a_higher_layer() {
+            let layer_0 = &[Group::new("b".into()).with_after_groups(vec!["a".into()])];
+            let layer_1 = &[Group::new("a".into())];
+
+            let layers = [
+                ("modpack".into(), layer_0.as_slice()),
+                ("local".into(), layer_1),
+            ];
+
+            match build_layered_groups_graph(&[], &layers, &[]) {
+                Err(BuildGroupsGraphError::UndefinedGroup(e)) => {
+                    assert_eq!("a", e.into_group_name());
+                }
+                _ => panic!("Expected an undefined group error"),
+            }
+        }
+
+        #[test]
+        fn should_use_layer_load_after_edges_for_layer_groups() {
+            let masterlist = &[Group::new("a".into())];
+            let layer_0 = &[Group::new("b".into()).with_after_groups(vec!["a".into()])];
+            let layer_1 = &[Group::new("c".into()).with_after_groups(vec!["b".into()])];
+            let layers = [
+                ("modpack".into(), layer_0.as_slice()),
+                ("local".into(), layer_1),
+            ];
+            let graph = build_layered_groups_graph(masterlist, &layers, &[]).unwrap();
+
+            let path = find_path(&graph, "a", "c").unwrap();
+
+            assert_eq!(
+                &[
+                    Vertex::new("a".into())
+                        .with_out_edge_type(EdgeType::LayerLoadAfter("modpack".into())),
+                    Vertex::new("b".into())
+                        .with_out_edge_type(EdgeType::LayerLoadAfter("local".into())),
+                    Vertex::new("c".into()),
+                ],
+                path.as_slice()
+            );
+        }
+
         #[test]
         fn should_error_if_after_groups_are_cyclic() {
             let masterlist = &[
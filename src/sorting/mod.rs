@@ -1,7 +1,16 @@
+pub mod custom;
 mod dfs;
+pub mod diff;
 pub mod error;
+pub mod export;
 pub mod groups;
+pub mod options;
+pub mod overlap;
+pub mod pins;
 pub mod plugins;
+pub mod progress;
+pub mod report;
+pub mod session;
 mod validate;
 pub mod vertex;
 
@@ -14,6 +23,7 @@ mod test {
     pub struct TestPlugin {
         name: String,
         masters: Vec<String>,
+        pub(super) crc: Option<u32>,
         pub(super) is_master: bool,
         pub(super) is_blueprint_plugin: bool,
         pub(super) override_record_count: usize,
@@ -60,6 +70,10 @@ mod test {
             Ok(self.masters.clone())
         }
 
+        fn crc(&self) -> Option<u32> {
+            self.crc
+        }
+
         fn override_record_count(&self) -> Result<usize, PluginDataError> {
             Ok(self.override_record_count)
         }
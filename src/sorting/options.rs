@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use super::{
+    error::SortingError,
+    overlap::{CountOverlapPolicy, OverlapPolicy},
+    pins::Pin,
+    progress::{CancellationToken, ProgressCallback, SortPhase},
+};
+
+/// Options that control how plugins are sorted.
+#[derive(Clone)]
+pub struct SortOptions {
+    report_all_cycles: bool,
+    require_unique_order: bool,
+    overlap_policy: Arc<dyn OverlapPolicy>,
+    pins: Vec<Pin>,
+    progress_callback: Option<Arc<ProgressCallback>>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl SortOptions {
+    /// Create options that give the default sorting behaviour.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether sorting should report every cycle in the plugin graph
+    /// instead of only the first cycle that it finds.
+    ///
+    /// If `true`, all plugins are checked for cycles caused by their data and
+    /// non-group metadata before any plugins are sorted, and if any cycles are
+    /// found sorting fails with [SortPluginsError::CyclesFound][crate::error::SortPluginsError::CyclesFound].
+    #[must_use]
+    pub fn with_report_all_cycles(mut self, report_all_cycles: bool) -> Self {
+        self.report_all_cycles = report_all_cycles;
+        self
+    }
+
+    /// Get whether sorting should report every cycle in the plugin graph.
+    pub fn report_all_cycles(&self) -> bool {
+        self.report_all_cycles
+    }
+
+    /// Set whether sorting should fail if the sorted load order is not the
+    /// only possible result.
+    ///
+    /// Sorting should always produce a unique load order, but if it doesn't
+    /// then the result may change unpredictably between sorts. By default this
+    /// is logged as an error and the result is returned anyway. If `true`,
+    /// sorting instead fails with
+    /// [SortPluginsError::NonUniqueOrder][crate::error::SortPluginsError::NonUniqueOrder],
+    /// which gives the first pair of adjacent plugins that have no edge
+    /// between them.
+    #[must_use]
+    pub fn with_require_unique_order(mut self, require_unique_order: bool) -> Self {
+        self.require_unique_order = require_unique_order;
+        self
+    }
+
+    /// Get whether sorting should fail if the sorted load order is not unique.
+    pub fn require_unique_order(&self) -> bool {
+        self.require_unique_order
+    }
+
+    /// Set the policy that decides the load order of plugins that overlap.
+    ///
+    /// The default policy is [CountOverlapPolicy].
+    #[must_use]
+    pub fn with_overlap_policy(mut self, overlap_policy: Arc<dyn OverlapPolicy>) -> Self {
+        self.overlap_policy = overlap_policy;
+        self
+    }
+
+    /// Get the policy that decides the load order of plugins that overlap.
+    pub fn overlap_policy(&self) -> &dyn OverlapPolicy {
+        self.overlap_policy.as_ref()
+    }
+
+    /// Set the pins that fix the positions of plugins in the sorted load
+    /// order.
+    ///
+    /// If a pin contradicts a plugin's masters or the game's hardcoded load
+    /// order, sorting fails with
+    /// [SortPluginsError::InvalidPin][crate::error::SortPluginsError::InvalidPin].
+    /// Pins that contradict each other or plugin metadata cause sorting to
+    /// fail with [SortPluginsError::CycleFound][crate::error::SortPluginsError::CycleFound].
+    #[must_use]
+    pub fn with_pins(mut self, pins: Vec<Pin>) -> Self {
+        self.pins = pins;
+        self
+    }
+
+    /// Get the pins that fix the positions of plugins in the sorted load
+    /// order.
+    pub fn pins(&self) -> &[Pin] {
+        &self.pins
+    }
+
+    /// Set a callback that is called to report progress during the slowest
+    /// phases of sorting.
+    ///
+    /// The callback may be called from more than one thread at the same time.
+    #[must_use]
+    pub fn with_progress_callback(mut self, progress_callback: Arc<ProgressCallback>) -> Self {
+        self.progress_callback = Some(progress_callback);
+        self
+    }
+
+    /// Set a token that can be used to cancel sorting.
+    ///
+    /// The token is checked between sorting phases and while processing each
+    /// item in a phase, and if it has been cancelled sorting fails with
+    /// [SortPluginsError::Cancelled][crate::error::SortPluginsError::Cancelled].
+    #[must_use]
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Get the token that can be used to cancel sorting, if one has been set.
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Returns an error if sorting has been cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<(), SortingError> {
+        if self
+            .cancellation_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Err(SortingError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Report progress to the progress callback, if there is one and the phase
+    /// has any items to process, then check if sorting has been cancelled.
+    pub(crate) fn report_progress(
+        &self,
+        phase: SortPhase,
+        done: usize,
+        total: usize,
+    ) -> Result<(), SortingError> {
+        if total > 0
+            && let Some(callback) = &self.progress_callback
+        {
+            callback(phase, done, total);
+        }
+
+        self.check_cancelled()
+    }
+}
+
+impl std::fmt::Debug for SortOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SortOptions")
+            .field("report_all_cycles", &self.report_all_cycles)
+            .field("require_unique_order", &self.require_unique_order)
+            .field("overlap_policy", &self.overlap_policy)
+            .field("pins", &self.pins)
+            .field("cancellation_token", &self.cancellation_token)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::default::Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            report_all_cycles: false,
+            require_unique_order: false,
+            overlap_policy: Arc::new(CountOverlapPolicy::default()),
+            pins: Vec::new(),
+            progress_callback: None,
+            cancellation_token: None,
+        }
+    }
+}
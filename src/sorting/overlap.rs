@@ -0,0 +1,326 @@
+use crate::{EdgeType, plugin::error::PluginDataError};
+
+/// A strategy for deciding which of two plugins should load first when they
+/// overlap.
+///
+/// Sorting calls [OverlapPolicy::compare] for each pair of plugins that aren't
+/// already directly linked by another interaction, unless one of the plugins
+/// overrides no records and loads no assets. If the policy returns an
+/// [OverlapEdge], an edge is added between the two plugins unless doing so
+/// would cause a cycle.
+///
+/// A policy only has the data that [OverlapPlugin] and [OverlapPair] provide,
+/// which doesn't include the types of the records that a plugin overrides, so
+/// a strategy such as weighting overrides by record type needs to get that
+/// data itself, e.g. by reading each plugin's file, using the plugin's name to
+/// find it.
+pub trait OverlapPolicy: std::fmt::Debug + Send + Sync {
+    /// Decide which plugin in the given pair should load first, if either.
+    fn compare(&self, pair: &mut OverlapPair<'_>) -> Result<Option<OverlapEdge>, PluginDataError>;
+}
+
+/// The default overlap policy, which compares overlapping plugins by the
+/// number of records they override and the number of assets they load.
+///
+/// If two plugins' records overlap and they override different numbers of
+/// records, the plugin that overrides more records loads first. Otherwise, if
+/// their assets overlap and they load different numbers of assets, the plugin
+/// that loads more assets loads first.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CountOverlapPolicy {
+    compare_records: bool,
+    compare_assets: bool,
+}
+
+impl CountOverlapPolicy {
+    /// Create a policy that compares both record and asset overlap.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the policy should compare plugins with overlapping records.
+    #[must_use]
+    pub fn with_compare_records(mut self, compare_records: bool) -> Self {
+        self.compare_records = compare_records;
+        self
+    }
+
+    /// Set whether the policy should compare plugins with overlapping assets.
+    #[must_use]
+    pub fn with_compare_assets(mut self, compare_assets: bool) -> Self {
+        self.compare_assets = compare_assets;
+        self
+    }
+}
+
+impl std::default::Default for CountOverlapPolicy {
+    fn default() -> Self {
+        Self {
+            compare_records: true,
+            compare_assets: true,
+        }
+    }
+}
+
+impl OverlapPolicy for CountOverlapPolicy {
+    fn compare(&self, pair: &mut OverlapPair<'_>) -> Result<Option<OverlapEdge>, PluginDataError> {
+        // Records are more significant than assets, so if two plugins have
+        // overlapping records and assets and one overrides more records but
+        // loads fewer assets than the other, the fact it overrides more
+        // records should take precedence. I.e. if two plugins don't have
+        // overlapping records, check their assets, otherwise only check their
+        // assets if their override record counts are equal.
+        let plugin = pair.plugin();
+        let other_plugin = pair.other_plugin();
+
+        if self.compare_records
+            && plugin.override_record_count() != other_plugin.override_record_count()
+            && pair.records_overlap()?
+        {
+            return Ok(Some(OverlapEdge::new(
+                plugin.override_record_count() > other_plugin.override_record_count(),
+                OverlapType::Records,
+            )));
+        }
+
+        if self.compare_assets
+            && plugin.asset_count() != other_plugin.asset_count()
+            && pair.assets_overlap()
+        {
+            return Ok(Some(OverlapEdge::new(
+                plugin.asset_count() > other_plugin.asset_count(),
+                OverlapType::Assets,
+            )));
+        }
+
+        Ok(None)
+    }
+}
+
+/// The data about a plugin that is available to an [OverlapPolicy].
+///
+/// Only the counts of overridden records and loaded assets are available, as
+/// the data that libloot reads from a plugin while sorting doesn't record
+/// which types of record it overrides.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct OverlapPlugin<'a> {
+    name: &'a str,
+    override_record_count: usize,
+    asset_count: usize,
+}
+
+impl<'a> OverlapPlugin<'a> {
+    pub(crate) fn new(name: &'a str, override_record_count: usize, asset_count: usize) -> Self {
+        Self {
+            name,
+            override_record_count,
+            asset_count,
+        }
+    }
+
+    /// Get the plugin's filename.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get the number of records that the plugin overrides.
+    pub fn override_record_count(&self) -> usize {
+        self.override_record_count
+    }
+
+    /// Get the number of assets that the plugin loads.
+    pub fn asset_count(&self) -> usize {
+        self.asset_count
+    }
+}
+
+/// A pair of plugins being compared by an [OverlapPolicy].
+///
+/// Checking whether two plugins overlap can be relatively slow, so the checks
+/// are only done when asked for.
+pub struct OverlapPair<'a> {
+    plugin: OverlapPlugin<'a>,
+    other_plugin: OverlapPlugin<'a>,
+    checker: &'a mut dyn OverlapChecker,
+}
+
+impl<'a> OverlapPair<'a> {
+    pub(crate) fn new(
+        plugin: OverlapPlugin<'a>,
+        other_plugin: OverlapPlugin<'a>,
+        checker: &'a mut dyn OverlapChecker,
+    ) -> Self {
+        Self {
+            plugin,
+            other_plugin,
+            checker,
+        }
+    }
+
+    /// Get the first plugin in the pair.
+    pub fn plugin(&self) -> OverlapPlugin<'a> {
+        self.plugin
+    }
+
+    /// Get the second plugin in the pair.
+    pub fn other_plugin(&self) -> OverlapPlugin<'a> {
+        self.other_plugin
+    }
+
+    /// Check if the two plugins edit any of the same records.
+    pub fn records_overlap(&mut self) -> Result<bool, PluginDataError> {
+        self.checker.records_overlap()
+    }
+
+    /// Check if the two plugins load any assets with the same path.
+    pub fn assets_overlap(&mut self) -> bool {
+        self.checker.assets_overlap()
+    }
+}
+
+/// Checks whether a pair of plugins overlap.
+pub(crate) trait OverlapChecker {
+    fn records_overlap(&mut self) -> Result<bool, PluginDataError>;
+
+    fn assets_overlap(&mut self) -> bool;
+}
+
+impl std::fmt::Debug for OverlapPair<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OverlapPair")
+            .field("plugin", &self.plugin)
+            .field("other_plugin", &self.other_plugin)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The ways in which two plugins can overlap.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OverlapType {
+    /// The plugins edit some of the same records.
+    Records,
+    /// The plugins load some assets with the same paths.
+    Assets,
+}
+
+impl From<OverlapType> for EdgeType {
+    fn from(value: OverlapType) -> Self {
+        match value {
+            OverlapType::Records => EdgeType::RecordOverlap,
+            OverlapType::Assets => EdgeType::AssetOverlap,
+        }
+    }
+}
+
+/// The result of an [OverlapPolicy] deciding that one plugin in a pair should
+/// load before the other.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct OverlapEdge {
+    plugin_loads_first: bool,
+    overlap_type: OverlapType,
+}
+
+impl OverlapEdge {
+    /// Create an edge that is due to the given type of overlap.
+    ///
+    /// If `plugin_loads_first` is `true`, the first plugin in the pair should
+    /// load before the second plugin, otherwise it should load after.
+    #[must_use]
+    pub fn new(plugin_loads_first: bool, overlap_type: OverlapType) -> Self {
+        Self {
+            plugin_loads_first,
+            overlap_type,
+        }
+    }
+
+    /// Get whether the first plugin in the pair should load first.
+    pub fn plugin_loads_first(&self) -> bool {
+        self.plugin_loads_first
+    }
+
+    /// Get the type of overlap that the edge is due to.
+    pub fn overlap_type(&self) -> OverlapType {
+        self.overlap_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestChecker {
+        records_overlap: bool,
+        assets_overlap: bool,
+    }
+
+    impl OverlapChecker for TestChecker {
+        fn records_overlap(&mut self) -> Result<bool, PluginDataError> {
+            Ok(self.records_overlap)
+        }
+
+        fn assets_overlap(&mut self) -> bool {
+            self.assets_overlap
+        }
+    }
+
+    fn compare(
+        policy: CountOverlapPolicy,
+        counts: (usize, usize, usize, usize),
+        records_overlap: bool,
+        assets_overlap: bool,
+    ) -> Option<OverlapEdge> {
+        let mut checker = TestChecker {
+            records_overlap,
+            assets_overlap,
+        };
+        let mut pair = OverlapPair::new(
+            OverlapPlugin::new("A.esp", counts.0, counts.1),
+            OverlapPlugin::new("B.esp", counts.2, counts.3),
+            &mut checker,
+        );
+
+        policy.compare(&mut pair).unwrap()
+    }
+
+    mod count_overlap_policy {
+        use super::*;
+
+        #[test]
+        fn compare_should_prefer_record_overlap_over_asset_overlap() {
+            let edge = compare(CountOverlapPolicy::new(), (2, 1, 1, 2), true, true);
+
+            assert_eq!(Some(OverlapEdge::new(true, OverlapType::Records)), edge);
+        }
+
+        #[test]
+        fn compare_should_compare_assets_if_record_counts_are_equal() {
+            let edge = compare(CountOverlapPolicy::new(), (1, 1, 1, 2), true, true);
+
+            assert_eq!(Some(OverlapEdge::new(false, OverlapType::Assets)), edge);
+        }
+
+        #[test]
+        fn compare_should_return_none_if_nothing_overlaps() {
+            let edge = compare(CountOverlapPolicy::new(), (2, 1, 1, 2), false, false);
+
+            assert_eq!(None, edge);
+        }
+
+        #[test]
+        fn compare_should_ignore_assets_if_configured_to() {
+            let policy = CountOverlapPolicy::new().with_compare_assets(false);
+            let edge = compare(policy, (1, 1, 1, 2), true, true);
+
+            assert_eq!(None, edge);
+        }
+
+        #[test]
+        fn compare_should_ignore_records_if_configured_to() {
+            let policy = CountOverlapPolicy::new().with_compare_records(false);
+            let edge = compare(policy, (2, 2, 1, 1), true, true);
+
+            assert_eq!(Some(OverlapEdge::new(true, OverlapType::Assets)), edge);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+/// A constraint that fixes the position of a plugin in the sorted load order.
+///
+/// Pins are applied as [EdgeType::Pin][crate::EdgeType::Pin] edges, which
+/// must be satisfied in the same way as edges from plugin data and metadata.
+/// Master-flagged and non-master plugins are sorted separately, so a pin can
+/// only position a plugin relative to other plugins that have the same master
+/// flag (and are both blueprint plugins or not).
+///
+/// Pins for plugins that are not being sorted are ignored.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Pin {
+    plugin: String,
+    position: PinPosition,
+}
+
+impl Pin {
+    /// Create a pin that fixes the given plugin at the given position.
+    #[must_use]
+    pub fn new(plugin: String, position: PinPosition) -> Self {
+        Self { plugin, position }
+    }
+
+    /// Get the filename of the pinned plugin.
+    pub fn plugin(&self) -> &str {
+        &self.plugin
+    }
+
+    /// Get the position that the plugin is pinned to.
+    pub fn position(&self) -> &PinPosition {
+        &self.position
+    }
+}
+
+/// The positions that a plugin can be pinned to.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum PinPosition {
+    /// The plugin must be at the given zero-based index in the sorted load
+    /// order.
+    ///
+    /// The plugins that load before the pinned plugin are those that are
+    /// earliest in the current load order, so pinning a plugin to its current
+    /// index keeps it where it is.
+    Index(usize),
+    /// The plugin must load before all other plugins that have the same
+    /// master flag.
+    First,
+    /// The plugin must load after all other plugins that have the same master
+    /// flag.
+    Last,
+    /// The plugin must load before the named plugin. If the named plugin is
+    /// not being sorted, the pin is ignored.
+    Before(String),
+    /// The plugin must load after the named plugin. If the named plugin is
+    /// not being sorted, the pin is ignored.
+    After(String),
+}
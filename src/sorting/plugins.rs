@@ -1,27 +1,51 @@
-use std::rc::Rc;
+use std::{
+    collections::VecDeque,
+    rc::Rc,
+    sync::{
+        Arc, PoisonError, RwLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use petgraph::{
     Graph,
     graph::{EdgeReference, NodeIndex},
     visit::EdgeRef,
 };
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
 
 use crate::{
-    EdgeType, LogLevel, Plugin,
+    EdgeType, LogLevel, Plugin, Vertex,
     logging::{self, is_log_enabled},
     metadata::{File, Group, PluginMetadata},
     plugin::error::PluginDataError,
     sorting::{
-        error::{CyclicInteractionError, PathfindingError, SortingError, UndefinedGroupError},
+        error::{
+            CyclicComponent, CyclicInteractionError, PathfindingError, PinError, SortingError,
+            UndefinedGroupError,
+        },
+        export::{SortingGraph, SortingGraphEdge, SortingGraphNode},
         groups::{get_default_group_node, sorted_group_nodes},
+        options::SortOptions,
+        overlap::{OverlapChecker, OverlapEdge, OverlapPair, OverlapPlugin},
+        pins::{Pin, PinPosition},
+        progress::SortPhase,
+        report::{MoveDirection, MovedPlugin, SortReport},
+        session::OverlapCache,
     },
 };
 
 use super::{
-    dfs::{BidirBfsVisitor, DfsVisitor, bidirectional_bfs, depth_first_search, find_cycle},
+    dfs::{
+        BidirBfsVisitor, DfsVisitor, bidirectional_bfs, depth_first_search, find_all_cycles,
+        find_cycle,
+    },
     groups::GroupsGraph,
-    validate::{validate_plugin_groups, validate_specific_and_hardcoded_edges},
+    validate::{
+        find_ignored_edges, validate_pins, validate_plugin_groups,
+        validate_specific_and_hardcoded_edges,
+    },
 };
 
 #[derive(Debug)]
@@ -33,11 +57,61 @@ pub struct PluginSortingData<'a, T: SortingPlugin> {
     load_order_index: usize,
 
     pub(super) group: Box<str>,
-    group_is_user_metadata: bool,
+    group_source: GroupSource,
     pub(crate) masterlist_load_after: Box<[String]>,
     pub(crate) user_load_after: Box<[String]>,
     pub(crate) masterlist_req: Box<[String]>,
     pub(crate) user_req: Box<[String]>,
+    /// "Load after" filenames from additional metadata layers, each paired
+    /// with the name of the layer that it came from.
+    pub(crate) layer_load_after: Box<[(Arc<str>, String)]>,
+    /// Requirement filenames from additional metadata layers, each paired
+    /// with the name of the layer that it came from.
+    pub(crate) layer_req: Box<[(Arc<str>, String)]>,
+}
+
+/// The metadata that set a plugin's group or that a path between groups
+/// involves.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+enum GroupSource {
+    #[default]
+    Masterlist,
+    Layer(Arc<str>),
+    User,
+}
+
+impl GroupSource {
+    fn from_load_after_edge(edge_type: &EdgeType) -> Self {
+        match edge_type {
+            EdgeType::UserLoadAfter => GroupSource::User,
+            EdgeType::LayerLoadAfter(name) => GroupSource::Layer(Arc::clone(name)),
+            _ => GroupSource::Masterlist,
+        }
+    }
+
+    /// Get a key that orders sources by increasing precedence, given the names
+    /// of the additional metadata layers from lowest to highest precedence.
+    fn precedence(&self, metadata_layers: &[Arc<str>]) -> (u8, usize) {
+        match self {
+            GroupSource::Masterlist => (0, 0),
+            GroupSource::Layer(name) => (
+                1,
+                metadata_layers
+                    .iter()
+                    .position(|l| l == name)
+                    .unwrap_or_default(),
+            ),
+            GroupSource::User => (2, 0),
+        }
+    }
+
+    fn group_edge_type(&self) -> EdgeType {
+        match self {
+            GroupSource::Masterlist => EdgeType::MasterlistGroup,
+            GroupSource::Layer(name) => EdgeType::LayerGroup(Arc::clone(name)),
+            GroupSource::User => EdgeType::UserGroup,
+        }
+    }
 }
 
 impl<'a, T: SortingPlugin> PluginSortingData<'a, T> {
@@ -59,7 +133,11 @@ impl<'a, T: SortingPlugin> PluginSortingData<'a, T> {
                 .or_else(|| masterlist_metadata.and_then(|m| m.group()))
                 .unwrap_or(Group::DEFAULT_NAME)
                 .into(),
-            group_is_user_metadata: user_metadata.and_then(|m| m.group()).is_some(),
+            group_source: if user_metadata.and_then(|m| m.group()).is_some() {
+                GroupSource::User
+            } else {
+                GroupSource::Masterlist
+            },
             masterlist_load_after: masterlist_metadata
                 .map(|m| to_filenames(m.load_after_files()))
                 .unwrap_or_default(),
@@ -72,9 +150,44 @@ impl<'a, T: SortingPlugin> PluginSortingData<'a, T> {
             user_req: user_metadata
                 .map(|m| to_filenames(m.requirements()))
                 .unwrap_or_default(),
+            layer_load_after: Box::default(),
+            layer_req: Box::default(),
         })
     }
 
+    /// Set the plugin's metadata from additional metadata layers, each paired
+    /// with the name of the layer that it came from, in increasing order of
+    /// precedence. A layer's group is used if the plugin has no user group and
+    /// no higher layer sets a group.
+    #[must_use]
+    pub fn with_layer_metadata(mut self, layers: &[(Arc<str>, PluginMetadata)]) -> Self {
+        let mut load_after = Vec::new();
+        let mut req = Vec::new();
+        for (name, metadata) in layers {
+            if let Some(group) = metadata.group()
+                && self.group_source != GroupSource::User
+            {
+                self.group = group.into();
+                self.group_source = GroupSource::Layer(Arc::clone(name));
+            }
+
+            load_after.extend(
+                to_filenames(metadata.load_after_files())
+                    .into_iter()
+                    .map(|f| (Arc::clone(name), f)),
+            );
+            req.extend(
+                to_filenames(metadata.requirements())
+                    .into_iter()
+                    .map(|f| (Arc::clone(name), f)),
+            );
+        }
+
+        self.layer_load_after = load_after.into();
+        self.layer_req = req.into();
+        self
+    }
+
     pub(super) fn name(&self) -> &str {
         self.plugin.name()
     }
@@ -91,23 +204,180 @@ impl<'a, T: SortingPlugin> PluginSortingData<'a, T> {
         self.plugin.masters()
     }
 
-    fn do_records_overlap(&self, other: &Self) -> Result<bool, PluginDataError> {
+    pub(super) fn crc(&self) -> Option<u32> {
+        self.plugin.crc()
+    }
+
+    pub(super) fn do_records_overlap(&self, other: &Self) -> Result<bool, PluginDataError> {
         self.plugin.do_records_overlap(other.plugin)
     }
 
-    fn do_assets_overlap(&self, other: &Self) -> bool {
+    pub(super) fn do_assets_overlap(&self, other: &Self) -> bool {
         self.plugin.do_assets_overlap(other.plugin)
     }
+
+    fn to_overlap_plugin(&self) -> OverlapPlugin<'_> {
+        OverlapPlugin::new(self.name(), self.override_record_count, self.asset_count())
+    }
+}
+
+/// An overlap cache that is shared between the threads that compare plugins.
+/// The cache only holds results, so it's still usable if a thread panicked
+/// while holding the lock.
+type SharedOverlapCache = RwLock<OverlapCache>;
+
+/// Checks if two plugins overlap, using cached results if available and
+/// caching any newly-calculated results as soon as they are calculated.
+struct PluginOverlapChecker<'a, 'b, T: SortingPlugin> {
+    plugin: &'a PluginSortingData<'b, T>,
+    other_plugin: &'a PluginSortingData<'b, T>,
+    overlap_cache: Option<&'a SharedOverlapCache>,
+    records_overlap: Option<bool>,
+    assets_overlap: Option<bool>,
+}
+
+impl<T: SortingPlugin> OverlapChecker for PluginOverlapChecker<'_, '_, T> {
+    fn records_overlap(&mut self) -> Result<bool, PluginDataError> {
+        let cached = self.overlap_cache.and_then(|c| {
+            c.read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .records_overlap(self.plugin, self.other_plugin)
+        });
+
+        if let Some(overlap) = cached.or(self.records_overlap) {
+            return Ok(overlap);
+        }
+
+        let overlap = self.plugin.do_records_overlap(self.other_plugin)?;
+        self.records_overlap = Some(overlap);
+
+        if let Some(cache) = self.overlap_cache {
+            cache
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert_records_overlap(self.plugin, self.other_plugin, overlap);
+        }
+
+        Ok(overlap)
+    }
+
+    fn assets_overlap(&mut self) -> bool {
+        let cached = self.overlap_cache.and_then(|c| {
+            c.read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .assets_overlap(self.plugin, self.other_plugin)
+        });
+
+        if let Some(overlap) = cached.or(self.assets_overlap) {
+            return overlap;
+        }
+
+        let overlap = self.plugin.do_assets_overlap(self.other_plugin);
+        self.assets_overlap = Some(overlap);
+
+        if let Some(cache) = self.overlap_cache {
+            cache
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert_assets_overlap(self.plugin, self.other_plugin, overlap);
+        }
+
+        overlap
+    }
+}
+
+/// A comparison of two plugins that found that an overlap edge should be
+/// added between them.
+#[derive(Debug)]
+struct PluginsComparison {
+    node_index: NodeIndex,
+    other_node_index: NodeIndex,
+    edge: OverlapEdge,
+}
+
+/// Compare the plugin at the given position with each plugin after it that it
+/// isn't already linked to, as described for `PluginsGraph::compare_plugins`.
+fn compare_plugin_with_later_plugins<T: SortingPlugin>(
+    plugins: &[(NodeIndex, &PluginSortingData<'_, T>)],
+    position: usize,
+    linked_pairs: &HashSet<(NodeIndex, NodeIndex)>,
+    options: &SortOptions,
+    overlap_cache: Option<&SharedOverlapCache>,
+) -> Result<Vec<PluginsComparison>, SortingError> {
+    let Some(((node_index, plugin), other_plugins)) =
+        plugins.get(position..).and_then(<[_]>::split_first)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut comparisons = Vec::new();
+    for (other_node_index, other_plugin) in other_plugins {
+        if linked_pairs.contains(&(*node_index, *other_node_index)) {
+            continue;
+        }
+
+        options.check_cancelled()?;
+
+        let mut checker = PluginOverlapChecker {
+            plugin,
+            other_plugin,
+            overlap_cache,
+            records_overlap: None,
+            assets_overlap: None,
+        };
+        let mut pair = OverlapPair::new(
+            plugin.to_overlap_plugin(),
+            other_plugin.to_overlap_plugin(),
+            &mut checker,
+        );
+
+        if let Some(edge) = options.overlap_policy().compare(&mut pair)? {
+            comparisons.push(PluginsComparison {
+                node_index: *node_index,
+                other_node_index: *other_node_index,
+                edge,
+            });
+        }
+    }
+
+    Ok(comparisons)
 }
 
-pub trait SortingPlugin {
+/// The plugin data that is needed to sort plugins.
+///
+/// This is implemented for [Plugin], and can be implemented for other types to
+/// sort plugins using [sort_plugins][crate::sort_plugins] without loading them
+/// from disk. Implementors must be [Sync] because checking whether plugins
+/// overlap is done in parallel.
+pub trait SortingPlugin: Sync {
+    /// Get the plugin's filename.
     fn name(&self) -> &str;
+
+    /// Check if the plugin is a master plugin, i.e. if it loads before
+    /// non-master plugins.
     fn is_master(&self) -> bool;
+
+    /// Check if the plugin is a blueprint plugin, which loads after all other
+    /// plugins if it is also a master plugin.
     fn is_blueprint_plugin(&self) -> bool;
+
+    /// Get the filenames of the plugin's masters.
     fn masters(&self) -> Result<Vec<String>, PluginDataError>;
+
+    /// Get the plugin's CRC-32 checksum, if it is known.
+    fn crc(&self) -> Option<u32>;
+
+    /// Get the number of records that the plugin overrides.
     fn override_record_count(&self) -> Result<usize, PluginDataError>;
+
+    /// Get the number of assets that the plugin loads.
     fn asset_count(&self) -> usize;
+
+    /// Check if the plugin edits any of the same records as the given plugin.
     fn do_records_overlap(&self, other: &Self) -> Result<bool, PluginDataError>;
+
+    /// Check if the plugin loads any assets with the same path as an asset
+    /// loaded by the given plugin.
     fn do_assets_overlap(&self, other: &Self) -> bool;
 }
 
@@ -126,6 +396,9 @@ impl SortingPlugin for Plugin {
     fn masters(&self) -> Result<Vec<String>, PluginDataError> {
         self.masters()
     }
+    fn crc(&self) -> Option<u32> {
+        self.crc()
+    }
     fn override_record_count(&self) -> Result<usize, PluginDataError> {
         self.override_record_count()
     }
@@ -224,6 +497,16 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
                 }
             }
 
+            for (layer, file) in &plugin.layer_req {
+                if let Some(other_node_index) = self.node_index_by_name(file) {
+                    self.add_edge(
+                        other_node_index,
+                        node_index,
+                        EdgeType::LayerRequirement(Arc::clone(layer)),
+                    );
+                }
+            }
+
             for file in &plugin.user_req {
                 if let Some(other_node_index) = self.node_index_by_name(file) {
                     self.add_edge(other_node_index, node_index, EdgeType::UserRequirement);
@@ -236,6 +519,16 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
                 }
             }
 
+            for (layer, file) in &plugin.layer_load_after {
+                if let Some(other_node_index) = self.node_index_by_name(file) {
+                    self.add_edge(
+                        other_node_index,
+                        node_index,
+                        EdgeType::LayerLoadAfter(Arc::clone(layer)),
+                    );
+                }
+            }
+
             for file in &plugin.user_load_after {
                 if let Some(other_node_index) = self.node_index_by_name(file) {
                     self.add_edge(other_node_index, node_index, EdgeType::UserLoadAfter);
@@ -284,6 +577,143 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         }
     }
 
+    /// Adds edges that fix the positions of pinned plugins, after checking
+    /// that they don't contradict any Master or Hardcoded edges.
+    ///
+    /// `index_offset` is the load order index of the first plugin in the
+    /// graph's partition.
+    fn add_pin_edges(&mut self, pins: &[Pin], index_offset: usize) -> Result<(), PinError> {
+        logging::trace!("Adding edges for pinned plugins...");
+
+        for pin in pins {
+            let Some(node_index) = self.node_index_by_name(pin.plugin()) else {
+                continue;
+            };
+
+            let Some((before, after)) =
+                self.pinned_neighbours(node_index, pin.position(), index_offset)
+            else {
+                continue;
+            };
+
+            self.check_pin_conflicts(node_index, &before, &after)?;
+
+            for from_index in before {
+                self.add_edge(from_index, node_index, EdgeType::Pin);
+            }
+
+            for to_index in after {
+                self.add_edge(node_index, to_index, EdgeType::Pin);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the nodes that a pin requires to load before and after the
+    /// pinned node, or `None` if the pin doesn't apply to this graph.
+    fn pinned_neighbours(
+        &self,
+        node_index: NodeIndex,
+        position: &PinPosition,
+        index_offset: usize,
+    ) -> Option<(Vec<NodeIndex>, Vec<NodeIndex>)> {
+        let mut others: Vec<_> = self.node_indices().filter(|i| *i != node_index).collect();
+
+        match position {
+            PinPosition::Index(index) => {
+                // Pinned indices have already been validated, so this should
+                // never be out of range.
+                let split_index = index
+                    .checked_sub(index_offset)
+                    .filter(|i| *i <= others.len())?;
+
+                others.sort_by_key(|i| self[*i].load_order_index);
+                let after = others.split_off(split_index);
+
+                Some((others, after))
+            }
+            PinPosition::First => Some((Vec::new(), others)),
+            PinPosition::Last => Some((others, Vec::new())),
+            PinPosition::Before(other) => self
+                .node_index_by_name(other)
+                .map(|i| (Vec::new(), vec![i])),
+            PinPosition::After(other) => self
+                .node_index_by_name(other)
+                .map(|i| (vec![i], Vec::new())),
+        }
+    }
+
+    /// Checks that no Master or Hardcoded edges require any of the given
+    /// nodes to load on the other side of the pinned node.
+    fn check_pin_conflicts(
+        &self,
+        node_index: NodeIndex,
+        before: &[NodeIndex],
+        after: &[NodeIndex],
+    ) -> Result<(), PinError> {
+        let descendants = self.fixed_edge_predecessors(node_index, petgraph::Direction::Outgoing);
+        if let Some(mut path) = before
+            .iter()
+            .find_map(|i| trace_path(&descendants, *i, node_index))
+        {
+            path.reverse();
+            return Err(PinError::ConflictingEdges(self.to_pin_cycle(&path)));
+        }
+
+        let ancestors = self.fixed_edge_predecessors(node_index, petgraph::Direction::Incoming);
+        if let Some(path) = after
+            .iter()
+            .find_map(|i| trace_path(&ancestors, *i, node_index))
+        {
+            return Err(PinError::ConflictingEdges(self.to_pin_cycle(&path)));
+        }
+
+        Ok(())
+    }
+
+    /// Walks the graph from the given node in the given direction, following
+    /// only Master and Hardcoded edges, and returns a map of each node that
+    /// was reached to the node that it was reached from.
+    fn fixed_edge_predecessors(
+        &self,
+        node_index: NodeIndex,
+        direction: petgraph::Direction,
+    ) -> HashMap<NodeIndex, NodeIndex> {
+        let mut predecessors = HashMap::default();
+        let mut queue = VecDeque::from([node_index]);
+
+        while let Some(current) = queue.pop_front() {
+            for edge in self.inner.edges_directed(current, direction) {
+                if !matches!(edge.weight(), EdgeType::Master | EdgeType::Hardcoded) {
+                    continue;
+                }
+
+                let next = match direction {
+                    petgraph::Direction::Outgoing => edge.target(),
+                    petgraph::Direction::Incoming => edge.source(),
+                };
+
+                if next != node_index && !predecessors.contains_key(&next) {
+                    predecessors.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        predecessors
+    }
+
+    /// Converts a path that ends with a pinned node into a cycle that is
+    /// closed by a Pin edge.
+    fn to_pin_cycle(&self, path: &[NodeIndex]) -> Vec<Vertex> {
+        let mut cycle = self.to_vertex_path(path);
+        if let Some(last) = cycle.pop() {
+            cycle.push(last.with_out_edge_type(EdgeType::Pin));
+        }
+        cycle
+    }
+
     fn check_for_cycles(&mut self) -> Result<(), CyclicInteractionError> {
         if let Some(cycle) = find_cycle(&self.inner, |node| node.name().to_owned()) {
             Err(CyclicInteractionError::new(cycle))
@@ -292,7 +722,18 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         }
     }
 
-    fn add_group_edges(&mut self, groups_graph: &GroupsGraph) -> Result<(), UndefinedGroupError> {
+    fn find_all_cycles(&self) -> Vec<CyclicComponent> {
+        find_all_cycles(&self.inner, |node| node.name().to_owned())
+            .into_iter()
+            .map(|(plugins, cycle)| CyclicComponent::new(plugins, cycle))
+            .collect()
+    }
+
+    fn add_group_edges(
+        &mut self,
+        groups_graph: &GroupsGraph,
+        metadata_layers: &[Arc<str>],
+    ) -> Result<(), UndefinedGroupError> {
         logging::trace!("Adding edges based on plugin group memberships...");
 
         // First build a map from groups to the plugins in those groups.
@@ -320,6 +761,7 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
             let mut visitor = GroupsPathVisitor::new(
                 self,
                 groups_graph,
+                metadata_layers,
                 &plugins_in_groups,
                 &mut finished_nodes,
                 Some(default_group_node),
@@ -338,6 +780,7 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         let mut visitor = GroupsPathVisitor::new(
             self,
             groups_graph,
+            metadata_layers,
             &plugins_in_groups,
             &mut finished_nodes,
             None,
@@ -353,95 +796,58 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         Ok(())
     }
 
-    fn add_overlap_edges(&mut self) -> Result<(), SortingError> {
+    fn add_overlap_edges(
+        &mut self,
+        options: &SortOptions,
+        mut overlap_cache: Option<&mut OverlapCache>,
+    ) -> Result<(), SortingError> {
         logging::trace!("Adding edges for overlapping plugins...");
 
-        let mut node_index_iter = self.node_indices();
-        while let Some(node_index) = node_index_iter.next() {
-            let plugin = Rc::clone(&self[node_index]);
-            let plugin_asset_count = plugin.asset_count();
+        // Move the cache behind a lock while the plugins are compared so that
+        // results can be cached as soon as they're calculated, then move it
+        // back even if comparing failed.
+        let shared_cache = overlap_cache
+            .as_deref_mut()
+            .map(|c| RwLock::new(std::mem::take(c)));
 
-            if plugin.override_record_count == 0 && plugin_asset_count == 0 {
-                logging::debug!(
-                    "Skipping vertex for \"{}\": the plugin contains no override records and loads no assets",
-                    plugin.name()
-                );
-                continue;
-            }
+        let comparisons = self.compare_plugins(options, shared_cache.as_ref());
 
-            // This loop should have no effect now that master-flagged and
-            // non-master-flagged plugins are sorted separately, but is kept
-            // as a safety net.
-            for other_node_index in node_index_iter.clone() {
-                let other_plugin = &self[other_node_index];
+        if let (Some(cache), Some(shared_cache)) = (overlap_cache, shared_cache) {
+            *cache = shared_cache
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner);
+        }
 
-                // Don't add an edge between these two plugins if one already
-                // exists (only check direct edges and not paths for efficiency).
-                if self.inner.contains_edge(node_index, other_node_index)
-                    || self.inner.contains_edge(other_node_index, node_index)
-                {
-                    continue;
-                }
+        let comparisons = comparisons?;
 
-                // Two plugins can overlap due to overriding the same records,
-                // or by loading assets from BSAs/BA2s that have the same path.
-                // If records overlap, the plugin that overrides more records
-                // should load earlier.
-                // If assets overlap, the plugin that loads more assets should
-                // load earlier.
-                // If two plugins have overlapping records and assets and one
-                // overrides more records but loads fewer assets than the other,
-                // the fact it overrides more records should take precedence
-                // (records are more significant than assets).
-                // I.e. if two plugins don't have overlapping records, check their
-                // assets, otherwise only check their assets if their override
-                // record counts are equal.
-
-                let outer_plugin_loads_first;
-                let edge_type;
-
-                if plugin.override_record_count == other_plugin.override_record_count
-                    || !plugin.do_records_overlap(other_plugin)?
-                {
-                    // Records don't overlap, or override the same number of records,
-                    // check assets.
-                    // No records overlap, check assets.
-                    let other_plugin_asset_count = other_plugin.asset_count();
-                    if plugin_asset_count == other_plugin_asset_count
-                        || !plugin.do_assets_overlap(other_plugin)
-                    {
-                        // Assets don't overlap or both plugins load the same number of
-                        // assets, don't add an edge.
-                        continue;
-                    }
+        let total = comparisons.len();
+        let mut done = 0;
+        options.report_progress(SortPhase::AddingOverlapEdges, done, total)?;
 
-                    outer_plugin_loads_first = plugin_asset_count > other_plugin_asset_count;
-                    edge_type = EdgeType::AssetOverlap;
-                } else {
-                    // Records overlap and override different numbers of records.
-                    // Load this plugin first if it overrides more records.
-                    outer_plugin_loads_first =
-                        plugin.override_record_count > other_plugin.override_record_count;
-                    edge_type = EdgeType::RecordOverlap;
-                }
+        for comparison in comparisons {
+            let overlap_edge = comparison.edge;
 
-                let (from_index, to_index) = if outer_plugin_loads_first {
-                    (node_index, other_node_index)
-                } else {
-                    (other_node_index, node_index)
-                };
+            done += 1;
+            options.report_progress(SortPhase::AddingOverlapEdges, done, total)?;
 
-                if !self.is_path_cached(from_index, to_index) {
-                    if self.path_exists(to_index, from_index) {
-                        logging::debug!(
-                            "Skipping {} edge from \"{}\" to \"{}\" as it would create a cycle.",
-                            edge_type,
-                            self[from_index].name(),
-                            self[to_index].name()
-                        );
-                    } else {
-                        self.add_edge(from_index, to_index, edge_type);
-                    }
+            let edge_type = EdgeType::from(overlap_edge.overlap_type());
+
+            let (from_index, to_index) = if overlap_edge.plugin_loads_first() {
+                (comparison.node_index, comparison.other_node_index)
+            } else {
+                (comparison.other_node_index, comparison.node_index)
+            };
+
+            if !self.is_path_cached(from_index, to_index) {
+                if self.path_exists(to_index, from_index) {
+                    logging::debug!(
+                        "Skipping {} edge from \"{}\" to \"{}\" as it would create a cycle.",
+                        edge_type,
+                        self[from_index].name(),
+                        self[to_index].name()
+                    );
+                } else {
+                    self.add_edge(from_index, to_index, edge_type);
                 }
             }
         }
@@ -449,7 +855,82 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         Ok(())
     }
 
-    fn add_tie_break_edges(&mut self) -> Result<(), PathfindingError> {
+    /// Use the given policy to compare each pair of plugins that could have an
+    /// overlap edge added between them, returning only the comparisons that
+    /// give an edge. Any overlap results that are calculated are added to the
+    /// given cache as they're calculated.
+    ///
+    /// Checking if plugins overlap is relatively slow, and the result only
+    /// depends on the plugins' data, so the pairs are compared in parallel.
+    fn compare_plugins(
+        &self,
+        options: &SortOptions,
+        overlap_cache: Option<&SharedOverlapCache>,
+    ) -> Result<Vec<PluginsComparison>, SortingError> {
+        let plugins: Vec<_> = self
+            .node_indices()
+            .map(|node_index| (node_index, self[node_index].as_ref()))
+            .collect();
+
+        // Don't add an edge between two plugins if one already exists (only
+        // check direct edges and not paths for efficiency). Overlap edges are
+        // only added between the plugins in each pair, so this can be checked
+        // before any are added.
+        let linked_pairs: HashSet<_> = self
+            .inner
+            .edge_references()
+            .flat_map(|e| [(e.source(), e.target()), (e.target(), e.source())])
+            .collect();
+
+        // Compare the pairs grouped by their first plugin so that progress can
+        // be reported for each plugin instead of for each pair.
+        let first_plugin_positions: Vec<_> = plugins
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, plugin))| {
+                let is_empty = plugin.override_record_count == 0 && plugin.asset_count() == 0;
+                if is_empty {
+                    logging::debug!(
+                        "Skipping vertex for \"{}\": the plugin contains no override records and loads no assets",
+                        plugin.name()
+                    );
+                }
+                !is_empty
+            })
+            .map(|(position, _)| position)
+            .collect();
+
+        let total = first_plugin_positions.len();
+        let done = AtomicUsize::new(0);
+        options.report_progress(SortPhase::CheckingOverlap, 0, total)?;
+
+        let comparisons = first_plugin_positions
+            .into_par_iter()
+            .map(|position| {
+                let comparisons = compare_plugin_with_later_plugins(
+                    &plugins,
+                    position,
+                    &linked_pairs,
+                    options,
+                    overlap_cache,
+                )?;
+
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                options.report_progress(SortPhase::CheckingOverlap, done, total)?;
+
+                Ok(comparisons)
+            })
+            .collect::<Result<Vec<_>, SortingError>>()?;
+
+        Ok(comparisons.into_iter().flatten().collect())
+    }
+
+    /// Returns the paths that forced plugins to load before plugins that they
+    /// previously loaded after.
+    fn add_tie_break_edges(
+        &mut self,
+        options: &SortOptions,
+    ) -> Result<Vec<Vec<NodeIndex>>, SortingError> {
         logging::trace!("Adding edges to break ties between plugins...");
 
         // In order for the sort to be performed stably, there must be only one
@@ -498,11 +979,19 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         // Holds nodes that have already been put into new_load_order.
         let mut processed_nodes = HashSet::default();
 
+        // Holds the paths that caused the load order to change.
+        let mut forced_paths = Vec::new();
+
         // First get the graph vertices and sort them into the current load order.
         let mut nodes: Vec<_> = self.node_indices().collect();
         nodes.sort_by_key(|a| self[*a].load_order_index);
 
-        for window in nodes.windows(2) {
+        let total = nodes.len();
+        options.report_progress(SortPhase::AddingTieBreakEdges, 0, total)?;
+
+        for (i, window) in nodes.windows(2).enumerate() {
+            options.report_progress(SortPhase::AddingTieBreakEdges, i + 1, total)?;
+
             let [current, next] = *window else {
                 // This should never happen.
                 logging::error!("Unexpectedly encountered a window length that was not 2");
@@ -548,6 +1037,8 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
                     }
                 }
                 Some(mut path_from_next_node) => {
+                    forced_paths.push(path_from_next_node.clone());
+
                     // Each vertex in pathFromNextVertex (besides the last, which is
                     // currentVertex) needs to be positioned relative to a vertex that has
                     // already been iterated over (i.e. in what begins as the old load
@@ -605,7 +1096,9 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
             }
         }
 
-        Ok(())
+        options.report_progress(SortPhase::AddingTieBreakEdges, total, total)?;
+
+        Ok(forced_paths)
     }
 
     fn pin_node_position(
@@ -704,6 +1197,81 @@ impl<'a, T: SortingPlugin> PluginsGraph<'a, T> {
         })
     }
 
+    /// Checks that the given sorted path is the only possible sorted path,
+    /// erroring if it isn't and the given options require a unique order.
+    fn check_order_is_unique(
+        &mut self,
+        sorted_nodes: &[NodeIndex],
+        options: &SortOptions,
+    ) -> Result<(), SortingError> {
+        if let Some((first, second)) = self.check_path_is_hamiltonian(sorted_nodes) {
+            logging::error!(
+                "The path is not unique. No edge exists between {} and {}",
+                self[first].name(),
+                self[second].name()
+            );
+
+            if options.require_unique_order() {
+                return Err(SortingError::NonUniqueOrder(
+                    self[first].name().to_owned(),
+                    self[second].name().to_owned(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the type of the highest-priority edge going from one node to
+    /// another, if any such edge exists.
+    fn strongest_edge_type(&self, from: NodeIndex, to: NodeIndex) -> Option<EdgeType> {
+        self.inner
+            .edges_connecting(from, to)
+            .map(|e| e.weight())
+            .min()
+            .cloned()
+    }
+
+    fn to_vertex_path(&self, path: &[NodeIndex]) -> Vec<Vertex> {
+        let mut vertices: Vec<_> = path
+            .windows(2)
+            .filter_map(|slice| match *slice {
+                [a, b] => {
+                    let vertex = Vertex::new(self[a].name().to_owned());
+                    match self.strongest_edge_type(a, b) {
+                        Some(edge_type) => Some(vertex.with_out_edge_type(edge_type)),
+                        None => Some(vertex),
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+
+        if let Some(last) = path.last() {
+            vertices.push(Vertex::new(self[*last].name().to_owned()));
+        }
+
+        vertices
+    }
+
+    fn export_to(&self, sorting_graph: &mut SortingGraph) {
+        for node_index in self.node_indices() {
+            let node = &self[node_index];
+            sorting_graph.add_node(SortingGraphNode::new(
+                node.name().to_owned(),
+                node.group.to_string(),
+            ));
+        }
+
+        for edge in self.inner.edge_references() {
+            sorting_graph.add_edge(SortingGraphEdge::new(
+                self[edge.source()].name().to_owned(),
+                self[edge.target()].name().to_owned(),
+                edge.weight().clone(),
+            ));
+        }
+    }
+
     fn cache_path(&mut self, from: NodeIndex, to: NodeIndex) {
         self.paths_cache.entry(from).or_default().insert(to);
     }
@@ -761,16 +1329,102 @@ impl<'a, T: SortingPlugin> std::ops::Index<NodeIndex> for PluginsGraph<'a, T> {
 }
 
 pub fn sort_plugins<T: SortingPlugin>(
-    mut plugins_sorting_data: Vec<PluginSortingData<T>>,
+    plugins_sorting_data: Vec<PluginSortingData<T>>,
     groups_graph: &GroupsGraph,
+    metadata_layers: &[Arc<str>],
     early_loading_plugins: &[String],
-) -> Result<Vec<String>, SortingError> {
+    options: &SortOptions,
+    mut overlap_cache: Option<&mut OverlapCache>,
+) -> Result<SortReport, SortingError> {
     if plugins_sorting_data.is_empty() {
-        return Ok(Vec::new());
+        return Ok(SortReport::default());
     }
 
     validate_plugin_groups(&plugins_sorting_data, groups_graph)?;
 
+    if let Some(cache) = overlap_cache.as_deref_mut() {
+        cache.retain_plugins(&plugins_sorting_data);
+    }
+
+    let (masters, non_masters, blueprint_masters) = partition_plugins(plugins_sorting_data);
+
+    validate_specific_and_hardcoded_edges(
+        &masters,
+        &blueprint_masters,
+        &non_masters,
+        early_loading_plugins,
+    )?;
+
+    validate_pins(options.pins(), [&masters, &non_masters, &blueprint_masters])?;
+
+    let ignored_edges = find_ignored_edges(&masters, &non_masters, &blueprint_masters)?;
+
+    // Blueprint masters load after non-masters.
+    let non_masters_offset = masters.len();
+    let blueprint_masters_offset = masters.len() + non_masters.len();
+
+    let mut masters_graph = build_partition_graph(masters, early_loading_plugins)?;
+    let mut blueprint_masters_graph =
+        build_partition_graph(blueprint_masters, early_loading_plugins)?;
+    let mut non_masters_graph = build_partition_graph(non_masters, early_loading_plugins)?;
+
+    masters_graph.add_pin_edges(options.pins(), 0)?;
+    blueprint_masters_graph.add_pin_edges(options.pins(), blueprint_masters_offset)?;
+    non_masters_graph.add_pin_edges(options.pins(), non_masters_offset)?;
+
+    if options.report_all_cycles() {
+        let components: Vec<_> = [&masters_graph, &blueprint_masters_graph, &non_masters_graph]
+            .into_iter()
+            .flat_map(PluginsGraph::find_all_cycles)
+            .collect();
+
+        if !components.is_empty() {
+            return Err(SortingError::CyclesFound(components));
+        }
+    }
+
+    let mut report = sort_plugins_partition(
+        masters_graph,
+        groups_graph,
+        metadata_layers,
+        options,
+        overlap_cache.as_deref_mut(),
+    )?;
+
+    let blueprint_masters_report = sort_plugins_partition(
+        blueprint_masters_graph,
+        groups_graph,
+        metadata_layers,
+        options,
+        overlap_cache.as_deref_mut(),
+    )?;
+
+    let non_masters_report = sort_plugins_partition(
+        non_masters_graph,
+        groups_graph,
+        metadata_layers,
+        options,
+        overlap_cache,
+    )?;
+
+    report.append(non_masters_report, EdgeType::MasterFlag);
+    report.append(blueprint_masters_report, EdgeType::BlueprintMaster);
+    report.set_ignored_edges(ignored_edges);
+
+    Ok(report)
+}
+
+type PluginPartitions<'a, T> = (
+    Vec<PluginSortingData<'a, T>>,
+    Vec<PluginSortingData<'a, T>>,
+    Vec<PluginSortingData<'a, T>>,
+);
+
+/// Sorts the given plugins by name and splits them into masters, non-masters
+/// and blueprint masters.
+fn partition_plugins<T: SortingPlugin>(
+    mut plugins_sorting_data: Vec<PluginSortingData<T>>,
+) -> PluginPartitions<T> {
     // Sort the plugins according to the lexicographical order of their names.
     // This ensures a consistent iteration order for vertices given the same
     // input data. The vertex iteration order can affect what edges get added
@@ -797,33 +1451,50 @@ pub fn sort_plugins<T: SortingPlugin>(
     let (masters, blueprint_masters): (Vec<_>, Vec<_>) =
         masters.into_iter().partition(|p| !p.is_blueprint_master());
 
-    validate_specific_and_hardcoded_edges(
-        &masters,
-        &blueprint_masters,
-        &non_masters,
-        early_loading_plugins,
-    )?;
+    (masters, non_masters, blueprint_masters)
+}
+
+pub fn build_plugins_graph<T: SortingPlugin>(
+    plugins_sorting_data: Vec<PluginSortingData<T>>,
+    groups_graph: &GroupsGraph,
+    metadata_layers: &[Arc<str>],
+    early_loading_plugins: &[String],
+) -> Result<SortingGraph, SortingError> {
+    let mut sorting_graph = SortingGraph::default();
+
+    if plugins_sorting_data.is_empty() {
+        return Ok(sorting_graph);
+    }
+
+    validate_plugin_groups(&plugins_sorting_data, groups_graph)?;
 
-    let mut masters_load_order =
-        sort_plugins_partition(masters, groups_graph, early_loading_plugins)?;
+    let (masters, non_masters, blueprint_masters) = partition_plugins(plugins_sorting_data);
 
-    let blueprint_masters_load_order =
-        sort_plugins_partition(blueprint_masters, groups_graph, early_loading_plugins)?;
+    for partition in [masters, non_masters, blueprint_masters] {
+        let mut graph = build_partition_graph(partition, early_loading_plugins)?;
 
-    let non_masters_load_order =
-        sort_plugins_partition(non_masters, groups_graph, early_loading_plugins)?;
+        // A cyclic graph can't be sorted, but it's still useful to see it, so
+        // don't error. Edges are only added from this point on if they don't
+        // cause cycles, so skip adding them as they wouldn't be added when
+        // sorting.
+        if graph.check_for_cycles().is_ok() {
+            graph.add_group_edges(groups_graph, metadata_layers)?;
+            graph.add_overlap_edges(&SortOptions::default(), None)?;
+            graph.add_tie_break_edges(&SortOptions::default())?;
+        }
 
-    masters_load_order.extend(non_masters_load_order);
-    masters_load_order.extend(blueprint_masters_load_order);
+        graph.export_to(&mut sorting_graph);
+    }
 
-    Ok(masters_load_order)
+    Ok(sorting_graph)
 }
 
-fn sort_plugins_partition<T: SortingPlugin>(
-    plugins_sorting_data: Vec<PluginSortingData<T>>,
-    groups_graph: &GroupsGraph,
+/// Builds a graph of the given plugins with edges from their data and
+/// non-group metadata.
+fn build_partition_graph<'a, T: SortingPlugin>(
+    plugins_sorting_data: Vec<PluginSortingData<'a, T>>,
     early_loading_plugins: &[String],
-) -> Result<Vec<String>, SortingError> {
+) -> Result<PluginsGraph<'a, T>, SortingError> {
     let mut graph = PluginsGraph::new();
 
     for plugin in plugins_sorting_data {
@@ -833,15 +1504,28 @@ fn sort_plugins_partition<T: SortingPlugin>(
     graph.add_specific_edges()?;
     graph.add_early_loading_plugin_edges(early_loading_plugins);
 
+    Ok(graph)
+}
+
+fn sort_plugins_partition<T: SortingPlugin>(
+    mut graph: PluginsGraph<T>,
+    groups_graph: &GroupsGraph,
+    metadata_layers: &[Arc<str>],
+    options: &SortOptions,
+    overlap_cache: Option<&mut OverlapCache>,
+) -> Result<SortReport, SortingError> {
     // Check for cycles now because from this point on edges are only added if
     // they don't cause cycles, and adding overlap and tie-break edges is
     // relatively slow, so checking now provides quicker feedback if there is an
     // issue.
     graph.check_for_cycles()?;
+    options.check_cancelled()?;
 
-    graph.add_group_edges(groups_graph)?;
-    graph.add_overlap_edges()?;
-    graph.add_tie_break_edges()?;
+    graph.add_group_edges(groups_graph, metadata_layers)?;
+    options.check_cancelled()?;
+
+    graph.add_overlap_edges(options, overlap_cache)?;
+    let forced_paths = graph.add_tie_break_edges(options)?;
 
     // Check for cycles again, just in case there's a bug that lets some occur.
     // The check doesn't take a significant amount of time.
@@ -849,20 +1533,101 @@ fn sort_plugins_partition<T: SortingPlugin>(
 
     let sorted_nodes = graph.topological_sort()?;
 
-    if let Some((first, second)) = graph.check_path_is_hamiltonian(&sorted_nodes) {
-        logging::error!(
-            "The path is not unique. No edge exists between {} and {}",
-            graph[first].name(),
-            graph[second].name()
-        );
-    }
+    graph.check_order_is_unique(&sorted_nodes, options)?;
+
+    let adjacent_plugin_paths = sorted_nodes
+        .windows(2)
+        .map(|slice| graph.to_vertex_path(slice))
+        .collect();
+
+    let moved_plugins = get_moved_plugins(&graph, &sorted_nodes, &forced_paths);
 
     let sorted_plugin_names = sorted_nodes
         .into_iter()
         .map(|i| graph[i].name().to_owned())
         .collect();
 
-    Ok(sorted_plugin_names)
+    Ok(SortReport::new(
+        sorted_plugin_names,
+        adjacent_plugin_paths,
+        moved_plugins,
+    ))
+}
+
+fn get_moved_plugins<T: SortingPlugin>(
+    graph: &PluginsGraph<T>,
+    sorted_nodes: &[NodeIndex],
+    forced_paths: &[Vec<NodeIndex>],
+) -> Vec<MovedPlugin> {
+    let mut moved_nodes = HashSet::default();
+    let mut moved_plugins = Vec::new();
+
+    for path in forced_paths {
+        // The path goes from a plugin to the plugin that previously loaded
+        // immediately before it, so every plugin in the path that previously
+        // loaded after the last plugin in the path has been moved earlier, and
+        // every plugin in the path that previously loaded before the first
+        // plugin in the path has been moved later.
+        let (Some(first_node), Some(last_node)) = (path.first(), path.last()) else {
+            continue;
+        };
+        let first_old_index = graph[*first_node].load_order_index;
+        let last_old_index = graph[*last_node].load_order_index;
+
+        for (i, node) in path.iter().enumerate() {
+            let old_index = graph[*node].load_order_index;
+            let (direction, node_path) = if old_index > last_old_index {
+                (MoveDirection::Earlier, path.get(i..))
+            } else if old_index < first_old_index {
+                (MoveDirection::Later, path.get(..=i))
+            } else {
+                continue;
+            };
+
+            if !moved_nodes.insert((*node, direction)) {
+                continue;
+            }
+
+            let Some(new_index) = sorted_nodes.iter().position(|n| n == node) else {
+                continue;
+            };
+
+            let vertex_path = node_path
+                .map(|p| graph.to_vertex_path(p))
+                .unwrap_or_default();
+
+            moved_plugins.push(MovedPlugin::new(
+                graph[*node].name().to_owned(),
+                old_index,
+                new_index,
+                direction,
+                vertex_path,
+            ));
+        }
+    }
+
+    moved_plugins.sort_by_key(MovedPlugin::new_index);
+
+    moved_plugins
+}
+
+/// Follows a map of nodes to the nodes they were reached from, starting at
+/// `start` and ending at `end`.
+fn trace_path(
+    predecessors: &HashMap<NodeIndex, NodeIndex>,
+    start: NodeIndex,
+    end: NodeIndex,
+) -> Option<Vec<NodeIndex>> {
+    let mut current = *predecessors.get(&start)?;
+    let mut path = vec![start];
+
+    while current != end {
+        path.push(current);
+        current = *predecessors.get(&current)?;
+    }
+    path.push(end);
+
+    Some(path)
 }
 
 fn path_to_string<T: SortingPlugin>(graph: &InnerPluginsGraph<T>, path: &[NodeIndex]) -> String {
@@ -1046,6 +1811,7 @@ type GroupNodeIndex = NodeIndex;
 struct GroupsPathVisitor<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> {
     plugins_graph: &'a mut PluginsGraph<'b, T>,
     groups_graph: &'e GroupsGraph,
+    metadata_layers: &'e [Arc<str>],
     groups_plugins: &'c HashMap<Box<str>, Vec<PluginNodeIndex>>,
     finished_group_vertices: &'d mut HashSet<GroupNodeIndex>,
     group_node_to_ignore_as_source: Option<GroupNodeIndex>,
@@ -1057,6 +1823,7 @@ impl<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> GroupsPathVisitor<'a, 'b, 'c, 'd, 'e,
     fn new(
         plugins_graph: &'a mut PluginsGraph<'b, T>,
         groups_graph: &'e GroupsGraph,
+        metadata_layers: &'e [Arc<str>],
         groups_plugins: &'c HashMap<Box<str>, Vec<PluginNodeIndex>>,
         finished_group_vertices: &'d mut HashSet<GroupNodeIndex>,
         group_node_to_ignore_as_source: Option<GroupNodeIndex>,
@@ -1064,6 +1831,7 @@ impl<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> GroupsPathVisitor<'a, 'b, 'c, 'd, 'e,
         Self {
             plugins_graph,
             groups_graph,
+            metadata_layers,
             groups_plugins,
             finished_group_vertices,
             group_node_to_ignore_as_source,
@@ -1110,12 +1878,14 @@ impl<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> GroupsPathVisitor<'a, 'b, 'c, 'd, 'e,
             return;
         };
 
-        let path_involves_user_metadata = std::iter::once(from_edge)
+        let path_source = std::iter::once(from_edge)
             .chain(edges.iter())
-            .any(|p| *p.0.weight() == EdgeType::UserLoadAfter);
+            .map(|p| GroupSource::from_load_after_edge(p.0.weight()))
+            .max_by_key(|s| s.precedence(self.metadata_layers))
+            .unwrap_or_default();
 
         for from_plugin in from_edge.1 {
-            self.add_edges_from_plugin(*from_plugin, target_plugins, path_involves_user_metadata);
+            self.add_edges_from_plugin(*from_plugin, target_plugins, &path_source);
         }
     }
 
@@ -1123,7 +1893,7 @@ impl<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> GroupsPathVisitor<'a, 'b, 'c, 'd, 'e,
         &mut self,
         from_plugin: PluginNodeIndex,
         to_plugins: &[PluginNodeIndex],
-        path_involves_user_metadata: bool,
+        path_source: &GroupSource,
     ) {
         if to_plugins.is_empty() {
             return;
@@ -1131,15 +1901,15 @@ impl<'a, 'b, 'c, 'd, 'e, T: SortingPlugin> GroupsPathVisitor<'a, 'b, 'c, 'd, 'e,
 
         for to_plugin in to_plugins {
             if !self.plugins_graph.is_path_cached(from_plugin, *to_plugin) {
-                let involves_user_metadata = path_involves_user_metadata
-                    || self.plugins_graph[from_plugin].group_is_user_metadata
-                    || self.plugins_graph[*to_plugin].group_is_user_metadata;
-
-                let edge_type = if involves_user_metadata {
-                    EdgeType::UserGroup
-                } else {
-                    EdgeType::MasterlistGroup
-                };
+                let edge_type = [
+                    path_source,
+                    &self.plugins_graph[from_plugin].group_source,
+                    &self.plugins_graph[*to_plugin].group_source,
+                ]
+                .into_iter()
+                .max_by_key(|s| s.precedence(self.metadata_layers))
+                .unwrap_or(path_source)
+                .group_edge_type();
 
                 if self.plugins_graph.path_exists(*to_plugin, from_plugin) {
                     logging::debug!(
@@ -1243,7 +2013,10 @@ mod tests {
     #![allow(clippy::many_single_char_names)]
     use super::*;
 
-    use crate::sorting::{groups::build_groups_graph, test::TestPlugin};
+    use crate::sorting::{
+        groups::{build_groups_graph, build_layered_groups_graph},
+        test::TestPlugin,
+    };
 
     const PLUGIN_A: &str = "A.esp";
     const PLUGIN_B: &str = "B.esp";
@@ -1341,6 +2114,36 @@ mod tests {
             let plugin = PluginSortingData::new(&blueprint_master, None, None, 0).unwrap();
             assert!(plugin.is_blueprint_master());
         }
+
+        #[test]
+        fn with_layer_metadata_should_use_the_highest_layer_group_if_there_is_no_user_group() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A]);
+
+            let mut layer_0 = PluginMetadata::new(PLUGIN_A).unwrap();
+            layer_0.set_group("B".into());
+            layer_0.set_load_after_files(vec![File::new("B.esp".into())]);
+            let mut layer_1 = PluginMetadata::new(PLUGIN_A).unwrap();
+            layer_1.set_group("C".into());
+            layer_1.set_requirements(vec![File::new("C.esp".into())]);
+            let layers = [("modpack".into(), layer_0), ("local".into(), layer_1)];
+
+            let plugin = fixture
+                .group_sorting_data(PLUGIN_A, "A")
+                .with_layer_metadata(&layers);
+
+            assert_eq!("C", plugin.group.as_ref());
+            assert_eq!(
+                [("modpack".into(), "B.esp".to_owned())],
+                *plugin.layer_load_after
+            );
+            assert_eq!([("local".into(), "C.esp".to_owned())], *plugin.layer_req);
+
+            let plugin = fixture
+                .user_group_sorting_data(PLUGIN_A, "A")
+                .with_layer_metadata(&layers);
+
+            assert_eq!("A", plugin.group.as_ref());
+        }
     }
 
     mod plugins_graph {
@@ -1357,10 +2160,11 @@ mod tests {
             from: NodeIndex,
             to: NodeIndex,
         ) -> EdgeType {
-            *graph
+            graph
                 .inner
                 .edge_weight(graph.inner.find_edge(from, to).unwrap())
                 .unwrap()
+                .clone()
         }
 
         mod check_for_cycles {
@@ -1434,6 +2238,61 @@ mod tests {
             assert!(sorted.is_empty());
         }
 
+        mod check_order_is_unique {
+            use super::*;
+
+            #[test]
+            fn should_error_if_a_unique_order_is_required_and_adjacent_plugins_are_not_linked() {
+                let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+                let mut graph = PluginsGraph::new();
+                let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
+                let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
+
+                let options = SortOptions::new().with_require_unique_order(true);
+
+                match graph.check_order_is_unique(&[a, b], &options) {
+                    Err(SortingError::NonUniqueOrder(first, second)) => {
+                        assert_eq!(PLUGIN_A, first);
+                        assert_eq!(PLUGIN_B, second);
+                    }
+                    r => panic!("Expected a non-unique order error, got {r:?}"),
+                }
+            }
+
+            #[test]
+            fn should_succeed_if_a_unique_order_is_not_required() {
+                let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+                let mut graph = PluginsGraph::new();
+                let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
+                let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
+
+                assert!(
+                    graph
+                        .check_order_is_unique(&[a, b], &SortOptions::default())
+                        .is_ok()
+                );
+            }
+
+            #[test]
+            fn should_succeed_if_all_adjacent_plugins_are_linked() {
+                let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+                let mut graph = PluginsGraph::new();
+                let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
+                let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
+                let c = graph.add_node(fixture.sorting_data(PLUGIN_C));
+
+                graph.add_edge(a, b, EdgeType::Master);
+                graph.add_edge(b, c, EdgeType::TieBreak);
+
+                let options = SortOptions::new().with_require_unique_order(true);
+
+                assert!(graph.check_order_is_unique(&[a, b, c], &options).is_ok());
+            }
+        }
+
         mod add_early_loading_plugin_edges {
             use super::*;
 
@@ -1528,7 +2387,7 @@ mod tests {
                 let a = graph.add_node(fixture.user_group_sorting_data(PLUGIN_A, "A"));
                 let b = graph.add_node(fixture.group_sorting_data(PLUGIN_B, "B"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::UserGroup, edge_type(&graph, a, b));
 
@@ -1543,7 +2402,7 @@ mod tests {
                 let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
                 let b = graph.add_node(fixture.user_group_sorting_data(PLUGIN_B, "B"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::UserGroup, edge_type(&graph, a, b));
 
@@ -1558,7 +2417,7 @@ mod tests {
                 let b = graph.add_node(fixture.group_sorting_data(PLUGIN_B, "B"));
                 let d = graph.add_node(fixture.sorting_data(PLUGIN_D));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::UserGroup, edge_type(&graph, b, d));
 
@@ -1573,7 +2432,7 @@ mod tests {
                 let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::UserGroup, edge_type(&graph, a, c));
 
@@ -1588,7 +2447,7 @@ mod tests {
                 let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
                 let d = graph.add_node(fixture.sorting_data(PLUGIN_D));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::UserGroup, edge_type(&graph, a, d));
 
@@ -1603,13 +2462,74 @@ mod tests {
                 let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
                 let b = graph.add_node(fixture.group_sorting_data(PLUGIN_B, "B"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert_eq!(EdgeType::MasterlistGroup, edge_type(&graph, a, b));
 
                 assert!(graph.check_for_cycles().is_ok());
             }
 
+            #[test]
+            fn should_add_layer_group_edge_if_a_plugin_is_in_group_due_to_layer_metadata() {
+                let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+                let mut metadata = PluginMetadata::new(PLUGIN_B).unwrap();
+                metadata.set_group("B".into());
+
+                let mut graph = PluginsGraph::<TestPlugin>::new();
+                let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
+                let b = graph.add_node(
+                    fixture
+                        .sorting_data(PLUGIN_B)
+                        .with_layer_metadata(&[("modpack".into(), metadata)]),
+                );
+
+                graph
+                    .add_group_edges(&fixture.groups_graph, &["modpack".into()])
+                    .unwrap();
+
+                assert_eq!(
+                    EdgeType::LayerGroup("modpack".into()),
+                    edge_type(&graph, a, b)
+                );
+
+                assert!(graph.check_for_cycles().is_ok());
+            }
+
+            #[test]
+            fn should_use_the_highest_precedence_layer_involved_for_a_layer_group_edge() {
+                let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+                let masterlist = &[Group::new("A".into()), Group::new("default".into())];
+                let layer = &[Group::new("B".into()).with_after_groups(vec!["A".into()])];
+                let groups_graph =
+                    build_layered_groups_graph(masterlist, &[("modpack".into(), layer)], &[])
+                        .unwrap();
+
+                let mut metadata = PluginMetadata::new(PLUGIN_B).unwrap();
+                metadata.set_group("B".into());
+
+                for (layers, expected) in [
+                    (["modpack".into(), "local".into()], "local"),
+                    (["local".into(), "modpack".into()], "modpack"),
+                ] {
+                    let mut graph = PluginsGraph::<TestPlugin>::new();
+                    let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
+                    let b = graph.add_node(
+                        fixture
+                            .sorting_data(PLUGIN_B)
+                            .with_layer_metadata(&[("local".into(), metadata.clone())]),
+                    );
+
+                    graph.add_group_edges(&groups_graph, &layers).unwrap();
+
+                    assert_eq!(
+                        EdgeType::LayerGroup(expected.into()),
+                        edge_type(&graph, a, b)
+                    );
+                }
+            }
+
             #[test]
             fn should_add_edges_between_plugins_in_indirectly_connected_groups_when_an_intermediate_plugin_edge_is_skipped()
              {
@@ -1627,7 +2547,7 @@ mod tests {
 
                 graph.add_edge(b1, a1, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A2.esp -> B1.esp -> A1.esp -> B2.esp -> C1.esp
                 //                                                -> C2.esp
@@ -1655,7 +2575,7 @@ mod tests {
                 let a = graph.add_node(fixture.group_sorting_data(PLUGIN_A, "A"));
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> C.esp
                 assert!(graph.inner.contains_edge(a, c));
@@ -1672,7 +2592,7 @@ mod tests {
                 let d = graph.add_node(fixture.sorting_data(PLUGIN_D));
                 let e = graph.add_node(fixture.group_sorting_data(PLUGIN_E, "E"));
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> D.esp -> E.esp
                 //                 ---------->
@@ -1693,7 +2613,7 @@ mod tests {
 
                 graph.add_edge(c, a, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert!(graph.inner.contains_edge(c, a));
                 assert!(!graph.inner.contains_edge(a, c));
@@ -1712,7 +2632,7 @@ mod tests {
 
                 graph.add_edge(c, a, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 assert!(graph.inner.contains_edge(c, a));
                 assert!(graph.inner.contains_edge(a, b));
@@ -1731,7 +2651,7 @@ mod tests {
 
                 graph.add_edge(c1, a, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be C1.esp -> A.esp -> C2.esp
                 assert!(graph.inner.contains_edge(c1, a));
@@ -1759,7 +2679,7 @@ mod tests {
                 graph.add_edge(c, d2, EdgeType::Master);
                 graph.add_edge(c, d3, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be: C.esp -> D2.esp -> B.esp -> D3.esp
                 //                  -> D1.esp ->
@@ -1794,7 +2714,7 @@ mod tests {
                 graph.add_edge(b1, a1, EdgeType::Master);
                 graph.add_edge(c1, b2, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A2.esp -> B1.esp -> A1.esp -> C1.esp -> B2.esp -> C2.esp
                 assert!(graph.inner.contains_edge(b1, a1));
@@ -1829,7 +2749,7 @@ mod tests {
                 graph.add_edge(b1, a1, EdgeType::Master);
                 graph.add_edge(c1, b1, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A2.esp -> C1.esp -> B1.esp -> A1.esp -> B2.esp -> C2.esp
                 assert!(graph.inner.contains_edge(b1, a1));
@@ -1859,7 +2779,7 @@ mod tests {
                 graph.add_edge(c, b1, EdgeType::Master);
                 graph.add_edge(c, b2, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> C1.esp -> B1.esp
                 //                           -> B2.esp
@@ -1897,7 +2817,7 @@ mod tests {
                 graph.add_edge(d1, c1, EdgeType::Master);
                 graph.add_edge(d2, c1, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be:
                 // A2.esp -> D1.esp -> C1.esp -> B1.esp -> A1.esp -> B2.esp -> C2.esp
@@ -1932,7 +2852,7 @@ mod tests {
 
                 graph.add_edge(d, b, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be D.esp -> B.esp -> C.esp
                 assert!(graph.inner.contains_edge(b, c));
@@ -1953,7 +2873,7 @@ mod tests {
 
                 graph.add_edge(f, d, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be E.esp -> F.esp -> D.esp
                 assert!(graph.inner.contains_edge(e, f));
@@ -1975,7 +2895,7 @@ mod tests {
 
                 graph.add_edge(e, d, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be E.esp -> D.esp -> F.esp
                 assert!(graph.inner.contains_edge(e, d));
@@ -1997,7 +2917,7 @@ mod tests {
 
                 graph.add_edge(f, d2, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be D1.esp -> E.esp -> F.esp -> D2.esp
                 assert!(graph.inner.contains_edge(e, f));
@@ -2023,7 +2943,7 @@ mod tests {
                 graph.add_edge(d, b, EdgeType::Master);
                 graph.add_edge(f, d, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // No ideal result, expected is F.esp -> D.esp -> B.esp -> C.esp -> E.esp
                 assert!(graph.inner.contains_edge(f, d));
@@ -2053,7 +2973,7 @@ mod tests {
                 graph.add_edge(d2, b, EdgeType::Master);
                 graph.add_edge(f, d1, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be D2.esp -> B.esp -> C.esp -> E.esp -> F.esp -> D1.esp
                 assert!(graph.inner.contains_edge(d2, b));
@@ -2089,7 +3009,7 @@ mod tests {
                 graph.add_edge(d4, c, EdgeType::Master);
                 graph.add_edge(f, d1, EdgeType::Master);
 
-                graph.add_group_edges(&fixture.groups_graph).unwrap();
+                graph.add_group_edges(&fixture.groups_graph, &[]).unwrap();
 
                 // Should be:
                 // D2.esp -> B.esp -> D4.esp -> C.esp -> D3.esp -> E.esp -> F.esp -> D1.esp
@@ -2131,7 +3051,7 @@ mod tests {
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
                 let d = graph.add_node(fixture.group_sorting_data(PLUGIN_D, "D"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -> C.esp
                 //                 -> D.esp
@@ -2171,7 +3091,7 @@ mod tests {
                 let d = graph.add_node(fixture.group_sorting_data(PLUGIN_D, "D"));
                 let e = graph.add_node(fixture.group_sorting_data(PLUGIN_E, "E"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -> C.esp -> E.esp
                 //                 -> D.esp ---------->
@@ -2210,7 +3130,7 @@ mod tests {
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
                 let d = graph.add_node(fixture.group_sorting_data(PLUGIN_D, "D"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -> D.esp
                 //                 -> C.esp ->
@@ -2251,7 +3171,7 @@ mod tests {
 
                 graph.add_edge(d, c, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -> D.esp -> C.esp -> E.esp
                 assert!(graph.inner.contains_edge(d, c));
@@ -2298,7 +3218,7 @@ mod tests {
                 let f = graph.add_node(fixture.group_sorting_data(PLUGIN_E, "F"));
                 let g = graph.add_node(fixture.group_sorting_data(PLUGIN_E, "G"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be:
                 // A.esp -> B.esp -> D.esp -> E.esp -> G.esp
@@ -2345,7 +3265,7 @@ mod tests {
 
                 graph.add_edge(e, a, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be:
                 // A.esp -> B.esp -> D.esp -> E.esp -> G.esp
@@ -2382,7 +3302,7 @@ mod tests {
                 let b = graph.add_node(fixture.group_sorting_data(PLUGIN_B, "B"));
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp
                 //           C.esp
@@ -2417,7 +3337,7 @@ mod tests {
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
                 let d = graph.add_node(fixture.group_sorting_data(PLUGIN_D, "D"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp
                 //           C.esp -> D.esp
@@ -2459,7 +3379,7 @@ mod tests {
 
                 graph.add_edge(c, b, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> C.esp -> D.esp
                 //           B.esp ---------->
@@ -2500,7 +3420,7 @@ mod tests {
 
                     graph.add_edge(d, b, EdgeType::Master);
 
-                    graph.add_group_edges(&groups_graph).unwrap();
+                    graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                     // Should be D.esp -> B.esp -> C.esp
                     assert!(graph.inner.contains_edge(b, c));
@@ -2543,7 +3463,7 @@ mod tests {
 
                     graph.add_edge(d, a, EdgeType::Master);
 
-                    graph.add_group_edges(&groups_graph).unwrap();
+                    graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                     // Should be B.esp -> D.esp -> A.esp -> C.esp
                     //           B.esp ------------------->
@@ -2595,7 +3515,7 @@ mod tests {
 
                     graph.add_edge(e, c, EdgeType::Master);
 
-                    graph.add_group_edges(&groups_graph).unwrap();
+                    graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                     // Should be A.esp -> B.esp -> D.esp -> E.esp -> C.esp
                     assert!(graph.inner.contains_edge(a, b));
@@ -2678,7 +3598,7 @@ mod tests {
 
                     graph.add_edge(c, a1, EdgeType::Master);
 
-                    graph.add_group_edges(&groups_graph).unwrap();
+                    graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                     // Should be A2.esp -> C.esp -> A1.esp -> B.esp
                     //           A2.esp -------------------->
@@ -2718,7 +3638,7 @@ mod tests {
 
                 graph.add_edge(c, d, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be C.esp -> D.esp -> A.esp -> B.esp
                 // Processing groups lexicographically would give:
@@ -2768,7 +3688,7 @@ mod tests {
 
                 graph.add_edge(f, b, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be          D.esp -> B.esp -> C.esp -> E.esp
                 //  A.esp -> F.esp ----------> B.esp
@@ -2810,7 +3730,7 @@ mod tests {
 
                 graph.add_edge(e, c, EdgeType::Master);
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -> E.esp -> C.esp -> D.esp
                 assert!(graph.inner.contains_edge(a, b));
@@ -2851,7 +3771,7 @@ mod tests {
                 let c = graph.add_node(fixture.group_sorting_data(PLUGIN_C, "C"));
                 let d = graph.add_node(fixture.group_sorting_data(PLUGIN_D, "D"));
 
-                graph.add_group_edges(&groups_graph).unwrap();
+                graph.add_group_edges(&groups_graph, &[]).unwrap();
 
                 // Should be A.esp -> B.esp -----------------------> C.esp -> D.esp
                 //                          -> BU1.esp -> BU2.esp ->
@@ -2876,6 +3796,98 @@ mod tests {
         mod add_overlap_edges {
             use super::*;
 
+            fn overlap_edges_with_threads(
+                fixture: &Fixture,
+                names: &[String],
+                num_threads: usize,
+            ) -> Vec<(String, String, EdgeType)> {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()
+                    .unwrap();
+
+                pool.install(|| {
+                    let mut graph = PluginsGraph::<TestPlugin>::new();
+                    let nodes: Vec<_> = names
+                        .iter()
+                        .map(|n| graph.add_node(fixture.sorting_data(n)))
+                        .collect();
+                    graph.add_edge(nodes[2], nodes[5], EdgeType::Master);
+
+                    let mut cache = OverlapCache::default();
+                    graph
+                        .add_overlap_edges(&SortOptions::default(), Some(&mut cache))
+                        .unwrap();
+
+                    let mut edges: Vec<_> = graph
+                        .inner
+                        .edge_references()
+                        .map(|e| {
+                            (
+                                graph[e.source()].name().to_owned(),
+                                graph[e.target()].name().to_owned(),
+                                e.weight().clone(),
+                            )
+                        })
+                        .collect();
+                    edges.sort();
+                    edges
+                })
+            }
+
+            #[test]
+            fn should_add_the_same_edges_when_comparing_in_parallel_as_in_serial() {
+                // Each plugin's override record count, asset count, and the
+                // plugins that its records and assets overlap with.
+                let plugins = [
+                    ("0.esp", 3, 0, vec!["1.esp", "4.esp"], vec![]),
+                    (
+                        "1.esp",
+                        1,
+                        2,
+                        vec!["0.esp", "5.esp"],
+                        vec!["3.esp", "6.esp"],
+                    ),
+                    ("2.esp", 2, 1, vec!["4.esp", "7.esp"], vec!["5.esp"]),
+                    ("3.esp", 2, 4, vec!["6.esp"], vec!["1.esp", "7.esp"]),
+                    ("4.esp", 5, 3, vec!["0.esp", "2.esp"], vec![]),
+                    ("5.esp", 2, 1, vec!["1.esp"], vec!["2.esp", "6.esp"]),
+                    ("6.esp", 2, 3, vec!["3.esp"], vec!["1.esp", "5.esp"]),
+                    ("7.esp", 4, 2, vec!["2.esp"], vec!["3.esp"]),
+                ];
+
+                let names: Vec<_> = plugins.iter().map(|p| p.0.to_owned()).collect();
+                let name_refs: Vec<_> = names.iter().map(String::as_str).collect();
+                let mut fixture = Fixture::with_plugins(&name_refs);
+
+                for (name, override_record_count, asset_count, records, assets) in plugins {
+                    let plugin = fixture.get_plugin_mut(name);
+                    plugin.override_record_count = override_record_count;
+                    plugin.asset_count = asset_count;
+                    for other in records {
+                        plugin.add_overlapping_records(other);
+                    }
+                    for other in assets {
+                        plugin.add_overlapping_assets(other);
+                    }
+                }
+
+                let serial_edges = overlap_edges_with_threads(&fixture, &names, 1);
+                let parallel_edges = overlap_edges_with_threads(&fixture, &names, 4);
+
+                assert!(
+                    serial_edges
+                        .iter()
+                        .any(|(_, _, t)| *t == EdgeType::RecordOverlap)
+                );
+                assert!(
+                    serial_edges
+                        .iter()
+                        .any(|(_, _, t)| *t == EdgeType::AssetOverlap)
+                );
+                assert_eq!(serial_edges, parallel_edges);
+            }
+
             #[test]
             fn should_not_add_edges_between_non_overlapping_plugins() {
                 let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
@@ -2884,7 +3896,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert!(!graph.inner.contains_edge(a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -2905,7 +3919,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert!(!graph.inner.contains_edge(a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -2926,7 +3942,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert_eq!(EdgeType::RecordOverlap, edge_type(&graph, a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -2946,7 +3964,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert!(!graph.inner.contains_edge(a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -2967,7 +3987,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert!(!graph.inner.contains_edge(a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -2988,7 +4010,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert!(!graph.inner.contains_edge(a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -3009,7 +4033,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert_eq!(EdgeType::AssetOverlap, edge_type(&graph, a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -3032,7 +4058,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert_eq!(EdgeType::AssetOverlap, edge_type(&graph, a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -3056,7 +4084,9 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert_eq!(EdgeType::AssetOverlap, edge_type(&graph, a, b));
                 assert!(!graph.inner.contains_edge(b, a));
@@ -3080,11 +4110,52 @@ mod tests {
                 let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
                 let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
 
-                graph.add_overlap_edges().unwrap();
+                graph
+                    .add_overlap_edges(&SortOptions::default(), None)
+                    .unwrap();
 
                 assert_eq!(EdgeType::RecordOverlap, edge_type(&graph, a, b));
                 assert!(!graph.inner.contains_edge(b, a));
             }
+
+            #[test]
+            fn should_use_the_given_overlap_policy() {
+                use crate::sorting::overlap::{CountOverlapPolicy, OverlapPolicy};
+
+                #[derive(Debug)]
+                struct ReversePolicy;
+
+                impl OverlapPolicy for ReversePolicy {
+                    fn compare(
+                        &self,
+                        pair: &mut OverlapPair<'_>,
+                    ) -> Result<Option<OverlapEdge>, PluginDataError> {
+                        Ok(CountOverlapPolicy::default()
+                            .compare(pair)?
+                            .map(|e| OverlapEdge::new(!e.plugin_loads_first(), e.overlap_type())))
+                    }
+                }
+
+                let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+                let a = fixture.get_plugin_mut(PLUGIN_A);
+                a.asset_count = 2;
+                a.add_overlapping_assets(PLUGIN_B);
+
+                let b = fixture.get_plugin_mut(PLUGIN_B);
+                b.asset_count = 1;
+
+                let mut graph = PluginsGraph::<TestPlugin>::new();
+                let a = graph.add_node(fixture.sorting_data(PLUGIN_A));
+                let b = graph.add_node(fixture.sorting_data(PLUGIN_B));
+
+                let options =
+                    SortOptions::new().with_overlap_policy(std::sync::Arc::new(ReversePolicy));
+                graph.add_overlap_edges(&options, None).unwrap();
+
+                assert_eq!(EdgeType::AssetOverlap, edge_type(&graph, b, a));
+                assert!(!graph.inner.contains_edge(a, b));
+            }
         }
 
         mod add_tie_break_edges {
@@ -3103,7 +4174,7 @@ mod tests {
                 let mut graph = PluginsGraph::<TestPlugin>::new();
                 graph.add_node(fixture.sorting_data(PLUGIN_A));
 
-                assert!(graph.add_tie_break_edges().is_ok());
+                assert!(graph.add_tie_break_edges(&SortOptions::default()).is_ok());
             }
 
             #[test]
@@ -3119,7 +4190,7 @@ mod tests {
                 graph.add_node(fixture.sorting_data(PLUGIN_D));
                 graph.add_node(fixture.sorting_data(PLUGIN_E));
 
-                graph.add_tie_break_edges().unwrap();
+                graph.add_tie_break_edges(&SortOptions::default()).unwrap();
 
                 let sorted = graph.topological_sort().unwrap();
 
@@ -3164,7 +4235,7 @@ mod tests {
                 graph.add_edge(g, d, EdgeType::RecordOverlap);
                 graph.add_edge(i, e, EdgeType::RecordOverlap);
 
-                graph.add_tie_break_edges().unwrap();
+                graph.add_tie_break_edges(&SortOptions::default()).unwrap();
 
                 let sorted = graph.topological_sort().unwrap();
 
@@ -3209,7 +4280,7 @@ mod tests {
                 graph.add_edge(c, d, EdgeType::RecordOverlap);
                 graph.add_edge(d, a, EdgeType::RecordOverlap);
 
-                graph.add_tie_break_edges().unwrap();
+                graph.add_tie_break_edges(&SortOptions::default()).unwrap();
 
                 let sorted = graph.topological_sort().unwrap();
 
@@ -3232,92 +4303,495 @@ mod tests {
     }
 
     mod sort_plugins {
-        use crate::{Vertex, sorting::error::PluginGraphValidationError};
+        use crate::sorting::{
+            error::PluginGraphValidationError,
+            report::{IgnoredEdge, IgnoredEdgeReason},
+        };
 
         use super::*;
 
+        const PLUGIN_C: &str = "C.esp";
+        const PLUGIN_D: &str = "D.esp";
+        const PLUGIN_E: &str = "E.esp";
+
         #[test]
-        fn should_not_change_the_result_if_given_its_own_output() {
+        fn should_error_if_cancelled() {
             let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
 
-            let expected = &[PLUGIN_A, PLUGIN_B];
+            let token = crate::sorting::progress::CancellationToken::new();
+            token.cancel();
+            let options = SortOptions::new().with_cancellation_token(token);
 
-            let sorted = sort_plugins(
+            match sort_plugins(
                 vec![
-                    fixture.sorting_data(PLUGIN_B),
                     fixture.sorting_data(PLUGIN_A),
+                    fixture.sorting_data(PLUGIN_B),
                 ],
                 &fixture.groups_graph,
                 &[],
-            )
-            .unwrap();
+                &[],
+                &options,
+                None,
+            ) {
+                Err(SortingError::Cancelled) => {}
+                r => panic!("Expected sorting to be cancelled, got {r:?}"),
+            }
+        }
+
+        #[test]
+        fn should_succeed_if_a_unique_order_is_required_and_tie_breaks_order_all_plugins() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
 
-            assert_eq!(expected, sorted.as_slice());
+            let options = SortOptions::new().with_require_unique_order(true);
 
-            let sorted = sort_plugins(
+            let report = sort_plugins(
                 vec![
-                    fixture.sorting_data(PLUGIN_A),
                     fixture.sorting_data(PLUGIN_B),
+                    fixture.sorting_data(PLUGIN_A),
+                    fixture.sorting_data(PLUGIN_C),
                 ],
                 &fixture.groups_graph,
                 &[],
+                &[],
+                &options,
+                None,
             )
             .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(&[PLUGIN_A, PLUGIN_B, PLUGIN_C], report.load_order());
         }
 
         #[test]
-        fn should_use_group_metadata_when_deciding_relative_plugin_positions() {
-            let fixture = Fixture::with_plugins(&[PLUGIN_B, PLUGIN_A]);
+        fn should_report_progress_for_each_phase() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
 
-            let data = vec![
-                fixture.group_sorting_data(PLUGIN_A, "A"),
-                fixture.group_sorting_data(PLUGIN_B, "B"),
-            ];
+            let a = fixture.get_plugin_mut(PLUGIN_A);
+            a.override_record_count = 2;
+            a.add_overlapping_records(PLUGIN_B);
 
-            let expected = &[PLUGIN_A, PLUGIN_B];
+            let b = fixture.get_plugin_mut(PLUGIN_B);
+            b.override_record_count = 1;
+
+            let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let callback_progress = std::sync::Arc::clone(&progress);
+            let options = SortOptions::new().with_progress_callback(std::sync::Arc::new(
+                move |phase, done, total| {
+                    callback_progress.lock().unwrap().push((phase, done, total));
+                },
+            ));
+
+            sort_plugins(
+                vec![
+                    fixture.sorting_data(PLUGIN_B),
+                    fixture.sorting_data(PLUGIN_A),
+                ],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &options,
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                vec![
+                    (SortPhase::CheckingOverlap, 0, 2),
+                    (SortPhase::CheckingOverlap, 1, 2),
+                    (SortPhase::CheckingOverlap, 2, 2),
+                    (SortPhase::AddingOverlapEdges, 0, 1),
+                    (SortPhase::AddingOverlapEdges, 1, 1),
+                    (SortPhase::AddingTieBreakEdges, 0, 2),
+                    (SortPhase::AddingTieBreakEdges, 1, 2),
+                    (SortPhase::AddingTieBreakEdges, 2, 2),
+                ],
+                *progress.lock().unwrap()
+            );
+        }
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[]).unwrap();
+        fn sort_with_pins(
+            fixture: &Fixture,
+            plugin_names: &[&str],
+            early_loading_plugins: &[String],
+            pins: Vec<Pin>,
+        ) -> Result<SortReport, SortingError> {
+            let data = plugin_names
+                .iter()
+                .map(|n| fixture.sorting_data(n))
+                .collect();
 
-            assert_eq!(expected, sorted.as_slice());
+            sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                early_loading_plugins,
+                &SortOptions::new().with_pins(pins),
+                None,
+            )
         }
 
         #[test]
-        fn should_use_load_after_metadata_when_deciding_relative_plugin_positions() {
-            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+        fn should_load_a_plugin_pinned_first_before_other_plugins_with_the_same_master_flag() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+            fixture.get_plugin_mut(PLUGIN_A).is_master = true;
 
-            let mut a = fixture.sorting_data(PLUGIN_A);
-            a.masterlist_load_after = Box::new([PLUGIN_B.into()]);
+            let sorted = sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B, PLUGIN_C],
+                &[],
+                vec![Pin::new(PLUGIN_C.into(), PinPosition::First)],
+            )
+            .unwrap();
 
-            let data = vec![a, fixture.sorting_data(PLUGIN_B)];
+            assert_eq!(&[PLUGIN_A, PLUGIN_C, PLUGIN_B], sorted.load_order());
+        }
 
-            let expected = &[PLUGIN_B, PLUGIN_A];
+        #[test]
+        fn should_load_a_plugin_pinned_last_after_other_plugins_with_the_same_master_flag() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[]).unwrap();
+            let sorted = sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B, PLUGIN_C],
+                &[],
+                vec![Pin::new(PLUGIN_A.into(), PinPosition::Last)],
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(&[PLUGIN_B, PLUGIN_C, PLUGIN_A], sorted.load_order());
         }
 
         #[test]
-        fn should_use_requirement_metadata_when_deciding_relative_plugin_positions() {
-            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+        fn should_load_a_plugin_pinned_to_an_index_at_that_index() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C, PLUGIN_D]);
+            fixture.get_plugin_mut(PLUGIN_A).is_master = true;
 
-            let mut a = fixture.sorting_data(PLUGIN_A);
-            a.masterlist_req = Box::new([PLUGIN_B.into()]);
+            let sorted = sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B, PLUGIN_C, PLUGIN_D],
+                &[],
+                vec![Pin::new(PLUGIN_D.into(), PinPosition::Index(2))],
+            )
+            .unwrap();
 
-            let data = vec![a, fixture.sorting_data(PLUGIN_B)];
+            assert_eq!(
+                &[PLUGIN_A, PLUGIN_B, PLUGIN_D, PLUGIN_C],
+                sorted.load_order()
+            );
+        }
 
-            let expected = &[PLUGIN_B, PLUGIN_A];
+        #[test]
+        fn should_load_a_plugin_pinned_after_another_plugin_after_that_plugin() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[]).unwrap();
+            let sorted = sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B, PLUGIN_C],
+                &[],
+                vec![Pin::new(
+                    PLUGIN_A.into(),
+                    PinPosition::After(PLUGIN_B.into()),
+                )],
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(&[PLUGIN_B, PLUGIN_A, PLUGIN_C], sorted.load_order());
         }
 
         #[test]
-        fn should_use_early_loader_positions_when_deciding_relative_plugin_positions() {
-            let fixture = Fixture::with_plugins(&[PLUGIN_B, PLUGIN_A]);
+        fn should_error_if_a_pin_conflicts_with_a_master_edge() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+            fixture.get_plugin_mut(PLUGIN_A).add_master(PLUGIN_B);
+
+            match sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B],
+                &[],
+                vec![Pin::new(
+                    PLUGIN_B.into(),
+                    PinPosition::After(PLUGIN_A.into()),
+                )],
+            ) {
+                Err(SortingError::InvalidPin(PinError::ConflictingEdges(cycle))) => assert_eq!(
+                    vec![
+                        Vertex::new(PLUGIN_B.into()).with_out_edge_type(EdgeType::Master),
+                        Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::Pin),
+                    ],
+                    cycle
+                ),
+                r => panic!("Expected a conflicting pin error, got {r:?}"),
+            }
+        }
+
+        #[test]
+        fn should_error_if_a_pin_conflicts_with_a_hardcoded_edge() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            match sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B],
+                &[PLUGIN_A.into()],
+                vec![Pin::new(PLUGIN_B.into(), PinPosition::First)],
+            ) {
+                Err(SortingError::InvalidPin(PinError::ConflictingEdges(cycle))) => assert_eq!(
+                    vec![
+                        Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::Hardcoded),
+                        Vertex::new(PLUGIN_B.into()).with_out_edge_type(EdgeType::Pin),
+                    ],
+                    cycle
+                ),
+                r => panic!("Expected a conflicting pin error, got {r:?}"),
+            }
+        }
+
+        #[test]
+        fn should_error_if_a_plugin_is_pinned_to_an_index_outside_its_partition() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+            fixture.get_plugin_mut(PLUGIN_A).is_master = true;
+
+            match sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B],
+                &[],
+                vec![Pin::new(PLUGIN_A.into(), PinPosition::Index(1))],
+            ) {
+                Err(SortingError::InvalidPin(PinError::IndexOutOfRange { plugin, index })) => {
+                    assert_eq!(PLUGIN_A, plugin);
+                    assert_eq!(1, index);
+                }
+                r => panic!("Expected an index out of range error, got {r:?}"),
+            }
+        }
+
+        #[test]
+        fn should_error_if_a_plugin_is_pinned_relative_to_a_plugin_in_another_partition() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+            fixture.get_plugin_mut(PLUGIN_A).is_master = true;
+
+            match sort_with_pins(
+                &fixture,
+                &[PLUGIN_A, PLUGIN_B],
+                &[],
+                vec![Pin::new(
+                    PLUGIN_B.into(),
+                    PinPosition::Before(PLUGIN_A.into()),
+                )],
+            ) {
+                Err(SortingError::InvalidPin(PinError::DifferentPartition {
+                    plugin,
+                    other_plugin,
+                })) => {
+                    assert_eq!(PLUGIN_B, plugin);
+                    assert_eq!(PLUGIN_A, other_plugin);
+                }
+                r => panic!("Expected a different partition error, got {r:?}"),
+            }
+        }
+
+        #[test]
+        fn should_report_metadata_edges_that_are_satisfied_by_master_flags_as_ignored() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+            fixture.get_plugin_mut(PLUGIN_A).is_master = true;
+            fixture.get_plugin_mut(PLUGIN_C).is_master = true;
+            fixture.get_plugin_mut(PLUGIN_B).add_master(PLUGIN_C);
+
+            let mut metadata = PluginMetadata::new(PLUGIN_B).unwrap();
+            metadata.set_load_after_files(vec![File::new(PLUGIN_A.into())]);
+            let (plugin_b, index) = fixture.get_plugin(PLUGIN_B);
+            let b = PluginSortingData::new(plugin_b, Some(&metadata), None, *index).unwrap();
+
+            let sorted = sort_plugins(
+                vec![
+                    fixture.sorting_data(PLUGIN_A),
+                    b,
+                    fixture.sorting_data(PLUGIN_C),
+                ],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                &[IgnoredEdge::new(
+                    PLUGIN_B.into(),
+                    PLUGIN_A.into(),
+                    EdgeType::MasterlistLoadAfter,
+                    IgnoredEdgeReason::MasterFlag
+                )],
+                sorted.ignored_edges()
+            );
+        }
+
+        #[test]
+        fn should_report_blueprint_masters_of_other_plugins_as_ignored() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+            let a = fixture.get_plugin_mut(PLUGIN_A);
+            a.is_master = true;
+            a.is_blueprint_plugin = true;
+            fixture.get_plugin_mut(PLUGIN_B).add_master(PLUGIN_A);
+
+            let sorted = sort_plugins(
+                vec![
+                    fixture.sorting_data(PLUGIN_A),
+                    fixture.sorting_data(PLUGIN_B),
+                ],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                &[IgnoredEdge::new(
+                    PLUGIN_B.into(),
+                    PLUGIN_A.into(),
+                    EdgeType::Master,
+                    IgnoredEdgeReason::BlueprintMasterLoadsLater
+                )],
+                sorted.ignored_edges()
+            );
+        }
+
+        #[test]
+        fn should_not_change_the_result_if_given_its_own_output() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let expected = &[PLUGIN_A, PLUGIN_B];
+
+            let sorted = sort_plugins(
+                vec![
+                    fixture.sorting_data(PLUGIN_B),
+                    fixture.sorting_data(PLUGIN_A),
+                ],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+
+            let sorted = sort_plugins(
+                vec![
+                    fixture.sorting_data(PLUGIN_A),
+                    fixture.sorting_data(PLUGIN_B),
+                ],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_use_group_metadata_when_deciding_relative_plugin_positions() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_B, PLUGIN_A]);
+
+            let data = vec![
+                fixture.group_sorting_data(PLUGIN_A, "A"),
+                fixture.group_sorting_data(PLUGIN_B, "B"),
+            ];
+
+            let expected = &[PLUGIN_A, PLUGIN_B];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_use_load_after_metadata_when_deciding_relative_plugin_positions() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.masterlist_load_after = Box::new([PLUGIN_B.into()]);
+
+            let data = vec![a, fixture.sorting_data(PLUGIN_B)];
+
+            let expected = &[PLUGIN_B, PLUGIN_A];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_use_requirement_metadata_when_deciding_relative_plugin_positions() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.masterlist_req = Box::new([PLUGIN_B.into()]);
+
+            let data = vec![a, fixture.sorting_data(PLUGIN_B)];
+
+            let expected = &[PLUGIN_B, PLUGIN_A];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_use_metadata_layer_load_after_metadata_when_deciding_relative_plugin_positions() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.layer_load_after = Box::new([("modpack".into(), PLUGIN_B.into())]);
+
+            let data = vec![a, fixture.sorting_data(PLUGIN_B)];
+
+            let expected = &[PLUGIN_B, PLUGIN_A];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_use_early_loader_positions_when_deciding_relative_plugin_positions() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_B, PLUGIN_A]);
 
             let data = vec![
                 fixture.sorting_data(PLUGIN_A),
@@ -3326,9 +4800,17 @@ mod tests {
 
             let expected = &[PLUGIN_A, PLUGIN_B];
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[PLUGIN_A.into()]).unwrap();
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[PLUGIN_A.into()],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(expected, sorted.load_order());
         }
 
         #[test]
@@ -3337,7 +4819,17 @@ mod tests {
 
             let data = vec![fixture.group_sorting_data(PLUGIN_A, "missing")];
 
-            assert!(sort_plugins(data, &fixture.groups_graph, &[]).is_err());
+            assert!(
+                sort_plugins(
+                    data,
+                    &fixture.groups_graph,
+                    &[],
+                    &[],
+                    &SortOptions::default(),
+                    None
+                )
+                .is_err()
+            );
         }
 
         #[test]
@@ -3352,7 +4844,14 @@ mod tests {
                 fixture.sorting_data(PLUGIN_B),
             ];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::CycleFound(e)) => {
                     assert_eq!(
                         &[
@@ -3379,7 +4878,14 @@ mod tests {
                 fixture.sorting_data(PLUGIN_B),
             ];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3404,7 +4910,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3430,7 +4943,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3456,7 +4976,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3482,7 +5009,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3508,7 +5042,14 @@ mod tests {
                 fixture.sorting_data(PLUGIN_B),
             ];
 
-            match sort_plugins(data, &fixture.groups_graph, &[PLUGIN_B.into()]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[PLUGIN_B.into()],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3541,9 +5082,17 @@ mod tests {
 
             let expected = &[PLUGIN_B, PLUGIN_A];
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[]).unwrap();
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(expected, sorted.load_order());
         }
 
         #[test]
@@ -3563,9 +5112,17 @@ mod tests {
 
             let expected = &[PLUGIN_B, PLUGIN_A];
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[]).unwrap();
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(expected, sorted.load_order());
         }
 
         #[test]
@@ -3583,7 +5140,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3613,7 +5177,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3644,7 +5215,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3673,7 +5251,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3704,7 +5289,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3734,7 +5326,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3765,7 +5364,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3794,7 +5400,14 @@ mod tests {
 
             let data = vec![a, fixture.sorting_data(PLUGIN_B)];
 
-            match sort_plugins(data, &fixture.groups_graph, &[]) {
+            match sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
                 Err(SortingError::ValidationError(PluginGraphValidationError::CycleFound(e))) => {
                     assert_eq!(
                         &[
@@ -3827,9 +5440,17 @@ mod tests {
 
             let expected = &[PLUGIN_A, PLUGIN_B];
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[PLUGIN_B.into()]).unwrap();
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[PLUGIN_B.into()],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
 
-            assert_eq!(expected, sorted.as_slice());
+            assert_eq!(expected, sorted.load_order());
         }
 
         #[test]
@@ -3847,9 +5468,461 @@ mod tests {
 
             let expected = &[PLUGIN_A, PLUGIN_B];
 
-            let sorted = sort_plugins(data, &fixture.groups_graph, &[PLUGIN_B.into()]).unwrap();
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[PLUGIN_B.into()],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(expected, sorted.load_order());
+        }
+
+        #[test]
+        fn should_report_the_strongest_edge_between_each_pair_of_adjacent_plugins() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+            let mut c = fixture.sorting_data(PLUGIN_C);
+            c.masterlist_load_after = Box::new([PLUGIN_B.into()]);
+            c.user_load_after = Box::new([PLUGIN_B.into()]);
+
+            let data = vec![
+                fixture.sorting_data(PLUGIN_A),
+                fixture.sorting_data(PLUGIN_B),
+                c,
+            ];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_A, PLUGIN_B, PLUGIN_C], sorted.load_order());
+            assert_eq!(
+                &[
+                    vec![
+                        Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::TieBreak),
+                        Vertex::new(PLUGIN_B.into()),
+                    ],
+                    vec![
+                        Vertex::new(PLUGIN_B.into())
+                            .with_out_edge_type(EdgeType::MasterlistLoadAfter),
+                        Vertex::new(PLUGIN_C.into()),
+                    ],
+                ],
+                sorted.adjacent_plugin_paths()
+            );
+            assert!(sorted.moved_plugins().is_empty());
+        }
+
+        #[test]
+        fn should_report_layer_edges_as_stronger_than_user_edges_and_weaker_than_masterlist_edges()
+        {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+            let mut b = fixture.sorting_data(PLUGIN_B);
+            b.masterlist_load_after = Box::new([PLUGIN_A.into()]);
+            b.layer_load_after = Box::new([("modpack".into(), PLUGIN_A.into())]);
+
+            let mut c = fixture.sorting_data(PLUGIN_C);
+            c.layer_req = Box::new([("local".into(), PLUGIN_B.into())]);
+            c.user_req = Box::new([PLUGIN_B.into()]);
+
+            let data = vec![fixture.sorting_data(PLUGIN_A), b, c];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_A, PLUGIN_B, PLUGIN_C], sorted.load_order());
+            assert_eq!(
+                &[
+                    vec![
+                        Vertex::new(PLUGIN_A.into())
+                            .with_out_edge_type(EdgeType::MasterlistLoadAfter),
+                        Vertex::new(PLUGIN_B.into()),
+                    ],
+                    vec![
+                        Vertex::new(PLUGIN_B.into())
+                            .with_out_edge_type(EdgeType::LayerRequirement("local".into())),
+                        Vertex::new(PLUGIN_C.into()),
+                    ],
+                ],
+                sorted.adjacent_plugin_paths()
+            );
+        }
+
+        #[test]
+        fn should_report_the_paths_that_caused_plugins_to_move_earlier_and_later() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.user_load_after = Box::new([PLUGIN_C.into()]);
+
+            let data = vec![
+                a,
+                fixture.sorting_data(PLUGIN_B),
+                fixture.sorting_data(PLUGIN_C),
+            ];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_C, PLUGIN_A, PLUGIN_B], sorted.load_order());
+            assert_eq!(
+                &[
+                    MovedPlugin::new(
+                        PLUGIN_C.into(),
+                        2,
+                        0,
+                        MoveDirection::Earlier,
+                        vec![
+                            Vertex::new(PLUGIN_C.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                            Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::TieBreak),
+                            Vertex::new(PLUGIN_B.into()),
+                        ]
+                    ),
+                    MovedPlugin::new(
+                        PLUGIN_A.into(),
+                        0,
+                        1,
+                        MoveDirection::Later,
+                        vec![
+                            Vertex::new(PLUGIN_C.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                            Vertex::new(PLUGIN_A.into()),
+                        ]
+                    ),
+                    MovedPlugin::new(
+                        PLUGIN_B.into(),
+                        1,
+                        2,
+                        MoveDirection::Later,
+                        vec![
+                            Vertex::new(PLUGIN_C.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                            Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::TieBreak),
+                            Vertex::new(PLUGIN_B.into()),
+                        ]
+                    ),
+                ],
+                sorted.moved_plugins()
+            );
+        }
+
+        #[test]
+        fn should_report_an_edge_between_partitions() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+            fixture.get_plugin_mut(PLUGIN_B).is_master = true;
+
+            let c = fixture.get_plugin_mut(PLUGIN_C);
+            c.is_master = true;
+            c.is_blueprint_plugin = true;
+
+            let data = vec![
+                fixture.sorting_data(PLUGIN_A),
+                fixture.sorting_data(PLUGIN_B),
+                fixture.sorting_data(PLUGIN_C),
+            ];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_B, PLUGIN_A, PLUGIN_C], sorted.load_order());
+            assert_eq!(
+                &[
+                    vec![
+                        Vertex::new(PLUGIN_B.into()).with_out_edge_type(EdgeType::MasterFlag),
+                        Vertex::new(PLUGIN_A.into()),
+                    ],
+                    vec![
+                        Vertex::new(PLUGIN_A.into()).with_out_edge_type(EdgeType::BlueprintMaster),
+                        Vertex::new(PLUGIN_C.into()),
+                    ],
+                ],
+                sorted.adjacent_plugin_paths()
+            );
+        }
+
+        #[test]
+        fn should_use_and_update_the_given_overlap_cache() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let a = fixture.get_plugin_mut(PLUGIN_A);
+            a.crc = Some(1);
+            a.override_record_count = 1;
+            a.add_overlapping_records(PLUGIN_B);
+            let b = fixture.get_plugin_mut(PLUGIN_B);
+            b.crc = Some(2);
+            b.override_record_count = 2;
+
+            let data = vec![
+                fixture.sorting_data(PLUGIN_A),
+                fixture.sorting_data(PLUGIN_B),
+            ];
+
+            let mut cache = OverlapCache::default();
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                Some(&mut cache),
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_B, PLUGIN_A], sorted.load_order());
+
+            // Plugins with the same names and CRCs are assumed to have the same
+            // overlap, so the cached result is used.
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let a = fixture.get_plugin_mut(PLUGIN_A);
+            a.crc = Some(1);
+            a.override_record_count = 1;
+            let b = fixture.get_plugin_mut(PLUGIN_B);
+            b.crc = Some(2);
+            b.override_record_count = 2;
+
+            let data = vec![
+                fixture.sorting_data(PLUGIN_A),
+                fixture.sorting_data(PLUGIN_B),
+            ];
+
+            let sorted = sort_plugins(
+                data,
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                Some(&mut cache),
+            )
+            .unwrap();
+
+            assert_eq!(&[PLUGIN_B, PLUGIN_A], sorted.load_order());
+        }
+
+        #[test]
+        fn should_error_with_all_cycles_if_reporting_all_cycles() {
+            let mut fixture =
+                Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C, PLUGIN_D, PLUGIN_E]);
+
+            fixture.get_plugin_mut(PLUGIN_D).is_master = true;
+            fixture.get_plugin_mut(PLUGIN_E).is_master = true;
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.user_load_after = Box::new([PLUGIN_C.into()]);
+            let mut b = fixture.sorting_data(PLUGIN_B);
+            b.masterlist_req = Box::new([PLUGIN_A.into()]);
+            let mut c = fixture.sorting_data(PLUGIN_C);
+            c.masterlist_load_after = Box::new([PLUGIN_B.into()]);
+            let mut d = fixture.sorting_data(PLUGIN_D);
+            d.user_load_after = Box::new([PLUGIN_E.into()]);
+            let mut e = fixture.sorting_data(PLUGIN_E);
+            e.user_req = Box::new([PLUGIN_D.into()]);
+
+            let options = SortOptions::new().with_report_all_cycles(true);
+
+            match sort_plugins(
+                vec![a, b, c, d, e],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &options,
+                None,
+            ) {
+                Err(SortingError::CyclesFound(components)) => {
+                    assert_eq!(2, components.len());
+
+                    assert_eq!(&[PLUGIN_D, PLUGIN_E], components[0].plugins());
+                    assert_eq!(
+                        &[
+                            Vertex::new(PLUGIN_D.into())
+                                .with_out_edge_type(EdgeType::UserRequirement),
+                            Vertex::new(PLUGIN_E.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                        ],
+                        components[0].cycle()
+                    );
 
-            assert_eq!(expected, sorted.as_slice());
+                    assert_eq!(&[PLUGIN_A, PLUGIN_B, PLUGIN_C], components[1].plugins());
+                    assert_eq!(
+                        &[
+                            Vertex::new(PLUGIN_A.into())
+                                .with_out_edge_type(EdgeType::MasterlistRequirement),
+                            Vertex::new(PLUGIN_B.into())
+                                .with_out_edge_type(EdgeType::MasterlistLoadAfter),
+                            Vertex::new(PLUGIN_C.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                        ],
+                        components[1].cycle()
+                    );
+                }
+                _ => panic!("Expected to find cycles"),
+            }
+        }
+
+        #[test]
+        fn should_error_with_the_first_cycle_found_if_not_reporting_all_cycles() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C, PLUGIN_D]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.user_load_after = Box::new([PLUGIN_B.into()]);
+            let mut b = fixture.sorting_data(PLUGIN_B);
+            b.user_load_after = Box::new([PLUGIN_A.into()]);
+            let mut c = fixture.sorting_data(PLUGIN_C);
+            c.user_load_after = Box::new([PLUGIN_D.into()]);
+            let mut d = fixture.sorting_data(PLUGIN_D);
+            d.user_load_after = Box::new([PLUGIN_C.into()]);
+
+            match sort_plugins(
+                vec![a, b, c, d],
+                &fixture.groups_graph,
+                &[],
+                &[],
+                &SortOptions::default(),
+                None,
+            ) {
+                Err(SortingError::CycleFound(e)) => {
+                    assert_eq!(
+                        &[
+                            Vertex::new(PLUGIN_A.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                            Vertex::new(PLUGIN_B.into())
+                                .with_out_edge_type(EdgeType::UserLoadAfter),
+                        ],
+                        e.into_cycle().as_slice()
+                    );
+                }
+                _ => panic!("Expected to find a cycle"),
+            }
+        }
+    }
+
+    mod build_plugins_graph {
+        use super::*;
+
+        const PLUGIN_C: &str = "C.esp";
+
+        #[test]
+        fn should_include_plugins_from_all_partitions_and_only_edges_within_them() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            fixture.get_plugin_mut(PLUGIN_B).is_master = true;
+
+            let data = vec![
+                fixture.group_sorting_data(PLUGIN_A, "B"),
+                fixture.sorting_data(PLUGIN_B),
+            ];
+
+            let graph = build_plugins_graph(data, &fixture.groups_graph, &[], &[]).unwrap();
+
+            assert_eq!(
+                &[
+                    SortingGraphNode::new(PLUGIN_B.into(), Group::DEFAULT_NAME.into()),
+                    SortingGraphNode::new(PLUGIN_A.into(), "B".into()),
+                ],
+                graph.nodes()
+            );
+            assert!(graph.edges().is_empty());
+        }
+
+        #[test]
+        fn should_include_edges_of_all_types() {
+            let mut fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B, PLUGIN_C]);
+
+            fixture
+                .get_plugin_mut(PLUGIN_B)
+                .add_overlapping_records(PLUGIN_C);
+            fixture.get_plugin_mut(PLUGIN_B).override_record_count = 2;
+
+            let mut c = fixture.sorting_data(PLUGIN_C);
+            c.user_load_after = Box::new([PLUGIN_A.into()]);
+
+            let data = vec![
+                fixture.sorting_data(PLUGIN_A),
+                fixture.sorting_data(PLUGIN_B),
+                c,
+            ];
+
+            let graph = build_plugins_graph(data, &fixture.groups_graph, &[], &[]).unwrap();
+
+            assert_eq!(
+                &[
+                    SortingGraphEdge::new(
+                        PLUGIN_A.into(),
+                        PLUGIN_C.into(),
+                        EdgeType::UserLoadAfter
+                    ),
+                    SortingGraphEdge::new(
+                        PLUGIN_B.into(),
+                        PLUGIN_C.into(),
+                        EdgeType::RecordOverlap
+                    ),
+                    SortingGraphEdge::new(PLUGIN_A.into(), PLUGIN_B.into(), EdgeType::TieBreak),
+                ],
+                graph.edges()
+            );
+        }
+
+        #[test]
+        fn should_not_error_if_the_graph_has_a_cycle() {
+            let fixture = Fixture::with_plugins(&[PLUGIN_A, PLUGIN_B]);
+
+            let mut a = fixture.sorting_data(PLUGIN_A);
+            a.user_load_after = Box::new([PLUGIN_B.into()]);
+            let mut b = fixture.sorting_data(PLUGIN_B);
+            b.masterlist_load_after = Box::new([PLUGIN_A.into()]);
+
+            let graph = build_plugins_graph(vec![a, b], &fixture.groups_graph, &[], &[]).unwrap();
+
+            assert_eq!(
+                &[
+                    SortingGraphEdge::new(
+                        PLUGIN_B.into(),
+                        PLUGIN_A.into(),
+                        EdgeType::UserLoadAfter
+                    ),
+                    SortingGraphEdge::new(
+                        PLUGIN_A.into(),
+                        PLUGIN_B.into(),
+                        EdgeType::MasterlistLoadAfter
+                    ),
+                ],
+                graph.edges()
+            );
         }
     }
 }
@@ -0,0 +1,80 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// A callback that is called with a sorting phase, the number of items that
+/// have been processed in that phase, and the total number of items that will
+/// be processed in that phase.
+pub type ProgressCallback = dyn Fn(SortPhase, usize, usize) + Send + Sync;
+
+/// The phases of sorting that report their progress.
+///
+/// Masters, non-masters and blueprint masters are sorted separately, so each
+/// phase happens once for each of those groups of plugins. Progress is not
+/// reported for a phase that has no items to process.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum SortPhase {
+    /// Checking which plugins overlap. The items are the plugins being
+    /// sorted.
+    CheckingOverlap,
+    /// Adding edges between plugins that overlap. The items are the pairs of
+    /// overlapping plugins.
+    AddingOverlapEdges,
+    /// Adding edges between plugins to give a unique load order. The items are
+    /// the plugins being sorted.
+    AddingTieBreakEdges,
+}
+
+impl std::fmt::Display for SortPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortPhase::CheckingOverlap => write!(f, "checking overlap"),
+            SortPhase::AddingOverlapEdges => write!(f, "adding overlap edges"),
+            SortPhase::AddingTieBreakEdges => write!(f, "adding tie-break edges"),
+        }
+    }
+}
+
+/// A token that can be used to cancel sorting from another thread.
+///
+/// Clones of a token share the same state, so cancelling one clone cancels
+/// them all.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel any sort that was given this token or one of its clones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_a_cancellation_token_should_cancel_its_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+
+        assert!(clone.is_cancelled());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{EdgeType, Vertex};
+
+/// The result of sorting plugins, including the interactions between plugins
+/// that decided their positions in the sorted load order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SortReport {
+    load_order: Vec<String>,
+    adjacent_plugin_paths: Vec<Vec<Vertex>>,
+    moved_plugins: Vec<MovedPlugin>,
+    ignored_edges: Vec<IgnoredEdge>,
+}
+
+impl SortReport {
+    pub(crate) fn new(
+        load_order: Vec<String>,
+        adjacent_plugin_paths: Vec<Vec<Vertex>>,
+        moved_plugins: Vec<MovedPlugin>,
+    ) -> Self {
+        Self {
+            load_order,
+            adjacent_plugin_paths,
+            moved_plugins,
+            ignored_edges: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_ignored_edges(&mut self, ignored_edges: Vec<IgnoredEdge>) {
+        self.ignored_edges = ignored_edges;
+    }
+
+    /// Get the sorted load order.
+    pub fn load_order(&self) -> &[String] {
+        &self.load_order
+    }
+
+    /// Consume the report, returning the sorted load order.
+    pub fn into_load_order(self) -> Vec<String> {
+        self.load_order
+    }
+
+    /// Get the paths that join each pair of adjacent plugins in the sorted
+    /// load order.
+    ///
+    /// The path at index `i` goes from the plugin at index `i` in the sorted
+    /// load order to the plugin at index `i + 1`. If no edge joins a pair of
+    /// plugins (which can only happen if the sorted load order is not unique),
+    /// the first vertex in that pair's path has no out edge type.
+    pub fn adjacent_plugin_paths(&self) -> &[Vec<Vertex>] {
+        &self.adjacent_plugin_paths
+    }
+
+    /// Get the plugins that had to load before a plugin that they previously
+    /// loaded after, or after a plugin that they previously loaded before,
+    /// along with the path that forced the change.
+    ///
+    /// A plugin can appear twice, once for each direction, if it was forced to
+    /// move in both.
+    pub fn moved_plugins(&self) -> &[MovedPlugin] {
+        &self.moved_plugins
+    }
+
+    /// Get the interactions between plugins that were ignored because they
+    /// could not have any effect on the sorted load order.
+    ///
+    /// Master-flagged plugins, non-master plugins and blueprint masters are
+    /// sorted separately, so metadata rules that go between them are not used
+    /// when sorting. Rules that would contradict the master and blueprint
+    /// flags cause sorting to fail, so the ignored rules are those that are
+    /// already satisfied by the flags. A plugin's masters always load before
+    /// it, so they are only included if they are blueprint masters of a plugin
+    /// that is not a blueprint master, as the game ignores them.
+    pub fn ignored_edges(&self) -> &[IgnoredEdge] {
+        &self.ignored_edges
+    }
+
+    /// Append the report for a later-loading partition of plugins, where the
+    /// last plugin in this report loads before the first plugin in the other
+    /// report due to an edge of the given type.
+    pub(crate) fn append(&mut self, other: SortReport, boundary_edge_type: EdgeType) {
+        if let (Some(last), Some(first)) = (self.load_order.last(), other.load_order.first()) {
+            self.adjacent_plugin_paths.push(vec![
+                Vertex::new(last.clone()).with_out_edge_type(boundary_edge_type),
+                Vertex::new(first.clone()),
+            ]);
+        }
+
+        let offset = self.load_order.len();
+
+        self.load_order.extend(other.load_order);
+        self.adjacent_plugin_paths
+            .extend(other.adjacent_plugin_paths);
+        self.moved_plugins
+            .extend(other.moved_plugins.into_iter().map(|mut m| {
+                m.new_index += offset;
+                m
+            }));
+    }
+}
+
+/// Represents a rule that would add an edge between two plugins, but which was
+/// ignored when sorting because it could not have any effect.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IgnoredEdge {
+    plugin: String,
+    target: String,
+    edge_type: EdgeType,
+    reason: IgnoredEdgeReason,
+}
+
+impl IgnoredEdge {
+    pub(crate) fn new(
+        plugin: String,
+        target: String,
+        edge_type: EdgeType,
+        reason: IgnoredEdgeReason,
+    ) -> Self {
+        Self {
+            plugin,
+            target,
+            edge_type,
+            reason,
+        }
+    }
+
+    /// Get the name of the plugin that has the ignored master or metadata.
+    pub fn plugin(&self) -> &str {
+        &self.plugin
+    }
+
+    /// Get the name of the plugin that the ignored master or metadata refers
+    /// to.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Get the type of edge that would have gone from the target plugin to
+    /// the plugin.
+    pub fn edge_type(&self) -> EdgeType {
+        self.edge_type.clone()
+    }
+
+    /// Get the reason why the edge was ignored.
+    pub fn reason(&self) -> IgnoredEdgeReason {
+        self.reason
+    }
+}
+
+/// The reasons why an edge between two plugins can be ignored when sorting.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum IgnoredEdgeReason {
+    /// The target plugin is a master and the plugin is not, so the target
+    /// already loads first.
+    MasterFlag,
+    /// The plugin is a blueprint master and the target plugin is not, so the
+    /// target already loads first.
+    BlueprintMaster,
+    /// The target plugin is a blueprint master and the plugin is not, so the
+    /// target loads after the plugin and the game ignores it as one of the
+    /// plugin's masters.
+    BlueprintMasterLoadsLater,
+}
+
+impl std::fmt::Display for IgnoredEdgeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MasterFlag => write!(f, "the target is a master and the plugin is not"),
+            Self::BlueprintMaster => {
+                write!(f, "the plugin is a blueprint master and the target is not")
+            }
+            Self::BlueprintMasterLoadsLater => write!(
+                f,
+                "the target is a blueprint master and so loads after the plugin"
+            ),
+        }
+    }
+}
+
+/// The direction in which a plugin was moved relative to another plugin.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MoveDirection {
+    /// The plugin had to load before a plugin that it previously loaded
+    /// after.
+    #[default]
+    Earlier,
+    /// The plugin had to load after a plugin that it previously loaded
+    /// before.
+    Later,
+}
+
+/// Represents a plugin that had to load before a plugin that it previously
+/// loaded after, or after a plugin that it previously loaded before.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MovedPlugin {
+    name: String,
+    old_index: usize,
+    new_index: usize,
+    direction: MoveDirection,
+    path: Vec<Vertex>,
+}
+
+impl MovedPlugin {
+    pub(crate) fn new(
+        name: String,
+        old_index: usize,
+        new_index: usize,
+        direction: MoveDirection,
+        path: Vec<Vertex>,
+    ) -> Self {
+        Self {
+            name,
+            old_index,
+            new_index,
+            direction,
+            path,
+        }
+    }
+
+    /// Get the name of the plugin that moved.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the plugin's index in the load order that was sorted.
+    pub fn old_index(&self) -> usize {
+        self.old_index
+    }
+
+    /// Get the plugin's index in the sorted load order.
+    pub fn new_index(&self) -> usize {
+        self.new_index
+    }
+
+    /// Get the direction in which the plugin was moved.
+    pub fn direction(&self) -> MoveDirection {
+        self.direction
+    }
+
+    /// Get the path that forced the plugin to move.
+    ///
+    /// If the plugin moved earlier, the path goes from this plugin to a plugin
+    /// that it previously loaded after. If the plugin moved later, the path
+    /// goes from a plugin that it previously loaded before to this plugin.
+    pub fn path(&self) -> &[Vertex] {
+        &self.path
+    }
+}
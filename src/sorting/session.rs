@@ -0,0 +1,258 @@
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+use super::plugins::{PluginSortingData, SortingPlugin};
+
+/// Holds state that can be reused between sorts of the same game's plugins.
+///
+/// Checking whether plugins overlap is the most expensive part of sorting a
+/// large number of plugins, so a session caches the results of those checks
+/// for each pair of plugins, identifying each plugin by its name and CRC. This
+/// means that re-sorting after adding, removing or changing a small number of
+/// plugins only needs to check the overlap of the plugins that changed.
+///
+/// Only plugins that have been fully loaded have a CRC, so the overlap of
+/// plugins that have only had their headers loaded is never cached.
+///
+/// Asset overlap also depends on the game's archives, so the session should be
+/// cleared if the archives that are loaded for any plugin change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SortingSession {
+    overlap_cache: OverlapCache,
+}
+
+impl SortingSession {
+    /// Create a new session with no cached state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discard all cached state.
+    pub fn clear(&mut self) {
+        self.overlap_cache = OverlapCache::default();
+    }
+
+    /// Check if the session has no cached state.
+    pub fn is_empty(&self) -> bool {
+        self.overlap_cache.records.is_empty() && self.overlap_cache.assets.is_empty()
+    }
+
+    pub(crate) fn overlap_cache_mut(&mut self) -> &mut OverlapCache {
+        &mut self.overlap_cache
+    }
+}
+
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+struct PluginKey {
+    name: String,
+    crc: u32,
+}
+
+impl PluginKey {
+    fn new<T: SortingPlugin>(plugin: &PluginSortingData<'_, T>) -> Option<Self> {
+        plugin.crc().map(|crc| Self {
+            name: plugin.name().to_owned(),
+            crc,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct PluginPairKey(PluginKey, PluginKey);
+
+impl PluginPairKey {
+    fn new<T: SortingPlugin>(
+        plugin: &PluginSortingData<'_, T>,
+        other_plugin: &PluginSortingData<'_, T>,
+    ) -> Option<Self> {
+        let key = PluginKey::new(plugin)?;
+        let other_key = PluginKey::new(other_plugin)?;
+
+        // Overlap is symmetric, so order the keys consistently to avoid
+        // storing the same result twice.
+        if key <= other_key {
+            Some(Self(key, other_key))
+        } else {
+            Some(Self(other_key, key))
+        }
+    }
+
+    fn involves_only(&self, keys: &HashSet<PluginKey>) -> bool {
+        keys.contains(&self.0) && keys.contains(&self.1)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct OverlapCache {
+    records: HashMap<PluginPairKey, bool>,
+    assets: HashMap<PluginPairKey, bool>,
+}
+
+impl OverlapCache {
+    pub(crate) fn records_overlap<T: SortingPlugin>(
+        &self,
+        plugin: &PluginSortingData<'_, T>,
+        other_plugin: &PluginSortingData<'_, T>,
+    ) -> Option<bool> {
+        let key = PluginPairKey::new(plugin, other_plugin)?;
+
+        self.records.get(&key).copied()
+    }
+
+    pub(crate) fn assets_overlap<T: SortingPlugin>(
+        &self,
+        plugin: &PluginSortingData<'_, T>,
+        other_plugin: &PluginSortingData<'_, T>,
+    ) -> Option<bool> {
+        let key = PluginPairKey::new(plugin, other_plugin)?;
+
+        self.assets.get(&key).copied()
+    }
+
+    /// Cache whether the given plugins' records overlap. Nothing is cached if
+    /// either plugin has no CRC.
+    pub(crate) fn insert_records_overlap<T: SortingPlugin>(
+        &mut self,
+        plugin: &PluginSortingData<'_, T>,
+        other_plugin: &PluginSortingData<'_, T>,
+        overlap: bool,
+    ) {
+        if let Some(key) = PluginPairKey::new(plugin, other_plugin) {
+            self.records.insert(key, overlap);
+        }
+    }
+
+    /// Cache whether the given plugins' assets overlap. Nothing is cached if
+    /// either plugin has no CRC.
+    pub(crate) fn insert_assets_overlap<T: SortingPlugin>(
+        &mut self,
+        plugin: &PluginSortingData<'_, T>,
+        other_plugin: &PluginSortingData<'_, T>,
+        overlap: bool,
+    ) {
+        if let Some(key) = PluginPairKey::new(plugin, other_plugin) {
+            self.assets.insert(key, overlap);
+        }
+    }
+
+    /// Discard cached results for pairs that involve any plugin that is not
+    /// one of the given plugins, so that the cache doesn't grow as plugins are
+    /// updated or removed.
+    pub(crate) fn retain_plugins<T: SortingPlugin>(
+        &mut self,
+        plugins: &[PluginSortingData<'_, T>],
+    ) {
+        let keys: HashSet<_> = plugins.iter().filter_map(PluginKey::new).collect();
+
+        self.records.retain(|k, _| k.involves_only(&keys));
+        self.assets.retain(|k, _| k.involves_only(&keys));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sorting::test::TestPlugin;
+
+    fn plugin(name: &str, crc: Option<u32>) -> TestPlugin {
+        let mut plugin = TestPlugin::new(name);
+        plugin.crc = crc;
+        plugin
+    }
+
+    fn sorting_data(plugin: &TestPlugin) -> PluginSortingData<'_, TestPlugin> {
+        PluginSortingData::new(plugin, None, None, 0).unwrap()
+    }
+
+    mod overlap_cache {
+        use super::*;
+
+        #[test]
+        fn records_overlap_should_return_the_inserted_result_for_either_plugin_order() {
+            let a = plugin("A.esp", Some(1));
+            let b = plugin("B.esp", Some(2));
+
+            let mut cache = OverlapCache::default();
+
+            assert_eq!(
+                None,
+                cache.records_overlap(&sorting_data(&a), &sorting_data(&b))
+            );
+
+            cache.insert_records_overlap(&sorting_data(&a), &sorting_data(&b), true);
+
+            assert_eq!(
+                Some(true),
+                cache.records_overlap(&sorting_data(&a), &sorting_data(&b))
+            );
+            assert_eq!(
+                Some(true),
+                cache.records_overlap(&sorting_data(&b), &sorting_data(&a))
+            );
+            assert_eq!(1, cache.records.len());
+        }
+
+        #[test]
+        fn insert_records_overlap_should_not_cache_the_result_if_a_plugin_has_no_crc() {
+            let a = plugin("A.esp", None);
+            let b = plugin("B.esp", Some(2));
+
+            let mut cache = OverlapCache::default();
+            cache.insert_records_overlap(&sorting_data(&a), &sorting_data(&b), true);
+
+            assert_eq!(
+                None,
+                cache.records_overlap(&sorting_data(&a), &sorting_data(&b))
+            );
+            assert!(cache.records.is_empty());
+        }
+
+        #[test]
+        fn records_overlap_should_not_return_a_cached_result_if_a_plugin_crc_has_changed() {
+            let a = plugin("A.esp", Some(1));
+            let b = plugin("B.esp", Some(2));
+
+            let mut cache = OverlapCache::default();
+            cache.insert_records_overlap(&sorting_data(&a), &sorting_data(&b), true);
+
+            let a = plugin("A.esp", Some(3));
+
+            assert_eq!(
+                None,
+                cache.records_overlap(&sorting_data(&a), &sorting_data(&b))
+            );
+        }
+
+        #[test]
+        fn assets_overlap_should_return_the_inserted_result_for_either_plugin_order() {
+            let a = plugin("A.esp", Some(1));
+            let b = plugin("B.esp", Some(2));
+
+            let mut cache = OverlapCache::default();
+            cache.insert_assets_overlap(&sorting_data(&a), &sorting_data(&b), true);
+
+            assert_eq!(
+                Some(true),
+                cache.assets_overlap(&sorting_data(&b), &sorting_data(&a))
+            );
+            assert_eq!(1, cache.assets.len());
+        }
+
+        #[test]
+        fn retain_plugins_should_remove_results_involving_other_plugins() {
+            let a = plugin("A.esp", Some(1));
+            let b = plugin("B.esp", Some(2));
+            let c = plugin("C.esp", Some(3));
+
+            let mut cache = OverlapCache::default();
+            cache.insert_records_overlap(&sorting_data(&a), &sorting_data(&b), false);
+            cache.insert_records_overlap(&sorting_data(&a), &sorting_data(&c), false);
+            cache.insert_assets_overlap(&sorting_data(&b), &sorting_data(&c), false);
+
+            cache.retain_plugins(&[sorting_data(&a), sorting_data(&c)]);
+
+            assert_eq!(1, cache.records.len());
+            assert!(cache.assets.is_empty());
+        }
+    }
+}
@@ -1,15 +1,20 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::Range, sync::Arc};
 
 use unicase::UniCase;
 
 use crate::{
     EdgeType, Vertex, logging,
-    sorting::error::{CyclicInteractionError, PluginGraphValidationError, UndefinedGroupError},
+    plugin::error::PluginDataError,
+    sorting::error::{
+        CyclicInteractionError, PinError, PluginGraphValidationError, UndefinedGroupError,
+    },
 };
 
 use super::{
     groups::GroupsGraph,
+    pins::{Pin, PinPosition},
     plugins::{PluginSortingData, SortingPlugin},
+    report::{IgnoredEdge, IgnoredEdgeReason},
 };
 
 pub fn validate_plugin_groups<T: SortingPlugin>(
@@ -122,7 +127,7 @@ fn validate_plugin<T: SortingPlugin>(
         plugin.name(),
         non_masters,
         blueprint_masters,
-        EdgeType::MasterlistRequirement,
+        &EdgeType::MasterlistRequirement,
     )?;
 
     validate_files(
@@ -130,7 +135,7 @@ fn validate_plugin<T: SortingPlugin>(
         plugin.name(),
         non_masters,
         blueprint_masters,
-        EdgeType::UserRequirement,
+        &EdgeType::UserRequirement,
     )?;
 
     validate_files(
@@ -138,7 +143,7 @@ fn validate_plugin<T: SortingPlugin>(
         plugin.name(),
         non_masters,
         blueprint_masters,
-        EdgeType::MasterlistLoadAfter,
+        &EdgeType::MasterlistLoadAfter,
     )?;
 
     validate_files(
@@ -146,9 +151,29 @@ fn validate_plugin<T: SortingPlugin>(
         plugin.name(),
         non_masters,
         blueprint_masters,
-        EdgeType::UserLoadAfter,
+        &EdgeType::UserLoadAfter,
     )?;
 
+    for (layer, file) in &plugin.layer_req {
+        validate_files(
+            std::slice::from_ref(file),
+            plugin.name(),
+            non_masters,
+            blueprint_masters,
+            &EdgeType::LayerRequirement(Arc::clone(layer)),
+        )?;
+    }
+
+    for (layer, file) in &plugin.layer_load_after {
+        validate_files(
+            std::slice::from_ref(file),
+            plugin.name(),
+            non_masters,
+            blueprint_masters,
+            &EdgeType::LayerLoadAfter(Arc::clone(layer)),
+        )?;
+    }
+
     Ok(())
 }
 
@@ -157,20 +182,20 @@ fn validate_files(
     plugin_name: &str,
     non_masters: &HashSet<UniCase<&str>>,
     blueprint_masters: &HashSet<UniCase<&str>>,
-    edge_type: EdgeType,
+    edge_type: &EdgeType,
 ) -> Result<(), CyclicInteractionError> {
     for file in files {
         let key = UniCase::new(file.as_str());
         if non_masters.contains(&key) {
             return Err(CyclicInteractionError::new(vec![
-                Vertex::new(file.clone()).with_out_edge_type(edge_type),
+                Vertex::new(file.clone()).with_out_edge_type(edge_type.clone()),
                 Vertex::new(plugin_name.to_owned()).with_out_edge_type(EdgeType::MasterFlag),
             ]));
         }
 
         if blueprint_masters.contains(&key) {
             return Err(CyclicInteractionError::new(vec![
-                Vertex::new(file.clone()).with_out_edge_type(edge_type),
+                Vertex::new(file.clone()).with_out_edge_type(edge_type.clone()),
                 Vertex::new(plugin_name.to_owned()).with_out_edge_type(EdgeType::BlueprintMaster),
             ]));
         }
@@ -199,3 +224,177 @@ fn validate_early_loading_plugins<T: SortingPlugin>(
 
     Ok(())
 }
+
+/// Checks that pinned indices are within the range of indices that the pinned
+/// plugins can occupy, and that relative pins don't cross between partitions.
+///
+/// The partitions must be given in the order that they appear in the sorted
+/// load order.
+pub fn validate_pins<T: SortingPlugin>(
+    pins: &[Pin],
+    partitions: [&[PluginSortingData<'_, T>]; 3],
+) -> Result<(), PinError> {
+    logging::trace!("Validating pins...");
+
+    for pin in pins {
+        let Some((partition_index, range)) = find_partition(partitions, pin.plugin()) else {
+            continue;
+        };
+
+        match pin.position() {
+            PinPosition::Index(index) => {
+                if !range.contains(index) {
+                    return Err(PinError::IndexOutOfRange {
+                        plugin: pin.plugin().to_owned(),
+                        index: *index,
+                    });
+                }
+            }
+            PinPosition::Before(other_plugin) | PinPosition::After(other_plugin) => {
+                if let Some((other_partition_index, _)) = find_partition(partitions, other_plugin)
+                    && other_partition_index != partition_index
+                {
+                    return Err(PinError::DifferentPartition {
+                        plugin: pin.plugin().to_owned(),
+                        other_plugin: other_plugin.clone(),
+                    });
+                }
+            }
+            PinPosition::First | PinPosition::Last => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the index of the partition that contains the given plugin, and the
+/// range of load order indices that the partition's plugins will occupy.
+fn find_partition<T: SortingPlugin>(
+    partitions: [&[PluginSortingData<'_, T>]; 3],
+    plugin_name: &str,
+) -> Option<(usize, Range<usize>)> {
+    let mut start = 0;
+    for (i, partition) in partitions.into_iter().enumerate() {
+        let end = start + partition.len();
+        if partition.iter().any(|p| unicase::eq(p.name(), plugin_name)) {
+            return Some((i, start..end));
+        }
+        start = end;
+    }
+
+    None
+}
+
+/// Finds the metadata rules that go between partitions and which therefore
+/// won't be used when sorting, but which don't contradict the partitions'
+/// order. Plugins' masters are only included if they are blueprint masters of
+/// plugins that are not, as other masters always load before the plugins that
+/// depend on them, so never need to be sorted.
+pub fn find_ignored_edges<T: SortingPlugin>(
+    masters: &[PluginSortingData<'_, T>],
+    non_masters: &[PluginSortingData<'_, T>],
+    blueprint_masters: &[PluginSortingData<'_, T>],
+) -> Result<Vec<IgnoredEdge>, PluginDataError> {
+    let masters_set: HashSet<UniCase<&str>> =
+        masters.iter().map(|p| UniCase::new(p.name())).collect();
+    let non_masters_set: HashSet<UniCase<&str>> =
+        non_masters.iter().map(|p| UniCase::new(p.name())).collect();
+    let blueprint_masters_set: HashSet<UniCase<&str>> = blueprint_masters
+        .iter()
+        .map(|p| UniCase::new(p.name()))
+        .collect();
+
+    let mut ignored_edges = Vec::new();
+
+    for plugin in masters {
+        push_ignored_edges(&mut ignored_edges, plugin, |file, edge_type| {
+            (*edge_type == EdgeType::Master && blueprint_masters_set.contains(&file))
+                .then_some(IgnoredEdgeReason::BlueprintMasterLoadsLater)
+        })?;
+    }
+
+    for plugin in non_masters {
+        push_ignored_edges(&mut ignored_edges, plugin, |file, edge_type| {
+            if *edge_type == EdgeType::Master {
+                blueprint_masters_set
+                    .contains(&file)
+                    .then_some(IgnoredEdgeReason::BlueprintMasterLoadsLater)
+            } else {
+                masters_set
+                    .contains(&file)
+                    .then_some(IgnoredEdgeReason::MasterFlag)
+            }
+        })?;
+    }
+
+    for plugin in blueprint_masters {
+        push_ignored_edges(&mut ignored_edges, plugin, |file, edge_type| {
+            (*edge_type != EdgeType::Master
+                && (masters_set.contains(&file) || non_masters_set.contains(&file)))
+            .then_some(IgnoredEdgeReason::BlueprintMaster)
+        })?;
+    }
+
+    Ok(ignored_edges)
+}
+
+fn push_ignored_edges<T: SortingPlugin>(
+    ignored_edges: &mut Vec<IgnoredEdge>,
+    plugin: &PluginSortingData<'_, T>,
+    reason: impl Fn(UniCase<&str>, &EdgeType) -> Option<IgnoredEdgeReason>,
+) -> Result<(), PluginDataError> {
+    let masters = plugin.masters()?;
+
+    let files = masters
+        .iter()
+        .map(|f| (f, EdgeType::Master))
+        .chain(
+            plugin
+                .masterlist_req
+                .iter()
+                .map(|f| (f, EdgeType::MasterlistRequirement)),
+        )
+        .chain(
+            plugin
+                .user_req
+                .iter()
+                .map(|f| (f, EdgeType::UserRequirement)),
+        )
+        .chain(
+            plugin
+                .masterlist_load_after
+                .iter()
+                .map(|f| (f, EdgeType::MasterlistLoadAfter)),
+        )
+        .chain(
+            plugin
+                .user_load_after
+                .iter()
+                .map(|f| (f, EdgeType::UserLoadAfter)),
+        )
+        .chain(
+            plugin
+                .layer_req
+                .iter()
+                .map(|(l, f)| (f, EdgeType::LayerRequirement(Arc::clone(l)))),
+        )
+        .chain(
+            plugin
+                .layer_load_after
+                .iter()
+                .map(|(l, f)| (f, EdgeType::LayerLoadAfter(Arc::clone(l)))),
+        );
+
+    for (file, edge_type) in files {
+        if let Some(reason) = reason(UniCase::new(file.as_str()), &edge_type) {
+            ignored_edges.push(IgnoredEdge::new(
+                plugin.name().to_owned(),
+                file.clone(),
+                edge_type,
+                reason,
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,29 @@
+use std::sync::Arc;
+
 /// An enum representing the different possible types of interactions between
 /// plugins or groups.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[non_exhaustive]
 pub enum EdgeType {
     Hardcoded,
     MasterFlag,
     Master,
     MasterlistRequirement,
+    /// A requirement from an additional metadata layer. The value is the
+    /// layer's name.
+    LayerRequirement(Arc<str>),
     UserRequirement,
     MasterlistLoadAfter,
+    /// A "load after" entry from an additional metadata layer. The value is
+    /// the layer's name.
+    LayerLoadAfter(Arc<str>),
     UserLoadAfter,
+    Pin,
     MasterlistGroup,
+    /// A group edge that involves metadata from an additional metadata layer
+    /// but no user metadata. The value is the name of the highest layer
+    /// involved.
+    LayerGroup(Arc<str>),
     UserGroup,
     RecordOverlap,
     AssetOverlap,
@@ -28,12 +41,16 @@ impl std::fmt::Display for EdgeType {
             EdgeType::UserRequirement => write!(f, "User Requirement"),
             EdgeType::MasterlistLoadAfter => write!(f, "Masterlist Load After"),
             EdgeType::UserLoadAfter => write!(f, "User Load After"),
+            EdgeType::Pin => write!(f, "Pin"),
             EdgeType::MasterlistGroup => write!(f, "Masterlist Group"),
             EdgeType::UserGroup => write!(f, "User Group"),
             EdgeType::RecordOverlap => write!(f, "Record Overlap"),
             EdgeType::AssetOverlap => write!(f, "Asset Overlap"),
             EdgeType::TieBreak => write!(f, "Tie Break"),
             EdgeType::BlueprintMaster => write!(f, "Blueprint Master"),
+            EdgeType::LayerRequirement(name) => write!(f, "Layer {name} Requirement"),
+            EdgeType::LayerLoadAfter(name) => write!(f, "Layer {name} Load After"),
+            EdgeType::LayerGroup(name) => write!(f, "Layer {name} Group"),
         }
     }
 }
@@ -70,6 +87,6 @@ impl Vertex {
 
     /// Get the type of the edge going from this vertex to the next in the path.
     pub fn out_edge_type(&self) -> Option<EdgeType> {
-        self.out_edge_type
+        self.out_edge_type.clone()
     }
 }
@@ -15,7 +15,7 @@ const BLANK_DIFFERENT_MASTER_DEPENDENT_ESM: &str = "Blank - Different Master Dep
 pub const BLANK_ESP: &str = "Blank.esp";
 pub const BLANK_DIFFERENT_ESP: &str = "Blank - Different.esp";
 pub const BLANK_MASTER_DEPENDENT_ESP: &str = "Blank - Master Dependent.esp";
-const BLANK_DIFFERENT_MASTER_DEPENDENT_ESP: &str = "Blank - Different Master Dependent.esp";
+pub const BLANK_DIFFERENT_MASTER_DEPENDENT_ESP: &str = "Blank - Different Master Dependent.esp";
 const BLANK_PLUGIN_DEPENDENT_ESP: &str = "Blank - Plugin Dependent.esp";
 const BLANK_DIFFERENT_PLUGIN_DEPENDENT_ESP: &str = "Blank - Different Plugin Dependent.esp";
 